@@ -0,0 +1,7 @@
+use rust_chess::protocols::uci::Uci;
+use std::io;
+
+fn main() {
+  let mut uci = Uci::new(io::stdout());
+  uci.run(io::stdin().lock());
+}
@@ -0,0 +1,268 @@
+use crate::bitbase::{Bitbase, Bitbases, Endgame, POSITIONS, Setup};
+
+const KING_STEPS: [(i32, i32); 8] = [
+  (-1, -1),
+  (-1, 0),
+  (-1, 1),
+  (0, -1),
+  (0, 1),
+  (1, -1),
+  (1, 0),
+  (1, 1),
+];
+const ROOK_STEPS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+// KPK needs the other two for the positions after a promotion
+pub fn generate() -> Bitbases {
+  let kqk = solve(Endgame::Kqk, &[]);
+  let krk = solve(Endgame::Krk, &[]);
+  let kpk = solve(Endgame::Kpk, &[&kqk, &krk]);
+  Bitbases { kpk, krk, kqk }
+}
+
+// Retrograde analysis: starting from the mates, and for KPK from the won
+// promotions, results are walked backwards one move at a time. A position
+// with the strong side to move is won as soon as one move wins; with the
+// weak side to move, once every move it has is lost, which a count of the
+// moves left tracks. Whatever is never reached is a draw.
+fn solve(endgame: Endgame, promotions: &[&Bitbase]) -> Bitbase {
+  let mut wins = Bitbase::new();
+  let mut moves_left = vec![0u8; POSITIONS / 2];
+  let mut queue = Vec::new();
+
+  for index in 0..POSITIONS {
+    let setup = Setup::from_index(index);
+    if !is_valid(endgame, &setup) {
+      continue;
+    }
+
+    if !setup.strong_to_move {
+      let moves = weak_king_moves(endgame, &setup).len();
+      moves_left[setup.index()] = moves as u8;
+      if moves == 0 && is_weak_king_attacked(endgame, &setup, setup.weak_king) {
+        wins.set_win(index);
+        queue.push(index);
+      }
+    } else if endgame == Endgame::Kpk && wins_by_promotion(&setup, promotions) {
+      wins.set_win(index);
+      queue.push(index);
+    }
+  }
+
+  while let Some(index) = queue.pop() {
+    let setup = Setup::from_index(index);
+
+    if setup.strong_to_move {
+      // The weak king moved into this lost position
+      for weak_king in unmoves(setup.weak_king, &KING_STEPS, false, &setup) {
+        let previous = Setup {
+          strong_to_move: false,
+          weak_king,
+          ..setup
+        };
+        let previous_index = previous.index();
+        if !is_valid(endgame, &previous) || wins.is_win(previous_index) {
+          continue;
+        }
+        moves_left[previous_index] -= 1;
+        if moves_left[previous_index] == 0 {
+          wins.set_win(previous_index);
+          queue.push(previous_index);
+        }
+      }
+    } else {
+      // A strong piece moved into this won position, or into its mirror
+      for setup in [setup, setup.mirrored()] {
+        for previous in strong_unmoves(endgame, &setup) {
+          let previous_index = previous.index();
+          if is_valid(endgame, &previous) && !wins.is_win(previous_index) {
+            wins.set_win(previous_index);
+            queue.push(previous_index);
+          }
+        }
+      }
+    }
+  }
+
+  wins
+}
+
+// A pawn about to promote wins when the queen or the rook it becomes does
+fn wins_by_promotion(setup: &Setup, promotions: &[&Bitbase]) -> bool {
+  let target = setup.piece + 8;
+  if setup.piece / 8 != 6
+    || target == setup.strong_king
+    || target == setup.weak_king
+  {
+    return false;
+  }
+
+  let promoted = Setup {
+    strong_to_move: false,
+    piece: target,
+    ..*setup
+  };
+  promotions.iter().any(|bitbase| bitbase.wins(&promoted))
+}
+
+// Kings apart and not on the same square as the piece, pawns off the first
+// and last rows, and the side that just moved not in check
+fn is_valid(endgame: Endgame, setup: &Setup) -> bool {
+  let Setup {
+    strong_king,
+    weak_king,
+    piece,
+    ..
+  } = *setup;
+  if strong_king == weak_king
+    || piece == strong_king
+    || piece == weak_king
+    || distance(strong_king, weak_king) <= 1
+  {
+    return false;
+  }
+  if endgame == Endgame::Kpk && !(8..56).contains(&piece) {
+    return false;
+  }
+  !(setup.strong_to_move && is_weak_king_attacked(endgame, setup, weak_king))
+}
+
+// Whether the weak king would be in check on `square`. The weak king itself
+// doesn't block the piece, since it is the one moving.
+fn is_weak_king_attacked(
+  endgame: Endgame,
+  setup: &Setup,
+  square: usize,
+) -> bool {
+  if distance(setup.strong_king, square) <= 1 {
+    return true;
+  }
+
+  let piece = setup.piece;
+  match endgame {
+    Endgame::Kpk => {
+      square / 8 == piece / 8 + 1 && (square % 8).abs_diff(piece % 8) == 1
+    }
+    Endgame::Krk => slides_to(piece, square, setup.strong_king, false),
+    Endgame::Kqk => slides_to(piece, square, setup.strong_king, true),
+  }
+}
+
+// Whether a rook, or a queen with `diagonals`, on `from` reaches `to` with
+// only `blocker` possibly in the way
+fn slides_to(from: usize, to: usize, blocker: usize, diagonals: bool) -> bool {
+  if from == to {
+    return false;
+  }
+  let rows = (to / 8) as i32 - (from / 8) as i32;
+  let files = (to % 8) as i32 - (from % 8) as i32;
+  let straight = rows == 0 || files == 0;
+  let diagonal = rows.abs() == files.abs();
+  if !(straight || diagonals && diagonal) {
+    return false;
+  }
+
+  let step = (rows.signum(), files.signum());
+  let mut square = from;
+  loop {
+    square = shift(square, step).unwrap();
+    if square == to {
+      return true;
+    }
+    if square == blocker {
+      return false;
+    }
+  }
+}
+
+// Legal squares of the weak king, taking the piece when it isn't defended
+fn weak_king_moves(endgame: Endgame, setup: &Setup) -> Vec<usize> {
+  KING_STEPS
+    .iter()
+    .filter_map(|&step| shift(setup.weak_king, step))
+    .filter(|&square| {
+      if square == setup.piece {
+        distance(square, setup.strong_king) > 1
+      } else {
+        !is_weak_king_attacked(endgame, setup, square)
+      }
+    })
+    .collect()
+}
+
+// Positions with the strong side to move where one of its moves, other
+// than a capture or promotion, leads to `setup`
+fn strong_unmoves(endgame: Endgame, setup: &Setup) -> Vec<Setup> {
+  let mut previous = Vec::new();
+  for strong_king in unmoves(setup.strong_king, &KING_STEPS, false, setup) {
+    previous.push(Setup {
+      strong_to_move: true,
+      strong_king,
+      ..*setup
+    });
+  }
+
+  let pieces = match endgame {
+    Endgame::Kpk => pawn_unmoves(setup),
+    Endgame::Krk => unmoves(setup.piece, &ROOK_STEPS, true, setup),
+    Endgame::Kqk => unmoves(setup.piece, &KING_STEPS, true, setup),
+  };
+  for piece in pieces {
+    previous.push(Setup {
+      strong_to_move: true,
+      piece,
+      ..*setup
+    });
+  }
+  previous
+}
+
+// Empty squares a king, or a slider with `sliding`, could have come from
+fn unmoves(
+  from: usize,
+  steps: &[(i32, i32)],
+  sliding: bool,
+  setup: &Setup,
+) -> Vec<usize> {
+  let occupied = [setup.strong_king, setup.weak_king, setup.piece];
+  let mut squares = Vec::new();
+  for &step in steps {
+    let mut square = from;
+    while let Some(next) = shift(square, step) {
+      if occupied.contains(&next) {
+        break;
+      }
+      squares.push(next);
+      if !sliding {
+        break;
+      }
+      square = next;
+    }
+  }
+  squares
+}
+
+// A pawn comes from one row below, or two from its starting row
+fn pawn_unmoves(setup: &Setup) -> Vec<usize> {
+  let occupied = [setup.strong_king, setup.weak_king];
+  let mut squares = Vec::new();
+  let one_back = setup.piece - 8;
+  if one_back >= 8 && !occupied.contains(&one_back) {
+    squares.push(one_back);
+    if setup.piece / 8 == 3 && !occupied.contains(&(one_back - 8)) {
+      squares.push(one_back - 8);
+    }
+  }
+  squares
+}
+
+fn shift(square: usize, (rows, files): (i32, i32)) -> Option<usize> {
+  let row = (square / 8) as i32 + rows;
+  let file = (square % 8) as i32 + files;
+  ((0..8).contains(&row) && (0..8).contains(&file))
+    .then_some((row * 8 + file) as usize)
+}
+
+fn distance(a: usize, b: usize) -> usize {
+  (a / 8).abs_diff(b / 8).max((a % 8).abs_diff(b % 8))
+}
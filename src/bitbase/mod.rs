@@ -0,0 +1,259 @@
+pub mod generator;
+
+use crate::chessboard::Chessboard;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::tablebase::Wdl;
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"RCBB";
+const VERSION: u8 = 1;
+
+// Positions of one endgame: the side to move, the strong king on the a to
+// d files (the others are mirrored), the weak king and the extra piece
+pub const POSITIONS: usize = 2 * 32 * 64 * 64;
+
+const BYTES_PER_BITBASE: usize = POSITIONS / 8;
+
+// Endgames of two kings and one more piece, in the order they are stored
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Endgame {
+  Kpk,
+  Krk,
+  Kqk,
+}
+
+impl Endgame {
+  pub const ALL: [Endgame; 3] = [Endgame::Kpk, Endgame::Krk, Endgame::Kqk];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Endgame::Kpk => "KPK",
+      Endgame::Krk => "KRK",
+      Endgame::Kqk => "KQK",
+    }
+  }
+
+  fn of_piece(piece: &Piece) -> Option<Self> {
+    match piece {
+      Piece::Pawn(_) => Some(Endgame::Kpk),
+      Piece::Rook(_) => Some(Endgame::Krk),
+      Piece::Queen(_) => Some(Endgame::Kqk),
+      _ => None,
+    }
+  }
+}
+
+// Squares are numbered from a1 to h8 by rows, with the strong side playing
+// up the board like white does
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Setup {
+  pub strong_to_move: bool,
+  pub strong_king: usize,
+  pub weak_king: usize,
+  pub piece: usize,
+}
+
+impl Setup {
+  // Mirrored files keep the result, so only one half of the strong king's
+  // squares is stored
+  pub fn index(&self) -> usize {
+    let mirror = if self.strong_king % 8 > 3 { 7 } else { 0 };
+    let strong_king = self.strong_king ^ mirror;
+    let strong_king = strong_king / 8 * 4 + strong_king % 8;
+
+    ((usize::from(self.strong_to_move) * 32 + strong_king) * 64
+      + (self.weak_king ^ mirror))
+      * 64
+      + (self.piece ^ mirror)
+  }
+
+  pub fn from_index(index: usize) -> Self {
+    let strong_king = index / (64 * 64) % 32;
+    Setup {
+      strong_to_move: index >= POSITIONS / 2,
+      strong_king: strong_king / 4 * 8 + strong_king % 4,
+      weak_king: index / 64 % 64,
+      piece: index % 64,
+    }
+  }
+
+  pub fn mirrored(&self) -> Self {
+    Setup {
+      strong_king: self.strong_king ^ 7,
+      weak_king: self.weak_king ^ 7,
+      piece: self.piece ^ 7,
+      ..*self
+    }
+  }
+}
+
+// One bit per position, set when the strong side wins. A lone king can
+// never win, so the other positions are draws.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bitbase {
+  bits: Vec<u64>,
+}
+
+impl Bitbase {
+  fn new() -> Self {
+    Bitbase {
+      bits: vec![0; POSITIONS / 64],
+    }
+  }
+
+  pub fn wins(&self, setup: &Setup) -> bool {
+    self.is_win(setup.index())
+  }
+
+  fn set_win(&mut self, index: usize) {
+    self.bits[index / 64] |= 1 << (index % 64);
+  }
+
+  fn is_win(&self, index: usize) -> bool {
+    self.bits[index / 64] >> (index % 64) & 1 != 0
+  }
+
+  // Number of positions the strong side wins
+  pub fn count_wins(&self) -> usize {
+    self
+      .bits
+      .iter()
+      .map(|bits| bits.count_ones() as usize)
+      .sum()
+  }
+}
+
+// Win/draw/loss results of the KPK, KRK and KQK endgames, generated by
+// `generator::generate` and stored in a small file of their own
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bitbases {
+  kpk: Bitbase,
+  krk: Bitbase,
+  kqk: Bitbase,
+}
+
+impl Bitbases {
+  pub fn generate() -> Self {
+    generator::generate()
+  }
+
+  pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)
+      .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Self::from_bytes(&bytes)
+  }
+
+  // Magic, version, then the bits of each endgame in little-endian words
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+    let Some(data) = bytes.strip_prefix(MAGIC) else {
+      return Err("Invalid bitbases: wrong magic".to_string());
+    };
+    let Some((&version, data)) = data.split_first() else {
+      return Err("Invalid bitbases: missing version".to_string());
+    };
+    if version != VERSION {
+      return Err(format!("Unsupported bitbases version: {}", version));
+    }
+    let expected = Endgame::ALL.len() * BYTES_PER_BITBASE;
+    if data.len() != expected {
+      return Err(format!(
+        "Invalid bitbases: {} bytes of data instead of {}",
+        data.len(),
+        expected
+      ));
+    }
+
+    let mut bitbases = data.chunks_exact(BYTES_PER_BITBASE).map(|chunk| {
+      let bits = chunk
+        .chunks_exact(8)
+        .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+        .collect();
+      Bitbase { bits }
+    });
+    Ok(Bitbases {
+      kpk: bitbases.next().unwrap(),
+      krk: bitbases.next().unwrap(),
+      kqk: bitbases.next().unwrap(),
+    })
+  }
+
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    for endgame in Endgame::ALL {
+      for bits in &self.get(endgame).bits {
+        bytes.extend_from_slice(&bits.to_le_bytes());
+      }
+    }
+    bytes
+  }
+
+  pub fn write(&self, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    fs::write(path, self.to_bytes())
+      .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+  }
+
+  pub fn get(&self, endgame: Endgame) -> &Bitbase {
+    match endgame {
+      Endgame::Kpk => &self.kpk,
+      Endgame::Krk => &self.krk,
+      Endgame::Kqk => &self.kqk,
+    }
+  }
+
+  // Result for `color` to move, or None when the position isn't one of the
+  // endgames covered
+  pub fn probe(&self, chessboard: &Chessboard, color: Color) -> Option<Wdl> {
+    let mut kings = [None; 2];
+    let mut extra = None;
+    for (x, row) in chessboard.board().iter().enumerate() {
+      for (y, square) in row.iter().enumerate() {
+        let Some(piece) = square else {
+          continue;
+        };
+        let square = x * BOARD_SIZE + y;
+        match piece {
+          Piece::King(_) => {
+            kings[usize::from(*piece.color() == Color::Black)] = Some(square)
+          }
+          _ if extra.is_some() => return None,
+          _ => extra = Some((piece, square)),
+        }
+      }
+    }
+
+    let (piece, square) = extra?;
+    let endgame = Endgame::of_piece(piece)?;
+    let strong = *piece.color();
+    let [Some(white_king), Some(black_king)] = kings else {
+      return None;
+    };
+
+    // Black's pieces play up the board once the rows are flipped
+    let (flip, strong_king, weak_king) = match strong {
+      Color::White => (0, white_king, black_king),
+      Color::Black => (56, black_king, white_king),
+    };
+    let setup = Setup {
+      strong_to_move: color == strong,
+      strong_king: strong_king ^ flip,
+      weak_king: weak_king ^ flip,
+      piece: square ^ flip,
+    };
+    // Pawns left on the last row aren't in the bitbase
+    if endgame == Endgame::Kpk && !(8..56).contains(&setup.piece) {
+      return None;
+    }
+
+    Some(match self.get(endgame).wins(&setup) {
+      false => Wdl::Draw,
+      true if setup.strong_to_move => Wdl::Win,
+      true => Wdl::Loss,
+    })
+  }
+}
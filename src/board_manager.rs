@@ -7,6 +7,10 @@ use crate::pieces::types::move_direction::{
 use crate::pieces::types::position::Position;
 use std::collections::HashMap;
 
+type SpecialMoveValidationFn =
+  dyn Fn(&BoardManager, Position, Position) -> bool;
+
+#[derive(Clone)]
 pub struct BoardManager {
   chessboard: Chessboard,
 }
@@ -70,14 +74,13 @@ impl BoardManager {
 
     if let Some(special_move_action) =
       self.extract_special_move(special_move_attempt)?
-    {
-      if !self.validate_special_move(
+      && !self.validate_special_move(
         special_move_action,
         piece_position,
         target_position,
-      ) {
-        return Err("Invalid special move".to_string());
-      }
+      )
+    {
+      return Err("Invalid special move".to_string());
     }
 
     Ok(())
@@ -170,13 +173,12 @@ impl BoardManager {
       }
       let piece = self.chessboard.get_piece(position).unwrap();
 
-      if piece.is_of_color(current_player_color) {
-        if self
+      if piece.is_of_color(current_player_color)
+        && self
           .can_apply_move(position, king_position, current_player_color)
           .is_ok()
-        {
-          return true;
-        }
+      {
+        return true;
       }
     }
     false
@@ -206,10 +208,10 @@ impl BoardManager {
     position: Position,
     player_color: Color,
   ) -> bool {
-    if let Some(piece) = self.chessboard.get_piece(position) {
-      if piece.is_of_color(player_color) {
-        return true;
-      }
+    if let Some(piece) = self.chessboard.get_piece(position)
+      && piece.is_of_color(player_color)
+    {
+      return true;
     }
     false
   }
@@ -217,7 +219,7 @@ impl BoardManager {
   fn get_special_move_validation_action(
     &self,
     special_move_validation: SpecialMoveValidationAction,
-  ) -> Box<dyn Fn(&BoardManager, Position, Position) -> bool> {
+  ) -> Box<SpecialMoveValidationFn> {
     let mut special_move_validation_functions = HashMap::new();
     special_move_validation_functions.insert(
       SpecialMoveValidationAction::EnemyPieceExists,
@@ -228,16 +230,15 @@ impl BoardManager {
           return false;
         }
         if let Some(piece) = board_manager.chessboard.get_piece(target_position)
-        {
-          if piece.is_of_color(
+          && piece.is_of_color(
             *board_manager
               .chessboard()
               .get_piece(piece_position)
               .unwrap()
               .color(),
-          ) {
-            return false;
-          }
+          )
+        {
+          return false;
         }
         true
       },
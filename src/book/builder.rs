@@ -0,0 +1,166 @@
+use crate::book::ENTRY_SIZE;
+use crate::book::polyglot::encode_move;
+use crate::notation::pgn::{PgnGame, PgnResult, parse_pgn};
+use crate::pieces::types::color::Color;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Debug)]
+pub struct BookBuilderOptions {
+  // Moves after this many plies of a game are left out
+  pub max_ply: usize,
+  // Moves played in fewer games than this are left out
+  pub min_games: u32,
+  // Games ending otherwise are skipped. Games without a known result
+  // always are, since they can't weigh moves.
+  pub results: Vec<PgnResult>,
+}
+
+impl Default for BookBuilderOptions {
+  fn default() -> Self {
+    BookBuilderOptions {
+      max_ply: 20,
+      min_games: 1,
+      results: vec![
+        PgnResult::WhiteWins,
+        PgnResult::BlackWins,
+        PgnResult::Draw,
+      ],
+    }
+  }
+}
+
+#[derive(Clone, Copy, Default)]
+struct MoveStats {
+  games: u32,
+  wins: u32,
+  draws: u32,
+}
+
+impl MoveStats {
+  // Polyglot's usual weighting: two points for a win, one for a draw
+  fn score(&self) -> u64 {
+    2 * self.wins as u64 + self.draws as u64
+  }
+}
+
+// Collects the moves played in PGN games into a Polyglot book
+pub struct BookBuilder {
+  options: BookBuilderOptions,
+  // Position key to the stats of each encoded move played from there
+  positions: HashMap<u64, HashMap<u16, MoveStats>>,
+  games: usize,
+}
+
+impl BookBuilder {
+  pub fn new(options: BookBuilderOptions) -> Self {
+    BookBuilder {
+      options,
+      positions: HashMap::new(),
+      games: 0,
+    }
+  }
+
+  // Number of games taken into the book so far
+  pub fn games(&self) -> usize {
+    self.games
+  }
+
+  // Number of positions seen so far, before filtering
+  pub fn positions(&self) -> usize {
+    self.positions.len()
+  }
+
+  // Returns whether the game passed the filters
+  pub fn add_game(&mut self, pgn_game: &PgnGame) -> Result<bool, String> {
+    if pgn_game.result == PgnResult::Unknown
+      || !self.options.results.contains(&pgn_game.result)
+    {
+      return Ok(false);
+    }
+
+    let max_ply = self.options.max_ply;
+    let mut ply = 0;
+    let mut played = Vec::new();
+    pgn_game.replay(|game, chess_move| {
+      if ply >= max_ply {
+        return false;
+      }
+      ply += 1;
+      played.push((
+        game.polyglot_key(),
+        encode_move(chess_move),
+        game.player_color(),
+      ));
+      true
+    })?;
+
+    for (key, chess_move, color) in played {
+      let stats = self
+        .positions
+        .entry(key)
+        .or_default()
+        .entry(chess_move)
+        .or_default();
+      stats.games += 1;
+      match (pgn_game.result, color) {
+        (PgnResult::Draw, _) => stats.draws += 1,
+        (PgnResult::WhiteWins, Color::White)
+        | (PgnResult::BlackWins, Color::Black) => stats.wins += 1,
+        _ => (),
+      }
+    }
+
+    self.games += 1;
+    Ok(true)
+  }
+
+  // Adds every game of a PGN file and returns how many passed the filters
+  pub fn add_pgn(&mut self, text: &str) -> Result<usize, String> {
+    let mut added = 0;
+    for pgn_game in parse_pgn(text)? {
+      if self.add_game(&pgn_game)? {
+        added += 1;
+      }
+    }
+    Ok(added)
+  }
+
+  // Book entries sorted by key, best move first within a position. Weights
+  // are scaled down when the largest one doesn't fit in 16 bits.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut entries: Vec<(u64, u16, u64)> = self
+      .positions
+      .iter()
+      .flat_map(|(key, moves)| {
+        moves.iter().filter_map(|(chess_move, stats)| {
+          (stats.games >= self.options.min_games && stats.score() > 0)
+            .then_some((*key, *chess_move, stats.score()))
+        })
+      })
+      .collect();
+    entries.sort_by_key(|(key, chess_move, score)| {
+      (*key, std::cmp::Reverse(*score), *chess_move)
+    });
+
+    let max_score = entries.iter().map(|(_, _, score)| *score).max();
+    let max_score = max_score.unwrap_or(0).max(u16::MAX as u64);
+
+    let mut bytes = Vec::with_capacity(entries.len() * ENTRY_SIZE);
+    for (key, chess_move, score) in entries {
+      let weight = (score * u16::MAX as u64 / max_score).max(1) as u16;
+      bytes.extend_from_slice(&key.to_be_bytes());
+      bytes.extend_from_slice(&chess_move.to_be_bytes());
+      bytes.extend_from_slice(&weight.to_be_bytes());
+      bytes.extend_from_slice(&0u32.to_be_bytes());
+    }
+    bytes
+  }
+
+  pub fn write(&self, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    fs::write(path, self.to_bytes())
+      .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+  }
+}
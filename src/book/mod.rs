@@ -0,0 +1,154 @@
+pub mod builder;
+pub mod polyglot;
+pub mod random64;
+
+use crate::book::polyglot::decode_move;
+use crate::chess_move::Move;
+use crate::game::Game;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Every entry of a Polyglot book takes 16 bytes
+const ENTRY_SIZE: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum BookChoice {
+  // Each move is played with a probability following its weight
+  #[default]
+  WeightedRandom,
+  // Always the move with the highest weight
+  Best,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BookMove {
+  pub chess_move: Move,
+  pub weight: u16,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+  key: u64,
+  chess_move: u16,
+  weight: u16,
+}
+
+// Opening book in the Polyglot `.bin` format: entries of big-endian key,
+// move, weight and learning data, sorted by key
+#[derive(Clone)]
+pub struct OpeningBook {
+  entries: Vec<Entry>,
+  random_state: u64,
+}
+
+impl OpeningBook {
+  pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)
+      .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Self::from_bytes(&bytes)
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+    if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+      return Err(format!(
+        "Invalid book: size {} is not a multiple of {} bytes",
+        bytes.len(),
+        ENTRY_SIZE
+      ));
+    }
+
+    let mut entries: Vec<Entry> = bytes
+      .chunks_exact(ENTRY_SIZE)
+      .map(|chunk| Entry {
+        key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+        chess_move: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+        weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+      })
+      .collect();
+    // Lookups rely on the order, which badly written books may not keep
+    entries.sort_by_key(|entry| entry.key);
+
+    let seed = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+
+    Ok(OpeningBook {
+      entries,
+      random_state: seed | 1,
+    })
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  // Makes weighted random choices reproducible
+  pub fn set_seed(&mut self, seed: u64) {
+    self.random_state = seed | 1;
+  }
+
+  // Book moves that are legal in the game, highest weight first. Moves the
+  // rules here don't allow, like castling, are left out.
+  pub fn moves(&self, game: &Game) -> Vec<BookMove> {
+    let key = game.polyglot_key();
+    let start = self.entries.partition_point(|entry| entry.key < key);
+    let legal_moves = game.legal_moves();
+
+    let mut moves: Vec<BookMove> = self.entries[start..]
+      .iter()
+      .take_while(|entry| entry.key == key)
+      .filter_map(|entry| {
+        let chess_move = decode_move(entry.chess_move, game.player_color())?;
+        legal_moves.contains(&chess_move).then_some(BookMove {
+          chess_move,
+          weight: entry.weight,
+        })
+      })
+      .collect();
+
+    moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+    moves
+  }
+
+  // Moves with no weight are never chosen
+  pub fn choose(&mut self, game: &Game, choice: BookChoice) -> Option<Move> {
+    let moves: Vec<BookMove> = self
+      .moves(game)
+      .into_iter()
+      .filter(|book_move| book_move.weight > 0)
+      .collect();
+
+    match choice {
+      BookChoice::Best => moves.first().map(|book_move| book_move.chess_move),
+      BookChoice::WeightedRandom => {
+        let total: u64 = moves.iter().map(|m| m.weight as u64).sum();
+        if total == 0 {
+          return None;
+        }
+
+        let mut pick = self.next_random() % total;
+        moves.into_iter().find_map(|book_move| {
+          if pick < book_move.weight as u64 {
+            return Some(book_move.chess_move);
+          }
+          pick -= book_move.weight as u64;
+          None
+        })
+      }
+    }
+  }
+
+  // xorshift64*
+  fn next_random(&mut self) -> u64 {
+    self.random_state ^= self.random_state >> 12;
+    self.random_state ^= self.random_state << 25;
+    self.random_state ^= self.random_state >> 27;
+    self.random_state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+  }
+}
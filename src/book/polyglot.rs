@@ -0,0 +1,137 @@
+use crate::book::random64::RANDOM64;
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::game::Game;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+// Promotion pieces as numbered in Polyglot moves, starting from 1
+const PROMOTION_KINDS: [char; 4] = ['n', 'b', 'r', 'q'];
+
+impl Chessboard {
+  // Key of the position in Polyglot books. Castling isn't part of this
+  // game, but books come from regular chess where the rights are part of
+  // the key, so they are assumed while the king and rook are on their
+  // starting squares.
+  pub fn polyglot_key(&self, player_color: Color) -> u64 {
+    let mut key = 0;
+
+    for position in self.get_all_positions() {
+      if let Some(piece) = self.get_piece(position) {
+        key ^= RANDOM64[64 * piece_kind(piece) + square_index(position)];
+      }
+    }
+
+    let rights = [
+      (Color::White, 0, BOARD_SIZE - 1),
+      (Color::White, 0, 0),
+      (Color::Black, BOARD_SIZE - 1, BOARD_SIZE - 1),
+      (Color::Black, BOARD_SIZE - 1, 0),
+    ];
+    for (index, (color, row, rook_file)) in rights.into_iter().enumerate() {
+      if self.has_piece(row, 4, 'k', color)
+        && self.has_piece(row, rook_file, 'r', color)
+      {
+        key ^= RANDOM64[CASTLING_OFFSET + index];
+      }
+    }
+
+    if player_color == Color::White {
+      key ^= RANDOM64[TURN_OFFSET];
+    }
+    key
+  }
+
+  fn has_piece(&self, x: usize, y: usize, kind: char, color: Color) -> bool {
+    let position = Position::new(x, y).unwrap();
+    self.get_piece(position).is_some_and(|piece| {
+      piece.is_of_color(color) && piece.symbol().to_ascii_lowercase() == kind
+    })
+  }
+}
+
+impl Game {
+  // Polyglot key of the current position. Like in regular chess, a pawn
+  // that just stepped two squares next to an enemy pawn adds its file.
+  pub fn polyglot_key(&self) -> u64 {
+    let chessboard = self.chessboard();
+    let mut key = chessboard.polyglot_key(self.player_color());
+
+    if let Some(last_move) = self.moves().last()
+      && let Some(Piece::Pawn(_)) = chessboard.get_piece(last_move.to())
+      && last_move.from().x().abs_diff(last_move.to().x()) == 2
+    {
+      let to = last_move.to();
+      let has_capturing_pawn = [to.y().wrapping_sub(1), to.y() + 1]
+        .into_iter()
+        .filter_map(|y| Position::new(to.x(), y).ok())
+        .any(|position| match chessboard.get_piece(position) {
+          Some(piece @ Piece::Pawn(_)) => {
+            piece.is_of_color(self.player_color())
+          }
+          _ => false,
+        });
+
+      if has_capturing_pawn {
+        key ^= RANDOM64[EN_PASSANT_OFFSET + to.y()];
+      }
+    }
+
+    key
+  }
+}
+
+// Moves are packed as to file, to row, from file and from row in 3 bits
+// each, then the promotion piece
+pub fn encode_move(chess_move: &Move) -> u16 {
+  let square = |position: Position| (position.x() << 3 | position.y()) as u16;
+  let promotion = chess_move.promotion().map_or(0, |piece| {
+    let symbol = piece.symbol().to_ascii_lowercase();
+    PROMOTION_KINDS
+      .iter()
+      .position(|kind| *kind == symbol)
+      .map_or(0, |index| index as u16 + 1)
+  });
+
+  square(chess_move.to()) | square(chess_move.from()) << 6 | promotion << 12
+}
+
+pub fn decode_move(bits: u16, color: Color) -> Option<Move> {
+  let square = |bits: u16| {
+    Position::new((bits >> 3 & 7) as usize, (bits & 7) as usize).unwrap()
+  };
+  let to = square(bits);
+  let from = square(bits >> 6);
+
+  match (bits >> 12 & 7) as usize {
+    0 => Some(Move::new(from, to)),
+    kind => {
+      let symbol = PROMOTION_KINDS.get(kind - 1)?;
+      let piece = Piece::from_kind_symbol(*symbol, color)?;
+      Some(Move::with_promotion(from, to, piece))
+    }
+  }
+}
+
+// Black pawn, white pawn, black knight, ... up to the white king
+fn piece_kind(piece: &Piece) -> usize {
+  let kind = match piece {
+    Piece::Pawn(_) => 0,
+    Piece::Knight(_) => 1,
+    Piece::Bishop(_) => 2,
+    Piece::Rook(_) => 3,
+    Piece::Queen(_) => 4,
+    Piece::King(_) => 5,
+  };
+  2 * kind + usize::from(piece.is_of_color(Color::White))
+}
+
+fn square_index(position: Position) -> usize {
+  position.x() * BOARD_SIZE + position.y()
+}
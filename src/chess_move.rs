@@ -0,0 +1,73 @@
+use crate::pieces::piece::Piece;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Move {
+  from: Position,
+  to: Position,
+  // Dead piece brought back when a pawn reaches the last row
+  promotion: Option<Piece>,
+}
+
+impl Move {
+  pub fn new(from: Position, to: Position) -> Self {
+    Move {
+      from,
+      to,
+      promotion: None,
+    }
+  }
+
+  pub fn with_promotion(
+    from: Position,
+    to: Position,
+    promotion: Piece,
+  ) -> Self {
+    Move {
+      from,
+      to,
+      promotion: Some(promotion),
+    }
+  }
+
+  pub fn from(&self) -> Position {
+    self.from
+  }
+
+  pub fn to(&self) -> Position {
+    self.to
+  }
+
+  pub fn promotion(&self) -> Option<&Piece> {
+    self.promotion.as_ref()
+  }
+
+  // Long algebraic notation as used by UCI (e.g. "e2e4", "e7e8q")
+  pub fn to_long_algebraic(&self) -> String {
+    let mut text =
+      format!("{}{}", self.from.to_algebraic(), self.to.to_algebraic());
+    if let Some(piece) = self.promotion {
+      text.push(piece.symbol().to_ascii_lowercase());
+    }
+    text
+  }
+
+  pub fn from_long_algebraic(text: &str, color: Color) -> Result<Self, String> {
+    if text.len() != 4 && text.len() != 5 || !text.is_ascii() {
+      return Err(format!("Invalid move: {}", text));
+    }
+
+    let from = Position::from_algebraic(&text[0..2])?;
+    let to = Position::from_algebraic(&text[2..4])?;
+
+    match text[4..].chars().next() {
+      Some(symbol) => {
+        let piece = Piece::from_kind_symbol(symbol, color)
+          .ok_or(format!("Invalid promotion piece: {}", symbol))?;
+        Ok(Move::with_promotion(from, to, piece))
+      }
+      None => Ok(Move::new(from, to)),
+    }
+  }
+}
@@ -1,3 +1,4 @@
+use crate::chess_move::Move;
 use crate::pieces::piece::Piece;
 use crate::pieces::types::BOARD_SIZE;
 use crate::pieces::types::color::Color;
@@ -20,6 +21,7 @@ const WHITE_PAWNS_ROW_X_POS: usize = 1;
 const FIRST_BLACK_ROW_X_POS: usize = 7;
 const BLACK_PAWNS_ROW_X_POS: usize = 6;
 
+#[derive(Clone)]
 pub struct Chessboard {
   chessboard: ChessboardType,
   white_dead_pieces: Vec<Piece>,
@@ -117,6 +119,13 @@ impl Chessboard {
     &self.black_dead_pieces
   }
 
+  pub fn dead_pieces(&self, color: Color) -> &Vec<Piece> {
+    match color {
+      Color::White => &self.white_dead_pieces,
+      Color::Black => &self.black_dead_pieces,
+    }
+  }
+
   pub fn capture_piece(&mut self, target_position: Position) {
     if let Some(target_piece) = self.take_piece(target_position) {
       if *target_piece.color() == Color::White {
@@ -178,13 +187,35 @@ impl Chessboard {
     Ok(())
   }
 
+  // Moves a piece and, for promotions, brings back the requested dead piece
+  pub fn apply_move(
+    &mut self,
+    chess_move: &Move,
+  ) -> Result<MoveResult, String> {
+    let color = *self
+      .get_piece(chess_move.from())
+      .ok_or("No piece at the given position")?
+      .color();
+
+    let res = self.move_piece(chess_move.from(), chess_move.to())?;
+
+    if let Some(promotion) = chess_move.promotion() {
+      let index = self
+        .dead_pieces(color)
+        .iter()
+        .position(|piece| piece == promotion)
+        .ok_or("Promotion piece is not available")?;
+      self.upgrade_piece(index, color, chess_move.to())?;
+      return Ok(MoveResult::None);
+    }
+
+    Ok(res)
+  }
+
   pub fn get_king_position(&self, color: Color) -> Option<Position> {
     for position in self.get_all_positions() {
       if let Some(piece) = self.get_piece(position) {
-        let is_king = match piece {
-          Piece::King(_) => true,
-          _ => false,
-        };
+        let is_king = matches!(piece, Piece::King(_));
         if is_king && piece.color() == &color {
           return Some(position);
         }
@@ -193,6 +224,32 @@ impl Chessboard {
     None
   }
 
+  // False when `color` is down to its king and pawns
+  pub fn has_pieces_besides_pawns(&self, color: Color) -> bool {
+    self.get_all_positions().into_iter().any(|position| {
+      match self.get_piece(position) {
+        Some(Piece::Pawn(_)) | Some(Piece::King(_)) | None => false,
+        Some(piece) => piece.is_of_color(color),
+      }
+    })
+  }
+
+  // False when `color` can't mate on its own: a bare king, or a king with
+  // one knight or bishop
+  pub fn has_mating_material(&self, color: Color) -> bool {
+    let pieces: Vec<&Piece> = self
+      .get_all_positions()
+      .into_iter()
+      .filter_map(|position| self.get_piece(position))
+      .filter(|piece| piece.is_of_color(color))
+      .filter(|piece| !matches!(piece, Piece::King(_)))
+      .collect();
+    !matches!(
+      pieces.as_slice(),
+      [] | [Piece::Knight(_)] | [Piece::Bishop(_)]
+    )
+  }
+
   pub fn get_all_positions(&self) -> Vec<Position> {
     let mut positions = Vec::new();
     for x in 0..BOARD_SIZE {
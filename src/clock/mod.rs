@@ -0,0 +1,203 @@
+pub mod time_control;
+
+use crate::clock::time_control::{Increment, TimeControl};
+use crate::pieces::types::color::Color;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Where the clock reads the time from, so that tests can move it by hand
+pub trait TimeSource: Send + Sync {
+  // Time since some fixed point, never going backwards
+  fn now(&self) -> Duration;
+}
+
+pub struct SystemTime {
+  start: Instant,
+}
+
+impl Default for SystemTime {
+  fn default() -> Self {
+    SystemTime {
+      start: Instant::now(),
+    }
+  }
+}
+
+impl TimeSource for SystemTime {
+  fn now(&self) -> Duration {
+    self.start.elapsed()
+  }
+}
+
+// Only moves when told to. Clones share the same time.
+#[derive(Clone, Default)]
+pub struct ManualTime {
+  now: Arc<Mutex<Duration>>,
+}
+
+impl ManualTime {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn advance(&self, duration: Duration) {
+    *self.now.lock().unwrap() += duration;
+  }
+}
+
+impl TimeSource for ManualTime {
+  fn now(&self) -> Duration {
+    *self.now.lock().unwrap()
+  }
+}
+
+// A chess clock: the time of the player to move runs until they press it,
+// which gives them their increment and starts the other player's time. A
+// player whose time reaches zero has lost on time, or flagged.
+#[derive(Clone)]
+pub struct Clock {
+  time_control: TimeControl,
+  time_source: Arc<dyn TimeSource>,
+  // Indexed by color, White first
+  remaining: [Duration; 2],
+  moves: [u32; 2],
+  // The player whose time is running, and when their move started
+  running: Option<(Color, Duration)>,
+}
+
+impl Clock {
+  pub fn new(time_control: TimeControl) -> Self {
+    Self::with_time_source(time_control, Arc::new(SystemTime::default()))
+  }
+
+  pub fn with_time_source(
+    time_control: TimeControl,
+    time_source: Arc<dyn TimeSource>,
+  ) -> Self {
+    let time = time_control.stages()[0].time;
+    Clock {
+      time_control,
+      time_source,
+      remaining: [time; 2],
+      moves: [0; 2],
+      running: None,
+    }
+  }
+
+  // A stopped clock carrying on with `remaining` times after `moves` moves,
+  // White's first, like one read back from a saved game
+  pub fn resume(
+    time_control: TimeControl,
+    remaining: [Duration; 2],
+    moves: [u32; 2],
+  ) -> Self {
+    Clock {
+      remaining,
+      moves,
+      ..Self::new(time_control)
+    }
+  }
+
+  // Starts the time of `color`, charging whoever was running for the time
+  // they used but without giving them an increment
+  pub fn start(&mut self, color: Color) {
+    self.stop();
+    self.running = Some((color, self.time_source.now()));
+  }
+
+  pub fn stop(&mut self) {
+    if let Some((color, _)) = self.running {
+      self.remaining[index(color)] = self.remaining(color);
+      self.running = None;
+    }
+  }
+
+  // Ends the move of the running player, who gets their increment and any
+  // time of the stage they reach, and starts the other player's time
+  pub fn press(&mut self) -> Result<(), String> {
+    let Some((color, since)) = self.running else {
+      return Err("The clock is not running".to_string());
+    };
+    if self.remaining(color).is_zero() {
+      return Err(format!("{} has run out of time", color));
+    }
+
+    let now = self.time_source.now();
+    let spent = now - since;
+    let player = index(color);
+    let (stage, _) = self.time_control.stage(self.moves[player]);
+    let bonus = match stage.increment {
+      Increment::Fischer(increment) => increment,
+      Increment::Bronstein(increment) => spent.min(increment),
+      Increment::None | Increment::Delay(_) => Duration::ZERO,
+    };
+    self.remaining[player] = self.remaining(color) + bonus;
+
+    self.moves[player] += 1;
+    if let (stage, true) = self.time_control.stage(self.moves[player]) {
+      self.remaining[player] += stage.time;
+    }
+    self.running = Some((color.next(), now));
+    Ok(())
+  }
+
+  // Time left to `color`, as it stands now if their time is running
+  pub fn remaining(&self, color: Color) -> Duration {
+    let remaining = self.remaining[index(color)];
+    let Some((running, since)) = self.running else {
+      return remaining;
+    };
+    if running != color {
+      return remaining;
+    }
+
+    let spent = self.time_source.now() - since;
+    let (stage, _) = self.time_control.stage(self.moves[index(color)]);
+    let charged = match stage.increment {
+      Increment::Delay(delay) => spent.saturating_sub(delay),
+      _ => spent,
+    };
+    remaining.saturating_sub(charged)
+  }
+
+  // The player who ran out of time, if any
+  pub fn flagged(&self) -> Option<Color> {
+    [Color::White, Color::Black]
+      .into_iter()
+      .find(|&color| self.remaining(color).is_zero())
+  }
+
+  pub fn running(&self) -> Option<Color> {
+    self.running.map(|(color, _)| color)
+  }
+
+  // Moves `color` made since the clock was set
+  pub fn moves(&self, color: Color) -> u32 {
+    self.moves[index(color)]
+  }
+
+  pub fn time_control(&self) -> &TimeControl {
+    &self.time_control
+  }
+}
+
+fn index(color: Color) -> usize {
+  match color {
+    Color::White => 0,
+    Color::Black => 1,
+  }
+}
+
+// Hours only when there are some, and tenths of a second once under ten
+// seconds, when they start to matter: "1:30:00", "4:59" or "0:09.4"
+pub fn format_duration(duration: Duration) -> String {
+  let seconds = duration.as_secs();
+  let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+  if hours > 0 {
+    format!("{}:{:02}:{:02}", hours, minutes, seconds % 60)
+  } else if seconds >= 10 {
+    format!("{}:{:02}", minutes, seconds % 60)
+  } else {
+    format!("0:{:02}.{}", seconds, duration.subsec_millis() / 100)
+  }
+}
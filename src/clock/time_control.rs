@@ -0,0 +1,195 @@
+use std::fmt;
+use std::time::Duration;
+
+// Time given back to a player for each move they make
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Increment {
+  None,
+  // Added after every move, however long it took
+  Fischer(Duration),
+  // Gives back the time the move took, up to the amount
+  Bronstein(Duration),
+  // US delay: the time only starts running once the delay is over
+  Delay(Duration),
+}
+
+// A number of moves to play in some time, or the rest of the game when
+// `moves` is None
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stage {
+  pub moves: Option<u32>,
+  pub time: Duration,
+  pub increment: Increment,
+}
+
+// The stages of a game, each adding its time to what is left of the
+// previous one. When every stage has a number of moves the last one
+// repeats, like 40 moves in 2 hours again and again.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TimeControl {
+  stages: Vec<Stage>,
+}
+
+impl TimeControl {
+  pub fn new(stages: Vec<Stage>) -> Result<Self, String> {
+    let Some((_, first_stages)) = stages.split_last() else {
+      return Err("A time control needs at least one stage".to_string());
+    };
+    if first_stages.iter().any(|stage| stage.moves.is_none()) {
+      return Err(
+        "Only the last stage can be for the rest of the game".to_string(),
+      );
+    }
+    if stages.iter().any(|stage| stage.moves == Some(0)) {
+      return Err("A stage needs at least one move".to_string());
+    }
+    Ok(TimeControl { stages })
+  }
+
+  pub fn sudden_death(time: Duration) -> Self {
+    Self::with_increment(time, Increment::None)
+  }
+
+  pub fn with_increment(time: Duration, increment: Increment) -> Self {
+    TimeControl {
+      stages: vec![Stage {
+        moves: None,
+        time,
+        increment,
+      }],
+    }
+  }
+
+  // Stages separated by ':', each `[moves/]minutes[+seconds]`, with a 'b'
+  // after the seconds for a Bronstein delay or a 'd' for a US delay, and
+  // otherwise a Fischer increment: "5", "3+2", "5+3d" or "40/90+30:30+30"
+  pub fn parse(text: &str) -> Result<Self, String> {
+    Self::parse_in(text, 60.0)
+  }
+
+  // The PGN TimeControl tag, which is written like `parse` takes it but
+  // with the time of each stage in seconds: "180+2" or "40/5400+30:1800+30"
+  pub fn from_pgn_tag(text: &str) -> Result<Self, String> {
+    Self::parse_in(text, 1.0)
+  }
+
+  pub fn to_pgn_tag(&self) -> String {
+    let mut tag = String::new();
+    self.write_stages(&mut tag, 1.0).unwrap();
+    tag
+  }
+
+  fn parse_in(text: &str, unit_seconds: f64) -> Result<Self, String> {
+    let stages = text
+      .split(':')
+      .map(|stage| parse_stage(stage, unit_seconds))
+      .collect::<Result<Vec<_>, _>>()
+      .map_err(|e| format!("Invalid time control {}: {}", text, e))?;
+    Self::new(stages)
+  }
+
+  fn write_stages(
+    &self,
+    f: &mut impl fmt::Write,
+    unit_seconds: f64,
+  ) -> fmt::Result {
+    for (index, stage) in self.stages.iter().enumerate() {
+      if index > 0 {
+        write!(f, ":")?;
+      }
+      if let Some(moves) = stage.moves {
+        write!(f, "{}/", moves)?;
+      }
+      write!(f, "{}", stage.time.as_secs_f64() / unit_seconds)?;
+      match stage.increment {
+        Increment::None => (),
+        Increment::Fischer(time) => write!(f, "+{}", time.as_secs_f64())?,
+        Increment::Bronstein(time) => write!(f, "+{}b", time.as_secs_f64())?,
+        Increment::Delay(time) => write!(f, "+{}d", time.as_secs_f64())?,
+      }
+    }
+    Ok(())
+  }
+
+  pub fn stages(&self) -> &[Stage] {
+    &self.stages
+  }
+
+  // The stage a player is in after making `moves` moves, and whether it
+  // starts with the next move
+  pub fn stage(&self, moves: u32) -> (&Stage, bool) {
+    let mut start = 0;
+    for stage in &self.stages {
+      match stage.moves {
+        Some(stage_moves) if moves >= start + stage_moves => {
+          start += stage_moves
+        }
+        _ => return (stage, moves == start),
+      }
+    }
+
+    let last = self.stages.last().unwrap();
+    let repeated = moves - start;
+    (last, repeated.is_multiple_of(last.moves.unwrap()))
+  }
+}
+
+impl fmt::Display for TimeControl {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.write_stages(f, 60.0)
+  }
+}
+
+fn parse_stage(text: &str, unit_seconds: f64) -> Result<Stage, String> {
+  let (moves, rest) = match text.split_once('/') {
+    Some((moves, rest)) => {
+      let moves = moves
+        .parse::<u32>()
+        .map_err(|_| format!("invalid move count {}", moves))?;
+      (Some(moves), rest)
+    }
+    None => (None, text),
+  };
+  let (time, increment) = match rest.split_once('+') {
+    Some((time, increment)) => (time, Some(increment)),
+    None => (rest, None),
+  };
+
+  let unit = if unit_seconds == 1.0 {
+    "seconds"
+  } else {
+    "minutes"
+  };
+  let time = parse_duration(time, unit_seconds)
+    .ok_or(format!("invalid {} {}", unit, time))?;
+  if time.is_zero() {
+    return Err("a stage needs some time".to_string());
+  }
+  let increment = match increment {
+    None => Increment::None,
+    Some(increment) => {
+      let (seconds, kind) = match increment.strip_suffix(['b', 'd']) {
+        Some(seconds) => (seconds, increment.chars().last()),
+        None => (increment, None),
+      };
+      let time = parse_duration(seconds, 1.0)
+        .ok_or(format!("invalid increment {}", increment))?;
+      match kind {
+        Some('b') => Increment::Bronstein(time),
+        Some('d') => Increment::Delay(time),
+        _ => Increment::Fischer(time),
+      }
+    }
+  };
+
+  Ok(Stage {
+    moves,
+    time,
+    increment,
+  })
+}
+
+fn parse_duration(text: &str, unit_seconds: f64) -> Option<Duration> {
+  let value = text.parse::<f64>().ok()?;
+  Duration::try_from_secs_f64(value * unit_seconds).ok()
+}
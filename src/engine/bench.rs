@@ -0,0 +1,46 @@
+use crate::engine::Engine;
+use crate::engine::search::{SearchLimits, SearchOptions};
+use crate::game::Game;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_BENCH_DEPTH: u32 = 4;
+
+// Fixed set of positions searched by the benchmark: the opening, a few
+// middlegames with tactics around and a simple ending
+pub const BENCH_POSITIONS: [&str; 5] = [
+  "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+  "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b - - 0 1",
+  "r2q1rk1/ppp2ppp/2np1n2/2b1p1B1/2B1P1b1/2NP1N2/PPP2PPP/R2Q1RK1 w - - 0 1",
+  "2r3k1/pp3ppp/2n1b3/3p4/3P4/2N1BN2/PP3PPP/2R3K1 b - - 0 1",
+  "8/5pk1/6p1/8/3P4/5KP1/8/8 w - - 0 1",
+];
+
+pub struct BenchResult {
+  pub nodes: u64,
+  pub elapsed: Duration,
+}
+
+// Searches every bench position to a fixed depth with a fresh engine, so
+// that the node count only depends on the search itself
+pub fn bench(depth: u32, search_options: SearchOptions) -> BenchResult {
+  let start = Instant::now();
+  let mut nodes = 0;
+
+  for fen in BENCH_POSITIONS {
+    let game = Game::from_fen(fen).unwrap();
+    let mut engine = Engine::new();
+    engine.set_search_options(search_options);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let result =
+      engine.search(&game, SearchLimits::depth(depth), stop, &mut |_| {});
+    nodes += result.nodes;
+  }
+
+  BenchResult {
+    nodes,
+    elapsed: start.elapsed(),
+  }
+}
@@ -0,0 +1,176 @@
+use crate::bitbase::Bitbases;
+use crate::chessboard::Chessboard;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use crate::tablebase::Wdl;
+
+// Score of endgames the bitbases know are won, above anything the material
+// could add up to, but below tablebase wins and mates
+pub const KNOWN_WIN: i32 = 10_000;
+
+type PieceSquareTable = [[i32; BOARD_SIZE]; BOARD_SIZE];
+
+// Bonuses from White's point of view: row 0 is White's back row
+const PAWN_TABLE: PieceSquareTable = [
+  [0, 0, 0, 0, 0, 0, 0, 0],
+  [5, 10, 10, -20, -20, 10, 10, 5],
+  [5, -5, -10, 0, 0, -10, -5, 5],
+  [0, 0, 0, 20, 20, 0, 0, 0],
+  [5, 5, 10, 25, 25, 10, 5, 5],
+  [10, 10, 20, 30, 30, 20, 10, 10],
+  [50, 50, 50, 50, 50, 50, 50, 50],
+  [0, 0, 0, 0, 0, 0, 0, 0],
+];
+
+const KNIGHT_TABLE: PieceSquareTable = [
+  [-50, -40, -30, -30, -30, -30, -40, -50],
+  [-40, -20, 0, 5, 5, 0, -20, -40],
+  [-30, 5, 10, 15, 15, 10, 5, -30],
+  [-30, 0, 15, 20, 20, 15, 0, -30],
+  [-30, 5, 15, 20, 20, 15, 5, -30],
+  [-30, 0, 10, 15, 15, 10, 0, -30],
+  [-40, -20, 0, 0, 0, 0, -20, -40],
+  [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+const BISHOP_TABLE: PieceSquareTable = [
+  [-20, -10, -10, -10, -10, -10, -10, -20],
+  [-10, 5, 0, 0, 0, 0, 5, -10],
+  [-10, 10, 10, 10, 10, 10, 10, -10],
+  [-10, 0, 10, 10, 10, 10, 0, -10],
+  [-10, 5, 5, 10, 10, 5, 5, -10],
+  [-10, 0, 5, 10, 10, 5, 0, -10],
+  [-10, 0, 0, 0, 0, 0, 0, -10],
+  [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+const ROOK_TABLE: PieceSquareTable = [
+  [0, 0, 0, 5, 5, 0, 0, 0],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [5, 10, 10, 10, 10, 10, 10, 5],
+  [0, 0, 0, 0, 0, 0, 0, 0],
+];
+
+const QUEEN_TABLE: PieceSquareTable = [
+  [-20, -10, -10, -5, -5, -10, -10, -20],
+  [-10, 0, 5, 0, 0, 0, 0, -10],
+  [-10, 5, 5, 5, 5, 5, 0, -10],
+  [0, 0, 5, 5, 5, 5, 0, -5],
+  [-5, 0, 5, 5, 5, 5, 0, -5],
+  [-10, 0, 5, 5, 5, 5, 0, -10],
+  [-10, 0, 0, 0, 0, 0, 0, -10],
+  [-20, -10, -10, -5, -5, -10, -10, -20],
+];
+
+const KING_TABLE: PieceSquareTable = [
+  [20, 30, 10, 0, 0, 10, 30, 20],
+  [20, 20, 0, 0, 0, 0, 20, 20],
+  [-10, -20, -20, -20, -20, -20, -20, -10],
+  [-20, -30, -30, -40, -40, -30, -30, -20],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+];
+
+#[derive(Default, Clone, Copy)]
+pub struct Evaluator<'a> {
+  bitbases: Option<&'a Bitbases>,
+}
+
+impl<'a> Evaluator<'a> {
+  pub fn with_bitbases(bitbases: Option<&'a Bitbases>) -> Self {
+    Evaluator { bitbases }
+  }
+
+  // Static score of the board in centipawns, from the point of view of the
+  // given color
+  pub fn evaluate(&self, chessboard: &Chessboard, color: Color) -> i32 {
+    if let Some(bitbases) = self.bitbases
+      && let Some(wdl) = bitbases.probe(chessboard, color)
+    {
+      return match wdl {
+        Wdl::Draw => 0,
+        Wdl::Win => self.known_win(chessboard, color),
+        _ => -self.known_win(chessboard, color.next()),
+      };
+    }
+
+    let mut score = 0;
+
+    for position in chessboard.get_all_positions() {
+      if let Some(piece) = chessboard.get_piece(position) {
+        let piece_score = piece.value() + self.square_bonus(piece, position);
+        if piece.is_of_color(color) {
+          score += piece_score;
+        } else {
+          score -= piece_score;
+        }
+      }
+    }
+
+    score
+  }
+
+  // Lone king against `winner`: the search is steered toward the mate, or
+  // the promotion, by pushing the lone king to the edge, bringing the kings
+  // together and advancing pawns
+  fn known_win(&self, chessboard: &Chessboard, winner: Color) -> i32 {
+    let mut score = KNOWN_WIN;
+    let mut kings = [Position::new(0, 0).unwrap(); 2];
+
+    for position in chessboard.get_all_positions() {
+      let Some(piece) = chessboard.get_piece(position) else {
+        continue;
+      };
+      let is_winner = piece.is_of_color(winner);
+      match piece {
+        Piece::King(_) => kings[usize::from(is_winner)] = position,
+        Piece::Pawn(_) => {
+          let advance = match winner {
+            Color::White => position.x(),
+            Color::Black => BOARD_SIZE - 1 - position.x(),
+          };
+          score += piece.value() + 20 * advance as i32;
+        }
+        _ => score += piece.value(),
+      }
+    }
+
+    let [lone_king, king] = kings;
+    let center_distance = |index: usize| {
+      let index = index as i32;
+      (3 - index).max(index - 4)
+    };
+    score +=
+      10 * (center_distance(lone_king.x()) + center_distance(lone_king.y()));
+    let kings_distance =
+      king.x().abs_diff(lone_king.x()) + king.y().abs_diff(lone_king.y());
+    score + 4 * (14 - kings_distance as i32)
+  }
+
+  fn square_bonus(&self, piece: &Piece, position: Position) -> i32 {
+    let table = match piece {
+      Piece::Pawn(_) => &PAWN_TABLE,
+      Piece::Knight(_) => &KNIGHT_TABLE,
+      Piece::Bishop(_) => &BISHOP_TABLE,
+      Piece::Rook(_) => &ROOK_TABLE,
+      Piece::Queen(_) => &QUEEN_TABLE,
+      Piece::King(_) => &KING_TABLE,
+    };
+
+    // Black's tables are White's mirrored vertically
+    let x = match piece.color() {
+      Color::White => position.x(),
+      Color::Black => BOARD_SIZE - 1 - position.x(),
+    };
+
+    table[x][position.y()]
+  }
+}
@@ -0,0 +1,193 @@
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::engine::evaluation::Evaluator;
+use crate::engine::search::MATE_SCORE;
+use crate::game::Game;
+use crate::move_generator::MoveGenerator;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+
+// A move suggested to the player to move, written in SAN, with a short
+// explanation of what it does
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint {
+  pub chess_move: Move,
+  pub san: String,
+  pub reason: String,
+}
+
+impl Hint {
+  pub fn new(game: &Game, chess_move: Move) -> Self {
+    Hint {
+      chess_move,
+      san: game.to_san(&chess_move),
+      reason: explain(game.chessboard(), &chess_move),
+    }
+  }
+}
+
+// Cheap suggestion without searching: the move leaving the best static
+// evaluation, once the moved piece may have been taken back
+pub fn quick_move(game: &Game) -> Option<Move> {
+  let chessboard = game.chessboard();
+  let color = game.player_color();
+  game
+    .legal_moves()
+    .into_iter()
+    .max_by_key(|chess_move| quick_score(chessboard, color, chess_move))
+}
+
+fn quick_score(
+  chessboard: &Chessboard,
+  color: Color,
+  chess_move: &Move,
+) -> i32 {
+  let mut child = chessboard.clone();
+  child.apply_move(chess_move).unwrap();
+
+  let opponent = color.next();
+  let move_generator = MoveGenerator::new(&child);
+  if !move_generator.has_legal_moves(opponent) {
+    return match move_generator.is_king_in_check(opponent) {
+      true => MATE_SCORE,
+      false => 0,
+    };
+  }
+
+  let target = chess_move.to();
+  let best_recapture = move_generator
+    .attackers(target, opponent)
+    .into_iter()
+    .map(|attacker| child.see(&Move::new(attacker, target)))
+    .max()
+    .unwrap_or(0);
+  Evaluator::default().evaluate(&child, color) - best_recapture.max(0)
+}
+
+// What the move does, most telling first: mates, captures, upgrades,
+// checks, then the pieces it attacks or saves
+pub fn explain(chessboard: &Chessboard, chess_move: &Move) -> String {
+  let Some(piece) = chessboard.get_piece(chess_move.from()) else {
+    return String::new();
+  };
+  let color = *piece.color();
+  let opponent = color.next();
+  let mut child = chessboard.clone();
+  if child.apply_move(chess_move).is_err() {
+    return String::new();
+  }
+
+  let move_generator = MoveGenerator::new(&child);
+  let check = move_generator.is_king_in_check(opponent);
+  if !move_generator.has_legal_moves(opponent) {
+    return match check {
+      true => "checkmates".to_string(),
+      false => "stalemates, which draws".to_string(),
+    };
+  }
+
+  let mut reasons = Vec::new();
+  if let Some(captured) = chessboard.get_piece(chess_move.to()) {
+    reasons.push(format!(
+      "captures the {} on {}",
+      piece_name(captured),
+      chess_move.to().to_algebraic()
+    ));
+  }
+  if let Some(promotion) = chess_move.promotion() {
+    reasons.push(format!("upgrades the pawn to a {}", piece_name(promotion)));
+  }
+  if check {
+    reasons.push("gives check".to_string());
+  }
+
+  let threatened = threats(&child, chess_move.to());
+  match threatened.as_slice() {
+    [] => (),
+    [(piece, position)] => reasons.push(format!(
+      "threatens the {} on {}",
+      piece_name(piece),
+      position.to_algebraic()
+    )),
+    _ => {
+      let targets: Vec<String> = threatened
+        .iter()
+        .map(|(piece, position)| {
+          format!("the {} on {}", piece_name(piece), position.to_algebraic())
+        })
+        .collect();
+      reasons.push(format!("forks {}", targets.join(" and ")));
+    }
+  }
+
+  let was_attacked = is_in_danger(chessboard, chess_move.from());
+  if reasons.is_empty() && was_attacked {
+    reasons.push(format!("moves the attacked {} away", piece_name(piece)));
+  }
+  if reasons.is_empty() {
+    reasons.push(match piece {
+      Piece::Pawn(_) => "advances the pawn".to_string(),
+      _ => format!("improves the {}'s position", piece_name(piece)),
+    });
+  }
+  if is_in_danger(&child, chess_move.to()) {
+    reasons.push(format!("but the {} can be taken", piece_name(piece)));
+  }
+
+  let last = reasons.pop().unwrap();
+  match reasons.is_empty() {
+    true => last,
+    false if last.starts_with("but") => {
+      format!("{} {}", reasons.join(", "), last)
+    }
+    false => format!("{} and {}", reasons.join(", "), last),
+  }
+}
+
+// Enemy pieces the piece on `from` attacks that are worth more than it or
+// aren't defended, kings left out since checks are told apart
+fn threats(chessboard: &Chessboard, from: Position) -> Vec<(Piece, Position)> {
+  let Some(piece) = chessboard.get_piece(from) else {
+    return Vec::new();
+  };
+  let color = *piece.color();
+  let move_generator = MoveGenerator::new(chessboard);
+
+  chessboard
+    .get_all_positions()
+    .into_iter()
+    .filter_map(|position| {
+      let target = chessboard.get_piece(position)?;
+      let attacked = !target.is_of_color(color)
+        && !matches!(target, Piece::King(_))
+        && move_generator.attackers(position, color).contains(&from);
+      let defended = move_generator.is_square_attacked(position, color.next());
+      (attacked && (target.value() > piece.value() || !defended))
+        .then_some((*target, position))
+    })
+    .collect()
+}
+
+// Whether the piece on `position` can be taken without losing material
+fn is_in_danger(chessboard: &Chessboard, position: Position) -> bool {
+  let Some(piece) = chessboard.get_piece(position) else {
+    return false;
+  };
+  let opponent = piece.color().next();
+  MoveGenerator::new(chessboard)
+    .attackers(position, opponent)
+    .into_iter()
+    .any(|attacker| chessboard.see(&Move::new(attacker, position)) > 0)
+}
+
+fn piece_name(piece: &Piece) -> &'static str {
+  match piece {
+    Piece::Pawn(_) => "pawn",
+    Piece::Knight(_) => "knight",
+    Piece::Bishop(_) => "bishop",
+    Piece::Rook(_) => "rook",
+    Piece::Queen(_) => "queen",
+    Piece::King(_) => "king",
+  }
+}
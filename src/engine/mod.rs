@@ -0,0 +1,327 @@
+pub mod bench;
+pub mod evaluation;
+pub mod hint;
+pub mod move_ordering;
+pub mod search;
+pub mod see;
+pub mod time_manager;
+pub mod transposition_table;
+pub mod zobrist;
+
+use crate::bitbase::Bitbases;
+use crate::book::{BookChoice, OpeningBook};
+use crate::chess_move::Move;
+use crate::engine::search::{
+  Score, Search, SearchInfo, SearchLimits, SearchOptions, SearchResult,
+  TABLEBASE_WIN,
+};
+use crate::engine::transposition_table::{
+  DEFAULT_SIZE_MB, MAX_SIZE_MB, TranspositionTable,
+};
+use crate::game::Game;
+use crate::tablebase::Tablebase;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+pub const MAX_THREADS: usize = 256;
+pub const MAX_MULTI_PV: usize = 64;
+
+pub struct Engine {
+  transposition_table: TranspositionTable,
+  search_options: SearchOptions,
+  threads: usize,
+  // Number of best lines the search reports
+  multi_pv: usize,
+  book: Option<OpeningBook>,
+  // The book can be switched off without unloading it
+  own_book: bool,
+  book_choice: BookChoice,
+  tablebase: Option<Tablebase>,
+  // Endgames the evaluator knows the result of
+  bitbases: Option<Bitbases>,
+}
+
+impl Default for Engine {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Engine {
+  pub fn new() -> Self {
+    Engine {
+      transposition_table: TranspositionTable::new(DEFAULT_SIZE_MB),
+      search_options: SearchOptions::default(),
+      threads: 1,
+      multi_pv: 1,
+      book: None,
+      own_book: true,
+      book_choice: BookChoice::default(),
+      tablebase: None,
+      bitbases: None,
+    }
+  }
+
+  // With more than one thread this is a Lazy SMP search: helper threads
+  // search the same position and only share what they find through the
+  // transposition table, while the main thread's result is the one
+  // reported. A single thread keeps the search deterministic, which is also
+  // what node limited searches use so that the limit is exact.
+  pub fn search(
+    &mut self,
+    game: &Game,
+    limits: SearchLimits,
+    stop: Arc<AtomicBool>,
+    on_info: &mut dyn FnMut(&SearchInfo),
+  ) -> SearchResult {
+    // Analysis always searches, even in book positions
+    if !limits.infinite
+      && let Some(book_move) = self.book_move(game)
+    {
+      return SearchResult {
+        best_move: Some(book_move),
+        score: Score::Centipawns(0),
+        depth: 0,
+        nodes: 0,
+        pv: vec![book_move],
+      };
+    }
+    // Same for endgames in the tablebases, which are played perfectly
+    if !limits.infinite
+      && let Some(result) = self.tablebase_move(game)
+    {
+      return result;
+    }
+
+    self.transposition_table.new_search();
+
+    let chessboard = game.chessboard();
+    let color = game.player_color();
+    let transposition_table = &self.transposition_table;
+    let tablebase = self.tablebase.as_ref();
+    let bitbases = self.bitbases.as_ref();
+    let search_options = self.search_options;
+    let helpers_stop = Arc::new(AtomicBool::new(false));
+    let threads = if limits.nodes.is_some() {
+      1
+    } else {
+      self.threads
+    };
+
+    thread::scope(|scope| {
+      let helpers: Vec<_> = (1..threads)
+        .map(|index| {
+          let helpers_stop = Arc::clone(&helpers_stop);
+          // Helpers keep searching until the main thread is done
+          let limits = SearchLimits {
+            infinite: true,
+            ..Default::default()
+          };
+
+          scope.spawn(move || {
+            Search::new(
+              limits,
+              search_options,
+              helpers_stop,
+              transposition_table,
+            )
+            .with_start_depth(1 + index as u32 % 2)
+            .with_tablebase(tablebase)
+            .with_bitbases(bitbases)
+            .run(chessboard, color, &mut |_| {})
+            .nodes
+          })
+        })
+        .collect();
+
+      let mut result =
+        Search::new(limits, search_options, stop, transposition_table)
+          .with_multi_pv(self.multi_pv)
+          .with_tablebase(tablebase)
+          .with_bitbases(bitbases)
+          .run(chessboard, color, on_info);
+
+      helpers_stop.store(true, Ordering::Relaxed);
+      for helper in helpers {
+        result.nodes += helper.join().unwrap();
+      }
+      result
+    })
+  }
+
+  // Forgets anything learned from the previous game
+  pub fn new_game(&mut self) {
+    self.transposition_table.clear();
+  }
+
+  pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+    match name.to_lowercase().as_str() {
+      "hash" => {
+        let size_mb = value
+          .parse::<usize>()
+          .ok()
+          .filter(|size_mb| (1..=MAX_SIZE_MB).contains(size_mb))
+          .ok_or(format!("Invalid Hash size: {}", value))?;
+        self.transposition_table.resize(size_mb);
+        Ok(())
+      }
+      "threads" => {
+        self.threads = value
+          .parse::<usize>()
+          .ok()
+          .filter(|threads| (1..=MAX_THREADS).contains(threads))
+          .ok_or(format!("Invalid Threads count: {}", value))?;
+        Ok(())
+      }
+      "multipv" => {
+        self.multi_pv = value
+          .parse::<usize>()
+          .ok()
+          .filter(|lines| (1..=MAX_MULTI_PV).contains(lines))
+          .ok_or(format!("Invalid MultiPV count: {}", value))?;
+        Ok(())
+      }
+      "nullmovepruning" => {
+        self.search_options.null_move_pruning = parse_check(name, value)?;
+        Ok(())
+      }
+      "latemovereductions" => {
+        self.search_options.late_move_reductions = parse_check(name, value)?;
+        Ok(())
+      }
+      "futilitypruning" => {
+        self.search_options.futility_pruning = parse_check(name, value)?;
+        Ok(())
+      }
+      "reversefutilitypruning" => {
+        self.search_options.reverse_futility_pruning =
+          parse_check(name, value)?;
+        Ok(())
+      }
+      "aspirationwindows" => {
+        self.search_options.aspiration_windows = parse_check(name, value)?;
+        Ok(())
+      }
+      "ownbook" => {
+        self.own_book = parse_check(name, value)?;
+        Ok(())
+      }
+      "bookfile" => {
+        self.book = match value {
+          "" | "<empty>" => None,
+          path => Some(OpeningBook::open(path)?),
+        };
+        Ok(())
+      }
+      "bookchoice" => {
+        self.book_choice = match value.to_lowercase().as_str() {
+          "weighted" => BookChoice::WeightedRandom,
+          "best" => BookChoice::Best,
+          _ => return Err(format!("Invalid BookChoice: {}", value)),
+        };
+        Ok(())
+      }
+      "syzygypath" => {
+        self.tablebase = match value {
+          "" | "<empty>" => None,
+          paths => Some(Tablebase::open(paths)?),
+        };
+        Ok(())
+      }
+      "bitbasefile" => {
+        self.bitbases = match value {
+          "" | "<empty>" => None,
+          path => Some(Bitbases::open(path)?),
+        };
+        Ok(())
+      }
+      _ => Err(format!("Unknown option: {}", name)),
+    }
+  }
+
+  pub fn set_book(&mut self, book: Option<OpeningBook>) {
+    self.book = book;
+  }
+
+  pub fn book(&self) -> Option<&OpeningBook> {
+    self.book.as_ref()
+  }
+
+  fn book_move(&mut self, game: &Game) -> Option<Move> {
+    if !self.own_book {
+      return None;
+    }
+    self.book.as_mut()?.choose(game, self.book_choice)
+  }
+
+  pub fn set_tablebase(&mut self, tablebase: Option<Tablebase>) {
+    self.tablebase = tablebase;
+  }
+
+  pub fn tablebase(&self) -> Option<&Tablebase> {
+    self.tablebase.as_ref()
+  }
+
+  pub fn set_bitbases(&mut self, bitbases: Option<Bitbases>) {
+    self.bitbases = bitbases;
+  }
+
+  pub fn bitbases(&self) -> Option<&Bitbases> {
+    self.bitbases.as_ref()
+  }
+
+  // The move keeping the best result, winning the fastest or losing the
+  // slowest, when the position is in the tablebases
+  fn tablebase_move(&self, game: &Game) -> Option<SearchResult> {
+    let tablebase = self.tablebase.as_ref()?;
+    let chessboard = game.chessboard();
+    if !tablebase.covers(chessboard) {
+      return None;
+    }
+    let root_move = tablebase
+      .best_move(chessboard, game.player_color())
+      .ok()??;
+
+    let score = match root_move.wdl.signum() {
+      1 => TABLEBASE_WIN - root_move.dtz,
+      -1 => -TABLEBASE_WIN - root_move.dtz,
+      _ => 0,
+    };
+    Some(SearchResult {
+      best_move: Some(root_move.chess_move),
+      score: Score::Centipawns(score),
+      depth: 0,
+      nodes: 0,
+      pv: vec![root_move.chess_move],
+    })
+  }
+
+  pub fn threads(&self) -> usize {
+    self.threads
+  }
+
+  pub fn multi_pv(&self) -> usize {
+    self.multi_pv
+  }
+
+  pub fn search_options(&self) -> SearchOptions {
+    self.search_options
+  }
+
+  pub fn set_search_options(&mut self, search_options: SearchOptions) {
+    self.search_options = search_options;
+  }
+
+  pub fn transposition_table(&self) -> &TranspositionTable {
+    &self.transposition_table
+  }
+}
+
+fn parse_check(name: &str, value: &str) -> Result<bool, String> {
+  match value.to_lowercase().as_str() {
+    "true" => Ok(true),
+    "false" => Ok(false),
+    _ => Err(format!("Invalid value for {}: {}", name, value)),
+  }
+}
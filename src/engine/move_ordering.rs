@@ -0,0 +1,203 @@
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::engine::search::MAX_DEPTH;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+
+const SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
+const KILLERS_PER_PLY: usize = 2;
+
+// History scores are halved once one of them grows past this, so that
+// recent cutoffs keep mattering more than old ones
+const HISTORY_LIMIT: i32 = 1 << 20;
+
+// Which of the heuristics learned during the search are used to order
+// quiet moves. Turning them off is mostly useful to measure what each is
+// worth.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveOrderingOptions {
+  pub killer_moves: bool,
+  pub history: bool,
+  pub countermoves: bool,
+}
+
+impl Default for MoveOrderingOptions {
+  fn default() -> Self {
+    MoveOrderingOptions {
+      killer_moves: true,
+      history: true,
+      countermoves: true,
+    }
+  }
+}
+
+impl MoveOrderingOptions {
+  pub fn none() -> Self {
+    MoveOrderingOptions {
+      killer_moves: false,
+      history: false,
+      countermoves: false,
+    }
+  }
+}
+
+// Groups moves are searched in, from first to last
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MoveClass {
+  HashMove,
+  GoodCapture,
+  Killer,
+  Countermove,
+  Quiet,
+  LosingCapture,
+}
+
+// Remembers the quiet moves that caused beta cutoffs so that they are
+// tried early in sibling and later nodes
+pub struct MoveOrdering {
+  options: MoveOrderingOptions,
+  killers: Vec<[Option<Move>; KILLERS_PER_PLY]>,
+  // Butterfly table indexed by side, from and to squares
+  history: Vec<i32>,
+  // Reply that refuted a move, indexed by that move's from and to squares
+  countermoves: Vec<Option<Move>>,
+}
+
+impl MoveOrdering {
+  pub fn new(options: MoveOrderingOptions) -> Self {
+    MoveOrdering {
+      options,
+      killers: vec![[None; KILLERS_PER_PLY]; MAX_DEPTH as usize + 1],
+      history: vec![0; 2 * SQUARES * SQUARES],
+      countermoves: vec![None; SQUARES * SQUARES],
+    }
+  }
+
+  // The hash move first, then captures that don't lose material by static
+  // exchange evaluation in most valuable victim / least valuable attacker
+  // order, then killers, the countermove and the remaining quiet moves by
+  // history, and losing captures last
+  pub fn order(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+    moves: &mut [Move],
+    hash_move: Option<Move>,
+    ply: u32,
+    previous_move: Option<Move>,
+  ) {
+    let killers = self.killers(ply);
+    let countermove = self.countermove(previous_move);
+
+    moves.sort_by_cached_key(|chess_move| {
+      if Some(*chess_move) == hash_move {
+        return (MoveClass::HashMove, 0, 0);
+      }
+
+      if let Some(victim) = chessboard.get_piece(chess_move.to()) {
+        let attacker = chessboard.get_piece(chess_move.from()).unwrap();
+        // Taking a piece worth at least the attacker can't lose material,
+        // so the exchange only needs evaluating otherwise
+        if attacker.value() > victim.value() {
+          let exchange = chessboard.see(chess_move);
+          if exchange < 0 {
+            return (MoveClass::LosingCapture, -exchange, 0);
+          }
+        }
+        return (MoveClass::GoodCapture, -victim.value(), attacker.value());
+      }
+
+      if let Some(slot) = killers.iter().position(|k| *k == Some(*chess_move)) {
+        return (MoveClass::Killer, slot as i32, 0);
+      }
+      if countermove == Some(*chess_move) {
+        return (MoveClass::Countermove, 0, 0);
+      }
+      (MoveClass::Quiet, -self.history_score(color, chess_move), 0)
+    });
+  }
+
+  // Called when a quiet move fails high; `tried` are the quiet moves searched
+  // before it at the same node, which didn't
+  pub fn record_cutoff(
+    &mut self,
+    color: Color,
+    chess_move: Move,
+    tried: &[Move],
+    depth: u32,
+    ply: u32,
+    previous_move: Option<Move>,
+  ) {
+    if self.options.killer_moves
+      && let Some(killers) = self.killers.get_mut(ply as usize)
+      && killers[0] != Some(chess_move)
+    {
+      killers[1] = killers[0];
+      killers[0] = Some(chess_move);
+    }
+
+    if self.options.history {
+      let bonus = (depth * depth) as i32;
+      self.add_history(color, &chess_move, bonus);
+      for quiet in tried {
+        self.add_history(color, quiet, -bonus);
+      }
+    }
+
+    if self.options.countermoves
+      && let Some(previous_move) = previous_move
+    {
+      self.countermoves[Self::move_index(&previous_move)] = Some(chess_move);
+    }
+  }
+
+  fn killers(&self, ply: u32) -> [Option<Move>; KILLERS_PER_PLY] {
+    match self.killers.get(ply as usize) {
+      Some(killers) if self.options.killer_moves => *killers,
+      _ => [None; KILLERS_PER_PLY],
+    }
+  }
+
+  fn countermove(&self, previous_move: Option<Move>) -> Option<Move> {
+    if !self.options.countermoves {
+      return None;
+    }
+    self.countermoves[Self::move_index(&previous_move?)]
+  }
+
+  fn history_score(&self, color: Color, chess_move: &Move) -> i32 {
+    if !self.options.history {
+      return 0;
+    }
+    self.history[Self::history_index(color, chess_move)]
+  }
+
+  fn add_history(&mut self, color: Color, chess_move: &Move, bonus: i32) {
+    let index = Self::history_index(color, chess_move);
+    self.history[index] += bonus;
+
+    if self.history[index].abs() > HISTORY_LIMIT {
+      for score in self.history.iter_mut() {
+        *score /= 2;
+      }
+    }
+  }
+
+  fn history_index(color: Color, chess_move: &Move) -> usize {
+    let side = match color {
+      Color::White => 0,
+      Color::Black => 1,
+    };
+    side * SQUARES * SQUARES + Self::move_index(chess_move)
+  }
+
+  fn move_index(chess_move: &Move) -> usize {
+    Self::square_index(chess_move.from()) * SQUARES
+      + Self::square_index(chess_move.to())
+  }
+
+  fn square_index(position: Position) -> usize {
+    position.x() * BOARD_SIZE + position.y()
+  }
+}
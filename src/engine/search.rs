@@ -0,0 +1,733 @@
+use crate::bitbase::Bitbases;
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::engine::evaluation::Evaluator;
+use crate::engine::move_ordering::{MoveOrdering, MoveOrderingOptions};
+use crate::engine::time_manager::TimeManager;
+use crate::engine::transposition_table::{Bound, TranspositionTable};
+use crate::move_generator::MoveGenerator;
+use crate::pieces::piece::QUEEN_VALUE;
+use crate::pieces::types::color::Color;
+use crate::tablebase::Tablebase;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+pub const MATE_SCORE: i32 = 30_000;
+pub const INFINITY: i32 = 32_000;
+pub const MAX_DEPTH: u32 = 64;
+
+// Scores above this are mates, with the distance encoded in the difference
+pub const MATE_THRESHOLD: i32 = MATE_SCORE - MAX_DEPTH as i32 * 2;
+
+// Tablebase wins, below mates since the distance to mate isn't known
+pub const TABLEBASE_WIN: i32 = 20_000;
+
+// Margin for what a capture can gain beyond the captured piece's value,
+// used to skip captures that can't bring the score back up to alpha
+const DELTA_MARGIN: i32 = 200;
+
+// Reverse futility pruning: a node whose static evaluation beats beta by
+// this much per remaining ply is assumed to fail high
+const REVERSE_FUTILITY_MARGIN: i32 = 120;
+const REVERSE_FUTILITY_MAX_DEPTH: u32 = 3;
+
+// Futility pruning: quiet moves are skipped near the leaves when the static
+// evaluation plus this margin can't reach alpha
+const FUTILITY_MARGINS: [i32; 3] = [0, 150, 300];
+
+const NULL_MOVE_MIN_DEPTH: u32 = 3;
+
+// Late move reductions apply to quiet moves searched after these many
+const LMR_MIN_MOVES: usize = 3;
+const LMR_MIN_DEPTH: u32 = 3;
+
+// Half width of the first aspiration window, widened on every failure
+const ASPIRATION_WINDOW: i32 = 50;
+const ASPIRATION_MIN_DEPTH: u32 = 4;
+
+// Score drop between iterations treated as the best move failing low
+const FAIL_LOW_MARGIN: i32 = 30;
+
+// Extra share of the soft time budget given when the best move changed in
+// the last iteration, or when its score dropped
+const BEST_MOVE_CHANGE_SCALE: f64 = 1.0;
+const FAIL_LOW_SCALE: f64 = 1.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Score {
+  Centipawns(i32),
+  // Moves until mate; negative when the side to move is getting mated
+  Mate(i32),
+}
+
+impl Score {
+  pub fn from_search_score(score: i32) -> Self {
+    if score >= MATE_THRESHOLD {
+      Score::Mate((MATE_SCORE - score + 1) / 2)
+    } else if score <= -MATE_THRESHOLD {
+      Score::Mate(-(MATE_SCORE + score + 1) / 2)
+    } else {
+      Score::Centipawns(score)
+    }
+  }
+}
+
+// Pruning and reduction techniques trade a little accuracy for searching
+// deeper in the same time; each can be turned off to test what it is worth
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchOptions {
+  pub move_ordering: MoveOrderingOptions,
+  pub null_move_pruning: bool,
+  pub late_move_reductions: bool,
+  pub futility_pruning: bool,
+  pub reverse_futility_pruning: bool,
+  pub aspiration_windows: bool,
+}
+
+impl Default for SearchOptions {
+  fn default() -> Self {
+    SearchOptions {
+      move_ordering: MoveOrderingOptions::default(),
+      null_move_pruning: true,
+      late_move_reductions: true,
+      futility_pruning: true,
+      reverse_futility_pruning: true,
+      aspiration_windows: true,
+    }
+  }
+}
+
+impl SearchOptions {
+  // Plain alpha-beta, still with the full move ordering
+  pub fn without_pruning() -> Self {
+    SearchOptions {
+      move_ordering: MoveOrderingOptions::default(),
+      null_move_pruning: false,
+      late_move_reductions: false,
+      futility_pruning: false,
+      reverse_futility_pruning: false,
+      aspiration_windows: false,
+    }
+  }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct SearchLimits {
+  pub depth: Option<u32>,
+  pub movetime: Option<Duration>,
+  pub nodes: Option<u64>,
+  // Clock of the side to move
+  pub time_left: Option<Duration>,
+  pub increment: Duration,
+  pub moves_to_go: Option<u32>,
+  pub infinite: bool,
+}
+
+impl SearchLimits {
+  pub fn depth(depth: u32) -> Self {
+    SearchLimits {
+      depth: Some(depth),
+      ..Default::default()
+    }
+  }
+}
+
+pub struct SearchInfo {
+  pub depth: u32,
+  // Rank of the line among the best ones, starting at 1
+  pub multipv: usize,
+  pub score: Score,
+  pub nodes: u64,
+  pub elapsed: Duration,
+  pub pv: Vec<Move>,
+}
+
+pub struct SearchResult {
+  pub best_move: Option<Move>,
+  pub score: Score,
+  pub depth: u32,
+  pub nodes: u64,
+  pub pv: Vec<Move>,
+}
+
+pub struct Search<'a> {
+  limits: SearchLimits,
+  options: SearchOptions,
+  transposition_table: &'a TranspositionTable,
+  tablebase: Option<&'a Tablebase>,
+  stop: Arc<AtomicBool>,
+  evaluator: Evaluator<'a>,
+  move_ordering: MoveOrdering,
+  // Helper threads start deeper so that they don't all search in lockstep
+  start_depth: u32,
+  // Number of best lines searched and reported at each depth
+  multi_pv: usize,
+  // Root moves left out while searching the lines after the first
+  excluded_root_moves: Vec<Move>,
+  time_manager: TimeManager,
+  deadline: Option<Instant>,
+  nodes: u64,
+  stopped: bool,
+  // Set when the root search failed low during the current iteration
+  root_failed_low: bool,
+}
+
+impl<'a> Search<'a> {
+  pub fn new(
+    limits: SearchLimits,
+    options: SearchOptions,
+    stop: Arc<AtomicBool>,
+    transposition_table: &'a TranspositionTable,
+  ) -> Self {
+    Search {
+      time_manager: TimeManager::new(&limits),
+      limits,
+      options,
+      transposition_table,
+      tablebase: None,
+      stop,
+      evaluator: Evaluator::default(),
+      move_ordering: MoveOrdering::new(options.move_ordering),
+      start_depth: 1,
+      multi_pv: 1,
+      excluded_root_moves: Vec::new(),
+      deadline: None,
+      nodes: 0,
+      stopped: false,
+      root_failed_low: false,
+    }
+  }
+
+  pub fn with_start_depth(mut self, start_depth: u32) -> Self {
+    self.start_depth = start_depth.clamp(1, MAX_DEPTH);
+    self
+  }
+
+  pub fn with_multi_pv(mut self, multi_pv: usize) -> Self {
+    self.multi_pv = multi_pv.max(1);
+    self
+  }
+
+  pub fn with_bitbases(mut self, bitbases: Option<&'a Bitbases>) -> Self {
+    self.evaluator = Evaluator::with_bitbases(bitbases);
+    self
+  }
+
+  pub fn with_tablebase(mut self, tablebase: Option<&'a Tablebase>) -> Self {
+    self.tablebase = tablebase;
+    self
+  }
+
+  // Iterative deepening: each completed depth reports its principal
+  // variation through `on_info`, followed by the next best lines with
+  // MultiPV, each found by searching the root again without the moves
+  // that start the lines before it. With a clock to manage, the search stops
+  // between iterations once the soft time budget is used up, which is
+  // stretched while the best move is unstable or failing low, and right
+  // away when there is only one legal move.
+  pub fn run(
+    &mut self,
+    chessboard: &Chessboard,
+    color: Color,
+    on_info: &mut dyn FnMut(&SearchInfo),
+  ) -> SearchResult {
+    self.time_manager = TimeManager::new(&self.limits);
+    self.deadline = self.time_manager.hard_deadline();
+    self.nodes = 0;
+    self.stopped = false;
+
+    let root_moves = MoveGenerator::new(chessboard).legal_moves(color);
+    let mut result = SearchResult {
+      best_move: root_moves.first().copied(),
+      score: Score::Centipawns(0),
+      depth: 0,
+      nodes: 0,
+      pv: Vec::new(),
+    };
+
+    if root_moves.is_empty() {
+      return result;
+    }
+
+    let max_depth = self.limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+    let lines = self.multi_pv.min(root_moves.len());
+    let mut previous_scores = vec![0; lines];
+    let mut best_move_changes = 0.0;
+    for depth in self.start_depth.min(max_depth)..=max_depth {
+      let mut pv = Vec::new();
+      self.root_failed_low = false;
+      self.excluded_root_moves.clear();
+      let previous_score = previous_scores[0];
+      let score =
+        self.search_root(chessboard, color, depth, previous_score, &mut pv);
+
+      if self.stopped {
+        break;
+      }
+
+      let failed_low = self.root_failed_low
+        || (result.depth > 0 && score < previous_score - FAIL_LOW_MARGIN);
+      previous_scores[0] = score;
+      if result.depth > 0 && pv.first() != result.best_move.as_ref() {
+        best_move_changes += 1.0;
+      }
+
+      result.best_move = pv.first().copied();
+      result.score = Score::from_search_score(score);
+      result.depth = depth;
+      result.pv = pv;
+
+      on_info(&SearchInfo {
+        depth,
+        multipv: 1,
+        score: result.score,
+        nodes: self.nodes,
+        elapsed: self.time_manager.elapsed(),
+        pv: result.pv.clone(),
+      });
+
+      let mut line_move = result.best_move;
+      for (line, previous_score) in
+        previous_scores.iter_mut().enumerate().skip(1)
+      {
+        self.excluded_root_moves.extend(line_move);
+        let mut pv = Vec::new();
+        let score =
+          self.search_root(chessboard, color, depth, *previous_score, &mut pv);
+        if self.stopped {
+          break;
+        }
+
+        *previous_score = score;
+        line_move = pv.first().copied();
+        on_info(&SearchInfo {
+          depth,
+          multipv: line + 1,
+          score: Score::from_search_score(score),
+          nodes: self.nodes,
+          elapsed: self.time_manager.elapsed(),
+          pv,
+        });
+      }
+      if self.stopped {
+        break;
+      }
+
+      // Fixed time and node budgets are used up whatever is found
+      let exact_budget =
+        self.limits.movetime.is_some() || self.limits.nodes.is_some();
+
+      // A forced mate won't get any better by searching deeper
+      if !self.limits.infinite && !exact_budget && score.abs() >= MATE_THRESHOLD
+      {
+        break;
+      }
+
+      if self.time_manager.is_adaptive() {
+        if root_moves.len() == 1 {
+          break;
+        }
+
+        let mut scale = 1.0 + BEST_MOVE_CHANGE_SCALE * best_move_changes;
+        if failed_low {
+          scale *= FAIL_LOW_SCALE;
+        }
+        if !self.time_manager.should_start_iteration(scale) {
+          break;
+        }
+        // Older changes matter less and less
+        best_move_changes /= 2.0;
+      }
+    }
+
+    result.nodes = self.nodes;
+    result
+  }
+
+  // Searches a narrow window around the previous iteration's score first,
+  // which cuts off more, and widens it until the score falls inside
+  fn search_root(
+    &mut self,
+    chessboard: &Chessboard,
+    color: Color,
+    depth: u32,
+    previous_score: i32,
+    pv: &mut Vec<Move>,
+  ) -> i32 {
+    if !self.options.aspiration_windows
+      || depth < ASPIRATION_MIN_DEPTH
+      || previous_score.abs() >= MATE_THRESHOLD
+    {
+      return self
+        .negamax(chessboard, color, depth, 0, -INFINITY, INFINITY, None, pv);
+    }
+
+    let mut window = ASPIRATION_WINDOW;
+    let mut alpha = previous_score - window;
+    let mut beta = previous_score + window;
+    loop {
+      pv.clear();
+      let score =
+        self.negamax(chessboard, color, depth, 0, alpha, beta, None, pv);
+
+      if self.stopped {
+        return score;
+      }
+      if score <= alpha {
+        self.root_failed_low = true;
+        alpha = (score - window).max(-INFINITY);
+      } else if score >= beta {
+        beta = (score + window).min(INFINITY);
+      } else {
+        return score;
+      }
+      window *= 2;
+    }
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn negamax(
+    &mut self,
+    chessboard: &Chessboard,
+    color: Color,
+    depth: u32,
+    ply: u32,
+    mut alpha: i32,
+    beta: i32,
+    previous_move: Option<Move>,
+    pv: &mut Vec<Move>,
+  ) -> i32 {
+    if depth == 0 {
+      return self.quiescence(chessboard, color, ply, alpha, beta);
+    }
+
+    if self.should_stop() {
+      self.stopped = true;
+      return 0;
+    }
+    self.nodes += 1;
+
+    let key = chessboard.hash(color);
+    let mut hash_move = None;
+    if let Some(entry) = self.transposition_table.probe(key) {
+      hash_move = entry.best_move();
+
+      // The root always searches so that it has a full PV to report
+      if ply > 0 && entry.depth() >= depth {
+        let score = entry.score(ply);
+        match entry.bound() {
+          Bound::Exact => return score,
+          Bound::Lower if score >= beta => return score,
+          Bound::Upper if score <= alpha => return score,
+          _ => (),
+        }
+      }
+    }
+
+    // Endgames in the tablebases have a known result
+    if ply > 0
+      && let Some(tablebase) = self.tablebase
+      && tablebase.covers(chessboard)
+      && let Ok(wdl) = tablebase.probe_wdl(chessboard, color)
+    {
+      let score = match wdl.signum() {
+        1 => TABLEBASE_WIN - ply as i32,
+        -1 => -TABLEBASE_WIN + ply as i32,
+        _ => 0,
+      };
+      self.transposition_table.store(
+        key,
+        depth,
+        Bound::Exact,
+        score,
+        hash_move,
+        ply,
+      );
+      return score;
+    }
+
+    let move_generator = MoveGenerator::new(chessboard);
+    let in_check = move_generator.is_king_in_check(color);
+    let mut moves = move_generator.legal_moves(color);
+    if moves.is_empty() {
+      if in_check {
+        return -MATE_SCORE + ply as i32;
+      }
+      return 0;
+    }
+    let excluding = ply == 0 && !self.excluded_root_moves.is_empty();
+    if excluding {
+      moves.retain(|chess_move| !self.excluded_root_moves.contains(chess_move));
+    }
+
+    // Nodes where pruning is safe: not the root, not in check and not
+    // searching for a mate score
+    let can_prune = ply > 0 && !in_check && beta.abs() < MATE_THRESHOLD;
+    let static_eval = self.evaluator.evaluate(chessboard, color);
+
+    if can_prune
+      && self.options.reverse_futility_pruning
+      && depth <= REVERSE_FUTILITY_MAX_DEPTH
+      && static_eval - REVERSE_FUTILITY_MARGIN * depth as i32 >= beta
+    {
+      return static_eval;
+    }
+
+    // Null move pruning: if passing still fails high, so would a real move.
+    // In pawn endings passing could be the only way out of zugzwang, so it is
+    // never tried there. A null move is never followed by another one.
+    if can_prune
+      && self.options.null_move_pruning
+      && depth >= NULL_MOVE_MIN_DEPTH
+      && previous_move.is_some()
+      && static_eval >= beta
+      && chessboard.has_pieces_besides_pawns(color)
+    {
+      let reduction = 2 + depth / 4;
+      let score = -self.negamax(
+        chessboard,
+        color.next(),
+        depth.saturating_sub(1 + reduction),
+        ply + 1,
+        -beta,
+        -beta + 1,
+        None,
+        &mut Vec::new(),
+      );
+
+      if self.stopped {
+        return 0;
+      }
+      if score >= beta {
+        return beta;
+      }
+    }
+
+    let futile = can_prune
+      && self.options.futility_pruning
+      && (depth as usize) < FUTILITY_MARGINS.len()
+      && static_eval + FUTILITY_MARGINS[depth as usize] <= alpha;
+
+    self.move_ordering.order(
+      chessboard,
+      color,
+      &mut moves,
+      hash_move,
+      ply,
+      previous_move,
+    );
+
+    let original_alpha = alpha;
+    let mut best_move = None;
+    let mut quiets_tried = Vec::new();
+    for (index, chess_move) in moves.into_iter().enumerate() {
+      let is_quiet = chessboard.is_position_empty(chess_move.to())
+        && chess_move.promotion().is_none();
+      let mut child = chessboard.clone();
+      child.apply_move(&chess_move).unwrap();
+
+      // Checks are never pruned or reduced
+      let is_quiet_non_check = is_quiet
+        && (futile || index >= LMR_MIN_MOVES)
+        && !MoveGenerator::new(&child).is_king_in_check(color.next());
+
+      if futile && index > 0 && is_quiet_non_check {
+        quiets_tried.push(chess_move);
+        continue;
+      }
+
+      // Late moves are unlikely to be best after good ordering: they get a
+      // shallower null window search first, and a full one only if they
+      // turn out to beat alpha
+      let reduction = if self.options.late_move_reductions
+        && !in_check
+        && depth >= LMR_MIN_DEPTH
+        && index >= LMR_MIN_MOVES
+        && is_quiet_non_check
+      {
+        if depth >= 6 && index >= 6 { 2 } else { 1 }
+      } else {
+        0
+      };
+
+      let mut child_pv = Vec::new();
+      let mut score = alpha + 1;
+      if reduction > 0 {
+        score = -self.negamax(
+          &child,
+          color.next(),
+          depth - 1 - reduction,
+          ply + 1,
+          -alpha - 1,
+          -alpha,
+          Some(chess_move),
+          &mut child_pv,
+        );
+      }
+      if score > alpha {
+        child_pv.clear();
+        score = -self.negamax(
+          &child,
+          color.next(),
+          depth - 1,
+          ply + 1,
+          -beta,
+          -alpha,
+          Some(chess_move),
+          &mut child_pv,
+        );
+      }
+
+      if self.stopped {
+        return 0;
+      }
+
+      if score > alpha {
+        alpha = score;
+        best_move = Some(chess_move);
+        pv.clear();
+        pv.push(chess_move);
+        pv.extend(child_pv);
+
+        if alpha >= beta {
+          if is_quiet {
+            self.move_ordering.record_cutoff(
+              color,
+              chess_move,
+              &quiets_tried,
+              depth,
+              ply,
+              previous_move,
+            );
+          }
+          break;
+        }
+      }
+
+      if is_quiet {
+        quiets_tried.push(chess_move);
+      }
+    }
+
+    let bound = if alpha >= beta {
+      Bound::Lower
+    } else if alpha > original_alpha {
+      Bound::Exact
+    } else {
+      Bound::Upper
+    };
+    // The root's entry keeps the best line's move, not a lesser line's
+    if !excluding {
+      self.transposition_table.store(
+        key,
+        depth,
+        bound,
+        alpha,
+        best_move.or(hash_move),
+        ply,
+      );
+    }
+
+    alpha
+  }
+
+  // Searches captures only until the position is quiet, so that the
+  // evaluation isn't taken in the middle of an exchange
+  fn quiescence(
+    &mut self,
+    chessboard: &Chessboard,
+    color: Color,
+    ply: u32,
+    mut alpha: i32,
+    beta: i32,
+  ) -> i32 {
+    if self.should_stop() {
+      self.stopped = true;
+      return 0;
+    }
+    self.nodes += 1;
+
+    let move_generator = MoveGenerator::new(chessboard);
+
+    // In check every evasion has to be looked at, as standing still isn't
+    // an option
+    let stand_pat = if move_generator.is_king_in_check(color) {
+      None
+    } else {
+      Some(self.evaluator.evaluate(chessboard, color))
+    };
+
+    if let Some(stand_pat) = stand_pat {
+      if stand_pat >= beta {
+        return beta;
+      }
+      // Delta pruning: even winning a queen wouldn't reach alpha
+      if stand_pat + QUEEN_VALUE + DELTA_MARGIN < alpha {
+        return alpha;
+      }
+      alpha = alpha.max(stand_pat);
+    }
+
+    let mut moves = match stand_pat {
+      Some(_) => move_generator.legal_captures(color),
+      None => move_generator.legal_moves(color),
+    };
+    if stand_pat.is_none() && moves.is_empty() {
+      return -MATE_SCORE + ply as i32;
+    }
+
+    self
+      .move_ordering
+      .order(chessboard, color, &mut moves, None, ply, None);
+
+    for chess_move in moves {
+      if let Some(stand_pat) = stand_pat {
+        // Captures losing material can't improve on standing pat
+        if chessboard.see(&chess_move) < 0 {
+          continue;
+        }
+        let captured_value = chessboard
+          .get_piece(chess_move.to())
+          .map_or(0, |piece| piece.value());
+        if stand_pat + captured_value + DELTA_MARGIN < alpha {
+          continue;
+        }
+      }
+
+      let mut child = chessboard.clone();
+      child.apply_move(&chess_move).unwrap();
+
+      let score =
+        -self.quiescence(&child, color.next(), ply + 1, -beta, -alpha);
+
+      if self.stopped {
+        return 0;
+      }
+
+      if score > alpha {
+        alpha = score;
+        if alpha >= beta {
+          break;
+        }
+      }
+    }
+
+    alpha
+  }
+
+  fn should_stop(&self) -> bool {
+    if self.stop.load(Ordering::Relaxed) {
+      return true;
+    }
+    if let Some(nodes) = self.limits.nodes
+      && self.nodes >= nodes
+    {
+      return true;
+    }
+    match self.deadline {
+      Some(deadline) => Instant::now() >= deadline,
+      None => false,
+    }
+  }
+}
@@ -0,0 +1,48 @@
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::move_generator::MoveGenerator;
+
+impl Chessboard {
+  // Static exchange evaluation: material the side making the move wins (or
+  // loses, if negative) once both sides have traded off all their pieces
+  // attacking the target square, always recapturing with the least valuable
+  // piece. Each side may stop trading when continuing would lose material.
+  pub fn see(&self, chess_move: &Move) -> i32 {
+    let Some(piece) = self.get_piece(chess_move.from()) else {
+      return 0;
+    };
+
+    let target = chess_move.to();
+    let mut chessboard = self.clone();
+    let mut color = piece.color().next();
+    let mut piece_on_target = piece.value();
+    let mut gains = vec![self.get_piece(target).map_or(0, |p| p.value())];
+
+    if chessboard.move_piece(chess_move.from(), target).is_err() {
+      return 0;
+    }
+
+    // Removing each capturing piece from its square reveals the sliding
+    // pieces behind it
+    while let Some(attacker) = MoveGenerator::new(&chessboard)
+      .attackers(target, color)
+      .into_iter()
+      .min_by_key(|position| chessboard.get_piece(*position).unwrap().value())
+    {
+      gains.push(piece_on_target - gains.last().unwrap());
+      piece_on_target = chessboard.get_piece(attacker).unwrap().value();
+      chessboard.move_piece(attacker, target).unwrap();
+      color = color.next();
+    }
+
+    // Walk the exchange back: each side picks the better of stopping or
+    // carrying on
+    while gains.len() > 1 {
+      let last = gains.pop().unwrap();
+      let previous = gains.last_mut().unwrap();
+      *previous = -(-*previous).max(last);
+    }
+
+    gains[0]
+  }
+}
@@ -0,0 +1,97 @@
+use crate::engine::search::SearchLimits;
+use std::time::{Duration, Instant};
+
+// Moves assumed to be left in the game when the GUI doesn't say
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+// Kept off the clock for the time it takes the move to reach the GUI
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+// How far the search may go past the soft budget when it has to
+const HARD_BUDGET_FACTOR: u32 = 4;
+
+// Splits the clock into a budget for the current move. The soft budget is
+// what a move normally gets and is only checked between iterations, so
+// the search can take longer when its best move keeps changing. The hard
+// budget stops the search wherever it is.
+pub struct TimeManager {
+  start: Instant,
+  soft_budget: Option<Duration>,
+  hard_budget: Option<Duration>,
+  // Only budgets derived from the clock adapt to how the search goes;
+  // `movetime` is used exactly
+  adaptive: bool,
+}
+
+impl TimeManager {
+  pub fn new(limits: &SearchLimits) -> Self {
+    let mut time_manager = TimeManager {
+      start: Instant::now(),
+      soft_budget: None,
+      hard_budget: None,
+      adaptive: false,
+    };
+
+    if limits.infinite {
+      return time_manager;
+    }
+
+    if let Some(movetime) = limits.movetime {
+      time_manager.soft_budget = Some(movetime);
+      time_manager.hard_budget = Some(movetime);
+    } else if let Some(time_left) = limits.time_left {
+      let available = time_left.saturating_sub(MOVE_OVERHEAD);
+      let moves_to_go = limits.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO);
+      let share = available / moves_to_go.max(1) + limits.increment * 3 / 4;
+
+      // Never plan to use more than half of what is left on the clock, or
+      // three quarters of it in an emergency
+      let hard_budget = (share * HARD_BUDGET_FACTOR).min(available * 3 / 4);
+      time_manager.soft_budget = Some(share.min(available / 2));
+      time_manager.hard_budget = Some(hard_budget);
+      time_manager.adaptive = true;
+    }
+
+    time_manager
+  }
+
+  pub fn soft_budget(&self) -> Option<Duration> {
+    self.soft_budget
+  }
+
+  pub fn hard_budget(&self) -> Option<Duration> {
+    self.hard_budget
+  }
+
+  pub fn is_adaptive(&self) -> bool {
+    self.adaptive
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    self.start.elapsed()
+  }
+
+  pub fn hard_deadline(&self) -> Option<Instant> {
+    self.hard_budget.map(|budget| self.start + budget)
+  }
+
+  // Soft budget stretched by `scale` (above 1 when the search is unsure of
+  // its move), but never past the hard one
+  pub fn scaled_budget(&self, scale: f64) -> Option<Duration> {
+    let soft_budget = self.soft_budget?.mul_f64(scale.max(0.0));
+    Some(soft_budget.min(self.hard_budget?))
+  }
+
+  // Whether another iteration is worth starting. Each one takes about as
+  // long as all the previous ones together, so none is started once half
+  // the budget is gone.
+  pub fn should_start_iteration(&self, scale: f64) -> bool {
+    if !self.adaptive {
+      return true;
+    }
+    match self.scaled_budget(scale) {
+      Some(budget) => self.elapsed() < budget / 2,
+      None => true,
+    }
+  }
+}
@@ -0,0 +1,242 @@
+use crate::chess_move::Move;
+use crate::engine::search::MATE_THRESHOLD;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::position::Position;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+pub const DEFAULT_SIZE_MB: usize = 16;
+pub const MAX_SIZE_MB: usize = 4096;
+
+// Layout of an entry packed into 64 bits
+const SCORE_SHIFT: u32 = 0;
+const DEPTH_SHIFT: u32 = 16;
+const BOUND_SHIFT: u32 = 24;
+const GENERATION_SHIFT: u32 = 26;
+const MOVE_SHIFT: u32 = 34;
+const HAS_MOVE_BIT: u64 = 1 << 50;
+// Tells a stored entry apart from an empty slot
+const VALID_BIT: u64 = 1 << 63;
+
+// Promotion pieces are stored as their index in this list plus one
+const PROMOTION_SYMBOLS: &str = "PNBRQKpnbrqk";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Bound {
+  Exact,
+  // The score is at least this much (the search failed high)
+  Lower,
+  // The score is at most this much (no move raised alpha)
+  Upper,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Entry {
+  depth: u32,
+  bound: Bound,
+  score: i32,
+  best_move: Option<Move>,
+  generation: u8,
+}
+
+impl Entry {
+  pub fn depth(&self) -> u32 {
+    self.depth
+  }
+
+  pub fn bound(&self) -> Bound {
+    self.bound
+  }
+
+  pub fn best_move(&self) -> Option<Move> {
+    self.best_move
+  }
+
+  // Mate scores are stored relative to the entry's position and turned back
+  // into distances from the root here
+  pub fn score(&self, ply: u32) -> i32 {
+    if self.score >= MATE_THRESHOLD {
+      self.score - ply as i32
+    } else if self.score <= -MATE_THRESHOLD {
+      self.score + ply as i32
+    } else {
+      self.score
+    }
+  }
+
+  fn pack(&self) -> u64 {
+    let bound = match self.bound {
+      Bound::Exact => 0,
+      Bound::Lower => 1,
+      Bound::Upper => 2,
+    };
+    let best_move = self.best_move.map_or(0, |best_move| {
+      HAS_MOVE_BIT | pack_move(&best_move) << MOVE_SHIFT
+    });
+
+    VALID_BIT
+      | (self.score as i16 as u16 as u64) << SCORE_SHIFT
+      | (self.depth.min(u8::MAX as u32) as u64) << DEPTH_SHIFT
+      | bound << BOUND_SHIFT
+      | (self.generation as u64) << GENERATION_SHIFT
+      | best_move
+  }
+
+  fn unpack(data: u64) -> Self {
+    let bound = match (data >> BOUND_SHIFT) & 0b11 {
+      0 => Bound::Exact,
+      1 => Bound::Lower,
+      _ => Bound::Upper,
+    };
+    let best_move = (data & HAS_MOVE_BIT != 0)
+      .then(|| unpack_move((data >> MOVE_SHIFT) & 0xffff));
+
+    Entry {
+      depth: ((data >> DEPTH_SHIFT) & 0xff) as u32,
+      bound,
+      score: (data >> SCORE_SHIFT) as u16 as i16 as i32,
+      best_move,
+      generation: (data >> GENERATION_SHIFT) as u8,
+    }
+  }
+}
+
+// The key is stored xor-ed with the data, so that an entry torn by two
+// threads writing the same slot at once no longer matches its key and is
+// ignored instead of being read back as garbage
+#[derive(Default)]
+struct Slot {
+  key: AtomicU64,
+  data: AtomicU64,
+}
+
+// Fixed-size table of search results indexed by position hash, shared
+// between search threads without locking. An entry is only replaced by a
+// result from a deeper (or equally deep) search, or once it is left over
+// from an earlier search.
+pub struct TranspositionTable {
+  slots: Vec<Slot>,
+  generation: AtomicU8,
+}
+
+impl TranspositionTable {
+  pub fn new(size_mb: usize) -> Self {
+    let mut slots = Vec::new();
+    slots.resize_with(Self::capacity(size_mb), Slot::default);
+
+    TranspositionTable {
+      slots,
+      generation: AtomicU8::new(0),
+    }
+  }
+
+  pub fn resize(&mut self, size_mb: usize) {
+    *self = Self::new(size_mb);
+  }
+
+  pub fn clear(&mut self) {
+    for slot in self.slots.iter_mut() {
+      *slot = Slot::default();
+    }
+    self.generation = AtomicU8::new(0);
+  }
+
+  // Called before each search so older entries become replaceable
+  pub fn new_search(&self) {
+    self.generation.fetch_add(1, Ordering::Relaxed);
+  }
+
+  pub fn len(&self) -> usize {
+    self.slots.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.slots.is_empty()
+  }
+
+  pub fn probe(&self, key: u64) -> Option<Entry> {
+    let slot = &self.slots[self.index(key)];
+    let data = slot.data.load(Ordering::Relaxed);
+    let stored_key = slot.key.load(Ordering::Relaxed) ^ data;
+
+    (data & VALID_BIT != 0 && stored_key == key).then(|| Entry::unpack(data))
+  }
+
+  pub fn store(
+    &self,
+    key: u64,
+    depth: u32,
+    bound: Bound,
+    score: i32,
+    best_move: Option<Move>,
+    ply: u32,
+  ) {
+    let slot = &self.slots[self.index(key)];
+    let generation = self.generation.load(Ordering::Relaxed);
+
+    let existing = slot.data.load(Ordering::Relaxed);
+    if existing & VALID_BIT != 0 {
+      let existing = Entry::unpack(existing);
+      if existing.generation == generation && existing.depth > depth {
+        return;
+      }
+    }
+
+    let score = if score >= MATE_THRESHOLD {
+      score + ply as i32
+    } else if score <= -MATE_THRESHOLD {
+      score - ply as i32
+    } else {
+      score
+    };
+
+    let data = Entry {
+      depth,
+      bound,
+      score,
+      best_move,
+      generation,
+    }
+    .pack();
+    slot.key.store(key ^ data, Ordering::Relaxed);
+    slot.data.store(data, Ordering::Relaxed);
+  }
+
+  fn index(&self, key: u64) -> usize {
+    (key % self.slots.len() as u64) as usize
+  }
+
+  fn capacity(size_mb: usize) -> usize {
+    let size_mb = size_mb.clamp(1, MAX_SIZE_MB);
+    (size_mb * 1024 * 1024 / size_of::<Slot>()).max(1)
+  }
+}
+
+// From and to squares take 6 bits each, the promotion piece the 4 above
+fn pack_move(chess_move: &Move) -> u64 {
+  let square = |position: Position| position.x() * BOARD_SIZE + position.y();
+  let promotion = chess_move.promotion().map_or(0, |piece| {
+    PROMOTION_SYMBOLS.find(piece.symbol()).unwrap() + 1
+  });
+
+  (square(chess_move.from()) | square(chess_move.to()) << 6 | promotion << 12)
+    as u64
+}
+
+fn unpack_move(bits: u64) -> Move {
+  let square = |bits: u64| {
+    let index = (bits & 0x3f) as usize;
+    Position::new(index / BOARD_SIZE, index % BOARD_SIZE).unwrap()
+  };
+  let from = square(bits);
+  let to = square(bits >> 6);
+
+  match (bits >> 12) as usize & 0xf {
+    0 => Move::new(from, to),
+    promotion => {
+      let symbol = PROMOTION_SYMBOLS.chars().nth(promotion - 1).unwrap();
+      Move::with_promotion(from, to, Piece::from_symbol(symbol).unwrap())
+    }
+  }
+}
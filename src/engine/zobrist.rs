@@ -0,0 +1,96 @@
+use crate::chessboard::Chessboard;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+
+const SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
+const PIECE_KINDS: usize = 12;
+// Dead pieces of one kind beyond this count all share the last key
+const MAX_DEAD_PIECES: usize = 16;
+
+const PIECE_KEYS: [[u64; SQUARES]; PIECE_KINDS] = piece_keys();
+const DEAD_PIECE_KEYS: [[u64; MAX_DEAD_PIECES]; PIECE_KINDS] =
+  dead_piece_keys();
+const BLACK_TO_MOVE_KEY: u64 = splitmix64(0x5eed_b1ac);
+
+// SplitMix64, used to fill the key tables at compile time
+const fn splitmix64(seed: u64) -> u64 {
+  let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  z ^ (z >> 31)
+}
+
+const fn piece_keys() -> [[u64; SQUARES]; PIECE_KINDS] {
+  let mut keys = [[0; SQUARES]; PIECE_KINDS];
+  let mut kind = 0;
+  while kind < PIECE_KINDS {
+    let mut square = 0;
+    while square < SQUARES {
+      keys[kind][square] = splitmix64((kind * SQUARES + square) as u64);
+      square += 1;
+    }
+    kind += 1;
+  }
+  keys
+}
+
+const fn dead_piece_keys() -> [[u64; MAX_DEAD_PIECES]; PIECE_KINDS] {
+  let mut keys = [[0; MAX_DEAD_PIECES]; PIECE_KINDS];
+  let mut kind = 0;
+  while kind < PIECE_KINDS {
+    let mut count = 0;
+    while count < MAX_DEAD_PIECES {
+      let seed = PIECE_KINDS * SQUARES + kind * MAX_DEAD_PIECES + count;
+      keys[kind][count] = splitmix64(seed as u64);
+      count += 1;
+    }
+    kind += 1;
+  }
+  keys
+}
+
+fn piece_kind_index(piece: &Piece) -> usize {
+  let kind = match piece {
+    Piece::Pawn(_) => 0,
+    Piece::Knight(_) => 1,
+    Piece::Bishop(_) => 2,
+    Piece::Rook(_) => 3,
+    Piece::Queen(_) => 4,
+    Piece::King(_) => 5,
+  };
+  match piece.color() {
+    Color::White => kind,
+    Color::Black => kind + 6,
+  }
+}
+
+impl Chessboard {
+  // Zobrist hash of the position. Dead pieces are part of it, since they
+  // decide which upgrades are possible.
+  pub fn hash(&self, player_color: Color) -> u64 {
+    let mut hash = 0;
+
+    for position in self.get_all_positions() {
+      if let Some(piece) = self.get_piece(position) {
+        let square = position.x() * BOARD_SIZE + position.y();
+        hash ^= PIECE_KEYS[piece_kind_index(piece)][square];
+      }
+    }
+
+    let mut dead_counts = [0; PIECE_KINDS];
+    let dead_pieces = self.white_dead_pieces().iter();
+    for piece in dead_pieces.chain(self.black_dead_pieces()) {
+      let kind = piece_kind_index(piece);
+      let count = dead_counts[kind].min(MAX_DEAD_PIECES - 1);
+      hash ^= DEAD_PIECE_KEYS[kind][count];
+      dead_counts[kind] += 1;
+    }
+
+    if player_color == Color::Black {
+      hash ^= BLACK_TO_MOVE_KEY;
+    }
+
+    hash
+  }
+}
@@ -0,0 +1,289 @@
+use crate::chess_move::Move;
+use crate::engine::search::{Score, SearchInfo, SearchLimits};
+use crate::game::Game;
+use crate::notation::fen::STARTING_FEN;
+use crate::pieces::types::color::Color;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub struct ExternalSearchResult {
+  pub best_move: Option<Move>,
+  // Last line of analysis the engine sent before its move
+  pub info: Option<SearchInfo>,
+}
+
+// Drives a UCI engine running as a child process, e.g. to play against a
+// stronger opponent or to analyse a game with it
+pub struct ExternalEngine {
+  child: Child,
+  // Shared with the `StopHandle`s, which write while a search is read
+  stdin: Arc<Mutex<ChildStdin>>,
+  stdout: BufReader<ChildStdout>,
+  name: Option<String>,
+}
+
+impl ExternalEngine {
+  // Starts the engine and waits for it to complete the UCI handshake
+  pub fn spawn(path: impl AsRef<Path>) -> Result<Self, String> {
+    let path = path.as_ref();
+    let mut child = Command::new(path)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()
+      .map_err(|e| format!("Failed to start {}: {}", path.display(), e))?;
+
+    let stdin = child.stdin.take().ok_or("Engine has no stdin")?;
+    let stdout =
+      BufReader::new(child.stdout.take().ok_or("Engine has no stdout")?);
+
+    let mut engine = ExternalEngine {
+      child,
+      stdin: Arc::new(Mutex::new(stdin)),
+      stdout,
+      name: None,
+    };
+
+    engine.send("uci")?;
+    loop {
+      let line = engine.read_line()?;
+      if let Some(name) = line.strip_prefix("id name ") {
+        engine.name = Some(name.trim().to_string());
+      } else if line.trim() == "uciok" {
+        break;
+      }
+    }
+
+    Ok(engine)
+  }
+
+  pub fn name(&self) -> Option<&str> {
+    self.name.as_deref()
+  }
+
+  pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+    self.send(&format!("setoption name {} value {}", name, value))?;
+    self.wait_until_ready()
+  }
+
+  pub fn new_game(&mut self) -> Result<(), String> {
+    self.send("ucinewgame")?;
+    self.wait_until_ready()
+  }
+
+  // Stops searches running on another thread
+  pub fn stop_handle(&self) -> StopHandle {
+    StopHandle {
+      stdin: Arc::clone(&self.stdin),
+    }
+  }
+
+  // Searches the current position of the game. Infinite searches go on
+  // until a `StopHandle` stops them.
+  pub fn search(
+    &mut self,
+    game: &Game,
+    limits: &SearchLimits,
+    on_info: &mut dyn FnMut(&SearchInfo),
+  ) -> Result<ExternalSearchResult, String> {
+    self.send(&Self::position_command(game))?;
+    self.send(&Self::go_command(game.player_color(), limits))?;
+
+    let mut last_info = None;
+    loop {
+      let line = self.read_line()?;
+      let mut tokens = line.split_whitespace();
+      match tokens.next() {
+        Some("info") => {
+          let args: Vec<&str> = tokens.collect();
+          if let Some(info) = Self::parse_info(&args, game.player_color()) {
+            on_info(&info);
+            last_info = Some(info);
+          }
+        }
+        Some("bestmove") => {
+          let best_move = match tokens.next() {
+            None | Some("0000") | Some("(none)") => None,
+            Some(text) => {
+              Some(Move::from_long_algebraic(text, game.player_color())?)
+            }
+          };
+          return Ok(ExternalSearchResult {
+            best_move,
+            info: last_info,
+          });
+        }
+        _ => (),
+      }
+    }
+  }
+
+  fn position_command(game: &Game) -> String {
+    let initial_fen = game.initial_fen();
+    let mut command = if initial_fen == STARTING_FEN {
+      "position startpos".to_string()
+    } else {
+      format!("position fen {}", initial_fen)
+    };
+
+    if !game.moves().is_empty() {
+      let moves: Vec<String> =
+        game.moves().iter().map(|m| m.to_long_algebraic()).collect();
+      command.push_str(" moves ");
+      command.push_str(&moves.join(" "));
+    }
+
+    command
+  }
+
+  fn go_command(color: Color, limits: &SearchLimits) -> String {
+    let mut command = "go".to_string();
+
+    if let Some(depth) = limits.depth {
+      command.push_str(&format!(" depth {}", depth));
+    }
+    if let Some(movetime) = limits.movetime {
+      command.push_str(&format!(" movetime {}", movetime.as_millis()));
+    }
+    if let Some(nodes) = limits.nodes {
+      command.push_str(&format!(" nodes {}", nodes));
+    }
+    if let Some(time_left) = limits.time_left {
+      let (time_key, increment_key) = match color {
+        Color::White => ("wtime", "winc"),
+        Color::Black => ("btime", "binc"),
+      };
+      command.push_str(&format!(
+        " {} {} {} {}",
+        time_key,
+        time_left.as_millis(),
+        increment_key,
+        limits.increment.as_millis()
+      ));
+    }
+    if let Some(moves_to_go) = limits.moves_to_go {
+      command.push_str(&format!(" movestogo {}", moves_to_go));
+    }
+    if limits.infinite {
+      command.push_str(" infinite");
+    }
+
+    command
+  }
+
+  // Only lines carrying a score and a principal variation are reported
+  fn parse_info(args: &[&str], color: Color) -> Option<SearchInfo> {
+    let mut depth = 0;
+    let mut multipv = 1;
+    let mut score = None;
+    let mut nodes = 0;
+    let mut elapsed = Duration::ZERO;
+    let mut pv = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+      let value = args.get(i + 1).copied().unwrap_or("");
+      match args[i] {
+        "depth" => depth = value.parse().ok()?,
+        "multipv" => multipv = value.parse().ok()?,
+        "nodes" => nodes = value.parse().ok()?,
+        "time" => elapsed = Duration::from_millis(value.parse().ok()?),
+        "score" => {
+          let amount = args.get(i + 2)?.parse().ok()?;
+          score = match value {
+            "cp" => Some(Score::Centipawns(amount)),
+            "mate" => Some(Score::Mate(amount)),
+            _ => None,
+          };
+          i += 1;
+        }
+        "pv" => {
+          // Moves alternate between the two sides
+          let mut mover = color;
+          for text in &args[i + 1..] {
+            pv.push(Move::from_long_algebraic(text, mover).ok()?);
+            mover = mover.next();
+          }
+          break;
+        }
+        // The rest of the line is free text
+        "string" => return None,
+        _ => {
+          i += 1;
+          continue;
+        }
+      }
+      i += 2;
+    }
+
+    if pv.is_empty() {
+      return None;
+    }
+
+    Some(SearchInfo {
+      depth,
+      multipv,
+      score: score?,
+      nodes,
+      elapsed,
+      pv,
+    })
+  }
+
+  fn wait_until_ready(&mut self) -> Result<(), String> {
+    self.send("isready")?;
+    while self.read_line()?.trim() != "readyok" {}
+    Ok(())
+  }
+
+  fn send(&mut self, command: &str) -> Result<(), String> {
+    send(&self.stdin, command)
+  }
+
+  fn read_line(&mut self) -> Result<String, String> {
+    let mut line = String::new();
+    match self.stdout.read_line(&mut line) {
+      Ok(0) => Err("Engine closed its output".to_string()),
+      Ok(_) => Ok(line),
+      Err(e) => Err(format!("Failed to read from engine: {}", e)),
+    }
+  }
+}
+
+// Sends `stop` to the engine, which then answers the running search with
+// its best move
+#[derive(Clone)]
+pub struct StopHandle {
+  stdin: Arc<Mutex<ChildStdin>>,
+}
+
+impl StopHandle {
+  pub fn stop(&self) -> Result<(), String> {
+    send(&self.stdin, "stop")
+  }
+}
+
+fn send(stdin: &Mutex<ChildStdin>, command: &str) -> Result<(), String> {
+  // A panic while writing leaves nothing half done that matters here
+  let mut stdin = stdin.lock().unwrap_or_else(|e| e.into_inner());
+  writeln!(stdin, "{}", command)
+    .and_then(|_| stdin.flush())
+    .map_err(|e| format!("Failed to write to engine: {}", e))
+}
+
+impl Drop for ExternalEngine {
+  fn drop(&mut self) {
+    let _ = self.send("quit");
+    // Don't hang on an engine that ignores `quit`
+    if let Ok(None) = self.child.try_wait() {
+      std::thread::sleep(Duration::from_millis(50));
+      if let Ok(None) = self.child.try_wait() {
+        let _ = self.child.kill();
+      }
+    }
+    let _ = self.child.wait();
+  }
+}
@@ -1,20 +1,46 @@
 use crate::board_manager::BoardManager;
+use crate::chess_move::Move;
 use crate::chessboard::{Chessboard, MoveResult};
+use crate::clock::Clock;
+use crate::move_generator::MoveGenerator;
+use crate::pieces::piece::Piece;
 use crate::pieces::types::color::Color;
 use crate::pieces::types::position::Position;
+use std::fs;
+use std::path::Path;
 
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum GameResult {
+  Checkmate { winner: Color },
+  Stalemate,
+  // The loser's flag fell
+  Timeout { winner: Color },
+  // A flag fell but the other player couldn't have mated anyway
+  TimeoutDraw,
+}
+
+#[derive(Clone)]
 pub struct Game {
   player_color: Color,
   board_manager: BoardManager,
+  // Board before each played move, so moves can be taken back
+  history: Vec<BoardManager>,
+  moves: Vec<Move>,
+  clock: Option<Clock>,
 }
 
 impl Game {
   pub fn new(player_color: Color) -> Self {
-    let board = Chessboard::standard();
-    let board_manager = BoardManager::new(board);
+    Self::with_chessboard(Chessboard::standard(), player_color)
+  }
+
+  pub fn with_chessboard(chessboard: Chessboard, player_color: Color) -> Self {
     Game {
       player_color,
-      board_manager,
+      board_manager: BoardManager::new(chessboard),
+      history: Vec::new(),
+      moves: Vec::new(),
+      clock: None,
     }
   }
 
@@ -23,6 +49,13 @@ impl Game {
     piece_position: Position,
     target_position: Position,
   ) -> Result<MoveResult, String> {
+    if let Some(GameResult::Timeout { .. } | GameResult::TimeoutDraw) =
+      self.result()
+    {
+      return Err(format!("{} has run out of time", self.player_color));
+    }
+    let board_before_move = self.board_manager.clone();
+
     match self.board_manager.move_piece(
       piece_position,
       target_position,
@@ -30,6 +63,13 @@ impl Game {
     ) {
       Ok(res) => {
         self.player_color = self.player_color.next();
+        self.history.push(board_before_move);
+        self.moves.push(Move::new(piece_position, target_position));
+        // The flag may have fallen while the move was being made
+        if let Err(e) = self.press_clock() {
+          self.undo()?;
+          return Err(e);
+        }
         Ok(res)
       }
       Err(e) => Err(e),
@@ -41,10 +81,173 @@ impl Game {
     piece_index: usize,
     upgrade_position: Position,
   ) -> Result<MoveResult, String> {
+    // The current player color is the opponent's color because it's changed after a valid move
+    let upgrading_color = self.player_color().next();
+    let piece = self
+      .chessboard()
+      .dead_pieces(upgrading_color)
+      .get(piece_index)
+      .copied();
+
+    let res = self.board_manager.upgrade_piece(
+      piece_index,
+      upgrading_color,
+      upgrade_position,
+    )?;
+
+    // Record what the pawn was upgraded to as part of its move
+    if let (Some(piece), Some(last_move)) = (piece, self.moves.last_mut()) {
+      *last_move =
+        Move::with_promotion(last_move.from(), last_move.to(), piece);
+    }
+
+    Ok(res)
+  }
+
+  // The square of a pawn that reached the last row and still waits for the
+  // dead piece it's upgraded to
+  pub fn pending_upgrade(&self) -> Option<Position> {
+    let last_move = self.moves.last()?;
+    let position = last_move.to();
+    let waiting = last_move.promotion().is_none()
+      && matches!(
+        self.chessboard().get_piece(position),
+        Some(Piece::Pawn(pawn)) if pawn.can_upgrade(position)
+      )
+      && !self
+        .chessboard()
+        .dead_pieces(self.player_color.next())
+        .is_empty();
+    waiting.then_some(position)
+  }
+
+  // Takes back the last played move
+  pub fn undo(&mut self) -> Result<Move, String> {
+    let board_manager = self.history.pop().ok_or("No move to undo")?;
+    self.board_manager = board_manager;
+    self.player_color = self.player_color.next();
+    // Time used isn't given back
+    if let Some(clock) = self.clock.as_mut()
+      && clock.running().is_some()
+    {
+      clock.start(self.player_color);
+    }
+    Ok(self.moves.pop().unwrap())
+  }
+
+  // Sets the clock, and starts it for the player to move
+  pub fn set_clock(&mut self, clock: Option<Clock>) {
+    self.clock = clock;
+    let player_color = self.player_color;
+    if let Some(clock) = self.clock.as_mut() {
+      clock.start(player_color);
+    }
+  }
+
+  pub fn clock(&self) -> Option<&Clock> {
+    self.clock.as_ref()
+  }
+
+  // The clock stops once the game is over
+  fn press_clock(&mut self) -> Result<(), String> {
+    let over = self.result().is_some();
+    let Some(clock) = self.clock.as_mut() else {
+      return Ok(());
+    };
+    clock.press()?;
+    if over {
+      clock.stop();
+    }
+    Ok(())
+  }
+
+  pub fn moves(&self) -> &[Move] {
+    &self.moves
+  }
+
+  // Board the game started from, before any of `moves` was played
+  pub fn initial_chessboard(&self) -> &Chessboard {
     self
-      .board_manager
-      // The current player color is the opponent's color because it's changed after a valid move
-      .upgrade_piece(piece_index, self.player_color().next(), upgrade_position)
+      .history
+      .first()
+      .map_or(self.chessboard(), |board_manager| {
+        board_manager.chessboard()
+      })
+  }
+
+  pub fn initial_player_color(&self) -> Color {
+    if self.moves.len().is_multiple_of(2) {
+      self.player_color
+    } else {
+      self.player_color.next()
+    }
+  }
+
+  // The game is over once the player to move has no legal move left, or
+  // when a player runs out of time
+  pub fn result(&self) -> Option<GameResult> {
+    let move_generator = MoveGenerator::new(self.chessboard());
+    if move_generator.has_legal_moves(self.player_color) {
+      let flagged = self.clock.as_ref()?.flagged()?;
+      let winner = flagged.next();
+      return Some(match self.chessboard().has_mating_material(winner) {
+        true => GameResult::Timeout { winner },
+        false => GameResult::TimeoutDraw,
+      });
+    }
+
+    if move_generator.is_king_in_check(self.player_color) {
+      Some(GameResult::Checkmate {
+        winner: self.player_color.next(),
+      })
+    } else {
+      Some(GameResult::Stalemate)
+    }
+  }
+
+  // Plays a fully specified move, including the piece to upgrade to
+  pub fn play_move(&mut self, chess_move: Move) -> Result<MoveResult, String> {
+    if !self.legal_moves().contains(&chess_move) {
+      return Err("Illegal move".to_string());
+    }
+
+    let res = self.play(chess_move.from(), chess_move.to())?;
+
+    match chess_move.promotion() {
+      Some(promotion) => {
+        let index = self
+          .chessboard()
+          .dead_pieces(self.player_color.next())
+          .iter()
+          .position(|piece| piece == promotion)
+          .ok_or("Promotion piece is not available")?;
+        self.upgrade_piece(index, chess_move.to())
+      }
+      None => Ok(res),
+    }
+  }
+
+  // Writes the game as PGN, so it can be loaded back to carry on
+  pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    fs::write(path, self.to_pgn()?)
+      .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+  }
+
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+      .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Self::from_pgn(&text)
+      .map_err(|e| format!("Invalid game in {}: {}", path.display(), e))
+  }
+
+  pub fn legal_moves(&self) -> Vec<Move> {
+    MoveGenerator::new(self.chessboard()).legal_moves(self.player_color)
+  }
+
+  pub fn chessboard(&self) -> &Chessboard {
+    self.board_manager.chessboard()
   }
 
   pub fn board_manager(&self) -> &BoardManager {
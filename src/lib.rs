@@ -0,0 +1,20 @@
+pub mod bitbase;
+pub mod board_manager;
+pub mod book;
+pub mod chess_move;
+pub mod chessboard;
+pub mod clock;
+pub mod engine;
+pub mod external_engine;
+pub mod game;
+pub mod move_generator;
+pub mod notation;
+pub mod pieces;
+pub mod presenters;
+pub mod protocols;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod tablebase;
+pub mod ui;
+
+mod tests;
@@ -1,19 +1,229 @@
-mod board_manager;
-mod chessboard;
-mod game;
-mod pieces;
-mod presenters;
-mod tests;
-mod ui;
-
-use game::Game;
-use pieces::types::color::Color;
-use ui::{GameUI, cmd::CmdUI};
+use rust_chess::bitbase::{Bitbases, Endgame};
+use rust_chess::book::OpeningBook;
+use rust_chess::book::builder::{BookBuilder, BookBuilderOptions};
+use rust_chess::clock::Clock;
+use rust_chess::clock::time_control::TimeControl;
+use rust_chess::external_engine::ExternalEngine;
+use rust_chess::game::Game;
+use rust_chess::notation::pgn::PgnResult;
+use rust_chess::pieces::types::color::Color;
+use rust_chess::presenters::style::RenderOptions;
+use rust_chess::protocols::xboard::XBoard;
+use rust_chess::tablebase::Tablebase;
+use rust_chess::ui::tui::TerminalUI;
+use rust_chess::ui::{GameUI, cmd::CmdUI};
+use std::env;
+use std::fs;
+use std::io;
 
 fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.get(1).is_some_and(|arg| arg == "make-book") {
+    if let Err(e) = make_book(&args[2..]) {
+      eprintln!("{}", e);
+    }
+    return;
+  }
+  if args.get(1).is_some_and(|arg| arg == "make-bitbases") {
+    if let Err(e) = make_bitbases(&args[2..]) {
+      eprintln!("{}", e);
+    }
+    return;
+  }
+
+  if env::args().any(|arg| arg == "--xboard") {
+    XBoard::new(io::stdout()).run(io::stdin().lock());
+    return;
+  }
+
+  // --tui plays on a full-screen board instead of the command prompt,
+  // which is the only one with the `book`, `tablebase`, `analyse` and `hint`
+  // commands that use what these flags load
+  let tui = args.iter().any(|arg| arg == "--tui");
+  if tui
+    && let Some(flag) = args
+      .iter()
+      .find(|arg| matches!(arg.as_str(), "--book" | "--syzygy" | "--bitbases"))
+  {
+    eprintln!(
+      "{} is for the command prompt and can't be used with --tui",
+      flag
+    );
+    return;
+  }
+
   let mut game = Game::new(Color::White);
 
-  // For now, always use CLI
-  let mut ui = CmdUI;
+  // --engine <path> lets an external UCI engine play Black
+  let external_engine = match args.iter().position(|arg| arg == "--engine") {
+    Some(index) => {
+      let Some(path) = args.get(index + 1) else {
+        eprintln!("Missing engine path after --engine");
+        return;
+      };
+      match ExternalEngine::spawn(path) {
+        Ok(external_engine) => Some(external_engine),
+        Err(e) => {
+          eprintln!("{}", e);
+          return;
+        }
+      }
+    }
+    None => None,
+  };
+
+  // --clock <control> plays on a clock, like 5+3 or 40/90+30:30+30
+  if let Some(index) = args.iter().position(|arg| arg == "--clock") {
+    let Some(text) = args.get(index + 1) else {
+      eprintln!("Missing time control after --clock");
+      return;
+    };
+    match TimeControl::parse(text) {
+      Ok(time_control) => game.set_clock(Some(Clock::new(time_control))),
+      Err(e) => {
+        eprintln!("{}", e);
+        return;
+      }
+    }
+  }
+
+  // --unicode, --color, --no-color and --ascii pick how the board is drawn
+  let render_options = RenderOptions::from_args(&args);
+
+  if tui {
+    let mut ui = match external_engine {
+      Some(external_engine) => {
+        TerminalUI::with_external_engine(external_engine)
+      }
+      None => TerminalUI::new(),
+    };
+    ui.set_render_options(render_options);
+    ui.start_game_loop(&mut game);
+    return;
+  }
+
+  let mut ui = match external_engine {
+    Some(external_engine) => CmdUI::with_external_engine(external_engine),
+    None => CmdUI::new(),
+  };
+  ui.set_render_options(render_options);
+
+  // --book <path> loads a Polyglot opening book for the `book` command
+  if let Some(index) = args.iter().position(|arg| arg == "--book") {
+    let Some(path) = args.get(index + 1) else {
+      eprintln!("Missing book path after --book");
+      return;
+    };
+    match OpeningBook::open(path) {
+      Ok(book) => ui.set_book(book),
+      Err(e) => {
+        eprintln!("{}", e);
+        return;
+      }
+    }
+  }
+
+  // --syzygy <dir> loads Syzygy tablebases for the `tablebase` command,
+  // analysis and hints
+  if let Some(index) = args.iter().position(|arg| arg == "--syzygy") {
+    let Some(path) = args.get(index + 1) else {
+      eprintln!("Missing tablebase directory after --syzygy");
+      return;
+    };
+    match Tablebase::open(path) {
+      Ok(tablebase) => ui.set_tablebase(tablebase),
+      Err(e) => {
+        eprintln!("{}", e);
+        return;
+      }
+    }
+  }
+
+  // --bitbases <file> loads the bitbases written by make-bitbases for
+  // analysis and hints
+  if let Some(index) = args.iter().position(|arg| arg == "--bitbases") {
+    let Some(path) = args.get(index + 1) else {
+      eprintln!("Missing bitbase file after --bitbases");
+      return;
+    };
+    match Bitbases::open(path) {
+      Ok(bitbases) => ui.set_bitbases(bitbases),
+      Err(e) => {
+        eprintln!("{}", e);
+        return;
+      }
+    }
+  }
+
   ui.start_game_loop(&mut game);
 }
+
+// make-book <games.pgn> <book.bin> [--max-ply N] [--min-games N]
+//           [--results 1-0,0-1,1/2-1/2]
+fn make_book(args: &[String]) -> Result<(), String> {
+  let usage = "Usage: rust_chess make-book <games.pgn> <book.bin> \
+               [--max-ply N] [--min-games N] [--results 1-0,0-1,1/2-1/2]";
+  let [pgn_path, book_path, flags @ ..] = args else {
+    return Err(usage.to_string());
+  };
+
+  let mut options = BookBuilderOptions::default();
+  let mut flags = flags.iter();
+  while let Some(flag) = flags.next() {
+    let value = flags
+      .next()
+      .ok_or(format!("Missing value after {}", flag))?;
+    let invalid = || format!("Invalid value for {}: {}", flag, value);
+    match flag.as_str() {
+      "--max-ply" => options.max_ply = value.parse().map_err(|_| invalid())?,
+      "--min-games" => {
+        options.min_games = value.parse().map_err(|_| invalid())?
+      }
+      "--results" => {
+        options.results = value
+          .split(',')
+          .map(|token| match PgnResult::from_token(token) {
+            Some(PgnResult::Unknown) | None => Err(invalid()),
+            Some(result) => Ok(result),
+          })
+          .collect::<Result<_, _>>()?
+      }
+      _ => return Err(format!("Unknown option: {}\n{}", flag, usage)),
+    }
+  }
+
+  let text = fs::read_to_string(pgn_path)
+    .map_err(|e| format!("Failed to read {}: {}", pgn_path, e))?;
+  let mut builder = BookBuilder::new(options);
+  builder.add_pgn(&text)?;
+  builder.write(book_path)?;
+
+  let entries = OpeningBook::open(book_path)?.len();
+  println!(
+    "Wrote {} entries for {} positions from {} games to {}",
+    entries,
+    builder.positions(),
+    builder.games(),
+    book_path
+  );
+  Ok(())
+}
+
+// make-bitbases <bitbases.bin>
+fn make_bitbases(args: &[String]) -> Result<(), String> {
+  let [path] = args else {
+    return Err("Usage: rust_chess make-bitbases <bitbases.bin>".to_string());
+  };
+
+  let bitbases = Bitbases::generate();
+  bitbases.write(path)?;
+  for endgame in Endgame::ALL {
+    println!(
+      "{}: {} won positions",
+      endgame.name(),
+      bitbases.get(endgame).count_wins()
+    );
+  }
+  println!("Wrote {}", path);
+  Ok(())
+}
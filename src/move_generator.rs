@@ -0,0 +1,257 @@
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::pieces::piece::Piece;
+use crate::pieces::traits::Movable;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::move_direction::{Direction, MovementPattern};
+use crate::pieces::types::position::Position;
+
+pub struct MoveGenerator<'a> {
+  chessboard: &'a Chessboard,
+}
+
+impl<'a> MoveGenerator<'a> {
+  pub fn new(chessboard: &'a Chessboard) -> Self {
+    MoveGenerator { chessboard }
+  }
+
+  // Moves that don't leave the mover's own king attacked
+  pub fn legal_moves(&self, color: Color) -> Vec<Move> {
+    self
+      .pseudo_legal_moves(color)
+      .into_iter()
+      .filter(|chess_move| self.is_legal(chess_move, color))
+      .collect()
+  }
+
+  pub fn has_legal_moves(&self, color: Color) -> bool {
+    self
+      .pseudo_legal_moves(color)
+      .iter()
+      .any(|chess_move| self.is_legal(chess_move, color))
+  }
+
+  pub fn pseudo_legal_moves(&self, color: Color) -> Vec<Move> {
+    let mut moves = Vec::new();
+
+    for position in self.chessboard.get_all_positions() {
+      if let Some(piece) = self.chessboard.get_piece(position)
+        && piece.is_of_color(color)
+      {
+        self.add_piece_moves(position, piece, &mut moves);
+      }
+    }
+
+    moves
+  }
+
+  pub fn is_king_in_check(&self, color: Color) -> bool {
+    match self.chessboard.get_king_position(color) {
+      Some(king_position) => {
+        self.is_square_attacked(king_position, color.next())
+      }
+      None => false,
+    }
+  }
+
+  // Moves that capture an enemy piece, for searches that only look at
+  // captures
+  pub fn legal_captures(&self, color: Color) -> Vec<Move> {
+    self
+      .pseudo_legal_moves(color)
+      .into_iter()
+      .filter(|chess_move| !self.chessboard.is_position_empty(chess_move.to()))
+      .filter(|chess_move| self.is_legal(chess_move, color))
+      .collect()
+  }
+
+  // Positions of the pieces of `by_color` attacking the target square
+  pub fn attackers(&self, target: Position, by_color: Color) -> Vec<Position> {
+    self
+      .chessboard
+      .get_all_positions()
+      .into_iter()
+      .filter(|position| match self.chessboard.get_piece(*position) {
+        Some(piece) if piece.is_of_color(by_color) => {
+          self.attacks(*position, piece, target)
+        }
+        _ => false,
+      })
+      .collect()
+  }
+
+  pub fn is_square_attacked(&self, target: Position, by_color: Color) -> bool {
+    self
+      .chessboard
+      .get_all_positions()
+      .into_iter()
+      .any(|position| match self.chessboard.get_piece(position) {
+        Some(piece) if piece.is_of_color(by_color) => {
+          self.attacks(position, piece, target)
+        }
+        _ => false,
+      })
+  }
+
+  fn is_legal(&self, chess_move: &Move, color: Color) -> bool {
+    let mut chessboard = self.chessboard.clone();
+    if chessboard.apply_move(chess_move).is_err() {
+      return false;
+    }
+    !MoveGenerator::new(&chessboard).is_king_in_check(color)
+  }
+
+  fn attacks(
+    &self,
+    position: Position,
+    piece: &Piece,
+    target: Position,
+  ) -> bool {
+    if let Piece::Pawn(pawn) = piece {
+      return pawn
+        .capture_directions()
+        .into_iter()
+        .any(|direction| position + direction.to_offset() == Some(target));
+    }
+
+    match piece.movement_pattern(position) {
+      MovementPattern::AppliableOnce(directions)
+      | MovementPattern::AppliableTwice(directions) => directions
+        .into_iter()
+        .any(|direction| position + direction.to_offset() == Some(target)),
+      MovementPattern::AppliableMultiple(directions) => {
+        directions.into_iter().any(|direction| {
+          let mut current = position;
+          while let Some(next) = current + direction.to_offset() {
+            if next == target {
+              return true;
+            }
+            if !self.chessboard.is_position_empty(next) {
+              return false;
+            }
+            current = next;
+          }
+          false
+        })
+      }
+    }
+  }
+
+  fn add_piece_moves(
+    &self,
+    position: Position,
+    piece: &Piece,
+    moves: &mut Vec<Move>,
+  ) {
+    if let Piece::Pawn(pawn) = piece {
+      self.add_pawn_moves(position, piece, pawn.capture_directions(), moves);
+      return;
+    }
+
+    let color = *piece.color();
+    match piece.movement_pattern(position) {
+      MovementPattern::AppliableOnce(directions)
+      | MovementPattern::AppliableTwice(directions) => {
+        for direction in directions {
+          if let Some(target) = position + direction.to_offset()
+            && self.can_land_on(target, color)
+          {
+            moves.push(Move::new(position, target));
+          }
+        }
+      }
+      MovementPattern::AppliableMultiple(directions) => {
+        for direction in directions {
+          let mut current = position;
+          while let Some(next) = current + direction.to_offset() {
+            if !self.can_land_on(next, color) {
+              break;
+            }
+            moves.push(Move::new(position, next));
+            if !self.chessboard.is_position_empty(next) {
+              break;
+            }
+            current = next;
+          }
+        }
+      }
+    }
+  }
+
+  fn add_pawn_moves(
+    &self,
+    position: Position,
+    piece: &Piece,
+    capture_directions: Vec<Direction>,
+    moves: &mut Vec<Move>,
+  ) {
+    let color = *piece.color();
+
+    // Pawns only step forward onto empty squares
+    let (directions, steps) = match piece.movement_pattern(position) {
+      MovementPattern::AppliableTwice(directions) => (directions, 2),
+      MovementPattern::AppliableOnce(directions)
+      | MovementPattern::AppliableMultiple(directions) => (directions, 1),
+    };
+    for direction in directions {
+      let mut current = position;
+      for _ in 0..steps {
+        match current + direction.to_offset() {
+          Some(next) if self.chessboard.is_position_empty(next) => {
+            self.add_pawn_move(position, next, color, moves);
+            current = next;
+          }
+          _ => break,
+        }
+      }
+    }
+
+    // And capture diagonally
+    for direction in capture_directions {
+      if let Some(target) = position + direction.to_offset()
+        && let Some(target_piece) = self.chessboard.get_piece(target)
+        && !target_piece.is_of_color(color)
+      {
+        self.add_pawn_move(position, target, color, moves);
+      }
+    }
+  }
+
+  // A pawn reaching the last row is upgraded to one of its side's dead
+  // pieces, so there is one move per distinct dead piece available
+  fn add_pawn_move(
+    &self,
+    from: Position,
+    to: Position,
+    color: Color,
+    moves: &mut Vec<Move>,
+  ) {
+    let reaches_last_row = match self.chessboard.get_piece(from) {
+      Some(Piece::Pawn(pawn)) => pawn.can_upgrade(to),
+      _ => false,
+    };
+
+    let dead_pieces = self.chessboard.dead_pieces(color);
+    if !reaches_last_row || dead_pieces.is_empty() {
+      moves.push(Move::new(from, to));
+      return;
+    }
+
+    let mut promotions: Vec<Piece> = Vec::new();
+    for piece in dead_pieces {
+      if !promotions.contains(piece) {
+        promotions.push(*piece);
+      }
+    }
+    for piece in promotions {
+      moves.push(Move::with_promotion(from, to, piece));
+    }
+  }
+
+  fn can_land_on(&self, target: Position, color: Color) -> bool {
+    match self.chessboard.get_piece(target) {
+      Some(piece) => !piece.is_of_color(color),
+      None => true,
+    }
+  }
+}
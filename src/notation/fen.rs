@@ -0,0 +1,134 @@
+use crate::chessboard::{Chessboard, ChessboardType};
+use crate::game::Game;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use std::array::from_fn;
+
+pub const STARTING_FEN: &str =
+  "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+
+// Number of pieces of each kind a side starts with, by FEN letter
+const STARTING_MATERIAL: [(char, usize); 6] =
+  [('p', 8), ('n', 2), ('b', 2), ('r', 2), ('q', 1), ('k', 1)];
+
+impl Chessboard {
+  // Builds a board from the piece placement field of a FEN string. Pieces
+  // missing from the standard set are considered dead, so they can be used
+  // for upgrades.
+  pub fn from_fen_placement(placement: &str) -> Result<Self, String> {
+    let rows: Vec<&str> = placement.split('/').collect();
+    if rows.len() != BOARD_SIZE {
+      return Err(format!("Invalid FEN placement: {}", placement));
+    }
+
+    let mut board: ChessboardType = from_fn(|_| from_fn(|_| None));
+
+    // FEN lists the 8th rank first
+    for (i, row) in rows.iter().enumerate() {
+      let x = BOARD_SIZE - 1 - i;
+      let mut y = 0;
+      for symbol in row.chars() {
+        if let Some(empty_squares) = symbol.to_digit(10) {
+          y += empty_squares as usize;
+          continue;
+        }
+        if y >= BOARD_SIZE {
+          return Err(format!("Invalid FEN row: {}", row));
+        }
+        board[x][y] = Some(
+          Piece::from_symbol(symbol)
+            .ok_or(format!("Invalid FEN piece: {}", symbol))?,
+        );
+        y += 1;
+      }
+      if y != BOARD_SIZE {
+        return Err(format!("Invalid FEN row: {}", row));
+      }
+    }
+
+    let white_dead_pieces = Self::missing_pieces(&board, Color::White);
+    let black_dead_pieces = Self::missing_pieces(&board, Color::Black);
+
+    Ok(Chessboard::new(board, white_dead_pieces, black_dead_pieces))
+  }
+
+  pub fn to_fen_placement(&self) -> String {
+    let mut rows = Vec::new();
+
+    for x in (0..BOARD_SIZE).rev() {
+      let mut row = String::new();
+      let mut empty_squares = 0;
+      for y in 0..BOARD_SIZE {
+        match self.get_piece(Position::new(x, y).unwrap()) {
+          Some(piece) => {
+            if empty_squares > 0 {
+              row.push_str(&empty_squares.to_string());
+              empty_squares = 0;
+            }
+            row.push(piece.symbol());
+          }
+          None => empty_squares += 1,
+        }
+      }
+      if empty_squares > 0 {
+        row.push_str(&empty_squares.to_string());
+      }
+      rows.push(row);
+    }
+
+    rows.join("/")
+  }
+
+  fn missing_pieces(board: &ChessboardType, color: Color) -> Vec<Piece> {
+    let mut missing = Vec::new();
+
+    for (symbol, count) in STARTING_MATERIAL {
+      let piece = Piece::from_kind_symbol(symbol, color).unwrap();
+      let on_board = board.iter().flatten().flatten().filter(|p| **p == piece);
+      for _ in on_board.count()..count {
+        missing.push(piece);
+      }
+    }
+
+    missing
+  }
+}
+
+impl Game {
+  // Castling and en passant fields are accepted but ignored, as the game
+  // doesn't implement those rules
+  pub fn from_fen(fen: &str) -> Result<Self, String> {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    if fields.is_empty() {
+      return Err("Empty FEN".to_string());
+    }
+
+    let chessboard = Chessboard::from_fen_placement(fields[0])?;
+    let player_color = match fields.get(1) {
+      Some(&"w") | None => Color::White,
+      Some(&"b") => Color::Black,
+      Some(side) => return Err(format!("Invalid FEN side to move: {}", side)),
+    };
+
+    Ok(Game::with_chessboard(chessboard, player_color))
+  }
+
+  pub fn to_fen(&self) -> String {
+    Self::format_fen(self.chessboard(), self.player_color())
+  }
+
+  // FEN of the position the game started from
+  pub fn initial_fen(&self) -> String {
+    Self::format_fen(self.initial_chessboard(), self.initial_player_color())
+  }
+
+  fn format_fen(chessboard: &Chessboard, player_color: Color) -> String {
+    let side = match player_color {
+      Color::White => "w",
+      Color::Black => "b",
+    };
+    format!("{} {} - - 0 1", chessboard.to_fen_placement(), side)
+  }
+}
@@ -0,0 +1,3 @@
+pub mod fen;
+pub mod pgn;
+pub mod san;
@@ -0,0 +1,388 @@
+use crate::chess_move::Move;
+use crate::clock::Clock;
+use crate::clock::time_control::TimeControl;
+use crate::game::{Game, GameResult};
+use crate::notation::fen::STARTING_FEN;
+use crate::pieces::types::color::Color;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PgnResult {
+  WhiteWins,
+  BlackWins,
+  Draw,
+  // Game still going or result not known
+  Unknown,
+}
+
+impl PgnResult {
+  pub fn from_token(token: &str) -> Option<Self> {
+    match token {
+      "1-0" => Some(PgnResult::WhiteWins),
+      "0-1" => Some(PgnResult::BlackWins),
+      "1/2-1/2" => Some(PgnResult::Draw),
+      "*" => Some(PgnResult::Unknown),
+      _ => None,
+    }
+  }
+
+  pub fn to_token(self) -> &'static str {
+    match self {
+      PgnResult::WhiteWins => "1-0",
+      PgnResult::BlackWins => "0-1",
+      PgnResult::Draw => "1/2-1/2",
+      PgnResult::Unknown => "*",
+    }
+  }
+}
+
+// One game of a PGN file: its tag pairs and main line, with comments and
+// variations left out
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+  pub tags: Vec<(String, String)>,
+  // Moves in Standard Algebraic Notation
+  pub moves: Vec<String>,
+  pub result: PgnResult,
+}
+
+impl PgnGame {
+  pub fn tag(&self, name: &str) -> Option<&str> {
+    self
+      .tags
+      .iter()
+      .find(|(tag, _)| tag == name)
+      .map(|(_, value)| value.as_str())
+  }
+
+  // Position the game starts from, which the FEN tag can change
+  pub fn initial_game(&self) -> Result<Game, String> {
+    match self.tag("FEN") {
+      Some(fen) if fen != STARTING_FEN => Game::from_fen(fen),
+      _ => Ok(Game::new(Color::White)),
+    }
+  }
+
+  // Plays the moves one by one, handing over the game before each move
+  // and the move about to be played, until `on_move` returns false. Stops
+  // at the first move that can't be played here, e.g. castling, and
+  // returns the game as it is then.
+  pub fn replay(
+    &self,
+    mut on_move: impl FnMut(&Game, &Move) -> bool,
+  ) -> Result<Game, String> {
+    let mut game = self.initial_game()?;
+
+    for san in &self.moves {
+      let Ok(chess_move) = game.parse_san(san) else {
+        break;
+      };
+      if !on_move(&game, &chess_move) {
+        break;
+      }
+      game.play_move(chess_move)?;
+    }
+
+    Ok(game)
+  }
+}
+
+// Movetext lines are kept under this width, as PGN export asks
+const LINE_WIDTH: usize = 79;
+
+impl Game {
+  // The game as PGN: the Seven Tag Roster, the starting position when it
+  // isn't the standard one, the time control and time left on the clock
+  // if there is one, and the moves played in SAN
+  pub fn to_pgn(&self) -> Result<String, String> {
+    let result = match self.result() {
+      Some(GameResult::Checkmate {
+        winner: Color::White,
+      })
+      | Some(GameResult::Timeout {
+        winner: Color::White,
+      }) => PgnResult::WhiteWins,
+      Some(GameResult::Checkmate {
+        winner: Color::Black,
+      })
+      | Some(GameResult::Timeout {
+        winner: Color::Black,
+      }) => PgnResult::BlackWins,
+      Some(GameResult::Stalemate | GameResult::TimeoutDraw) => PgnResult::Draw,
+      None => PgnResult::Unknown,
+    };
+
+    let mut tags = vec![
+      ("Event", "?".to_string()),
+      ("Site", "?".to_string()),
+      ("Date", "????.??.??".to_string()),
+      ("Round", "?".to_string()),
+      ("White", "?".to_string()),
+      ("Black", "?".to_string()),
+      ("Result", result.to_token().to_string()),
+    ];
+    let initial_fen = self.initial_fen();
+    if initial_fen != STARTING_FEN {
+      tags.push(("SetUp", "1".to_string()));
+      tags.push(("FEN", initial_fen));
+    }
+    if let Some(clock) = self.clock() {
+      tags.push(("TimeControl", clock.time_control().to_pgn_tag()));
+      tags.push(("WhiteClock", clock_tag(clock.remaining(Color::White))));
+      tags.push(("BlackClock", clock_tag(clock.remaining(Color::Black))));
+    }
+    let mut pgn: String = tags
+      .iter()
+      .map(|(name, value)| {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("[{} \"{}\"]\n", name, value)
+      })
+      .collect();
+    pgn.push('\n');
+
+    let mut tokens = Vec::new();
+    let first_ply = first_ply(self.initial_player_color());
+    for (ply, san) in self.san_moves()?.into_iter().enumerate() {
+      let number = (first_ply + ply) / 2 + 1;
+      if (first_ply + ply).is_multiple_of(2) {
+        tokens.push(format!("{}.", number));
+      } else if ply == 0 {
+        tokens.push(format!("{}...", number));
+      }
+      tokens.push(san);
+    }
+    tokens.push(result.to_token().to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+      if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+        pgn.push_str(&line);
+        pgn.push('\n');
+        line.clear();
+      }
+      if !line.is_empty() {
+        line.push(' ');
+      }
+      line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+    Ok(pgn)
+  }
+
+  // Reads back a single game, failing on the first move that can't be
+  // played rather than stopping there like `PgnGame::replay`
+  pub fn from_pgn(text: &str) -> Result<Self, String> {
+    let games = parse_pgn(text)?;
+    let [pgn_game] = games.as_slice() else {
+      return Err(format!("Expected one game, found {}", games.len()));
+    };
+
+    let mut game = pgn_game.initial_game()?;
+    let initial_player_color = game.player_color();
+    let first_ply = first_ply(initial_player_color);
+    for (ply, san) in pgn_game.moves.iter().enumerate() {
+      let number = (first_ply + ply) / 2 + 1;
+      let number = match game.player_color() {
+        Color::White => format!("{}.", number),
+        Color::Black => format!("{}...", number),
+      };
+      game
+        .parse_san(san)
+        .and_then(|chess_move| game.play_move(chess_move))
+        .map_err(|e| format!("Move {} {}: {}", number, san, e))?;
+    }
+
+    // The clock only comes back with the time each side had left. "-" is a
+    // game without a time control, and "?" one whose time control is unknown.
+    let time_control = pgn_game
+      .tag("TimeControl")
+      .filter(|tag| !matches!(*tag, "-" | "?"));
+    if let (Some(time_control), Some(white), Some(black)) = (
+      time_control,
+      pgn_game.tag("WhiteClock"),
+      pgn_game.tag("BlackClock"),
+    ) {
+      let time_control = TimeControl::from_pgn_tag(time_control)?;
+      let [white, black] = [white, black].map(|clock| {
+        parse_clock_tag(clock).ok_or(format!("Invalid clock time: {}", clock))
+      });
+      // Each side's moves, the one to move first having made the odd one
+      let plies = pgn_game.moves.len() as u32;
+      let moves = match initial_player_color {
+        Color::White => [plies.div_ceil(2), plies / 2],
+        Color::Black => [plies / 2, plies.div_ceil(2)],
+      };
+      let clock = Clock::resume(time_control, [white?, black?], moves);
+      game.set_clock(Some(clock));
+    }
+    Ok(game)
+  }
+}
+
+// Remaining time in the clock tags: "1:05:00", or "0:00:09.400" with the
+// milliseconds once there are some
+fn clock_tag(remaining: Duration) -> String {
+  let seconds = remaining.as_secs();
+  let mut tag = format!(
+    "{}:{:02}:{:02}",
+    seconds / 3600,
+    seconds / 60 % 60,
+    seconds % 60
+  );
+  if remaining.subsec_millis() > 0 {
+    tag.push_str(&format!(".{:03}", remaining.subsec_millis()));
+  }
+  tag
+}
+
+fn parse_clock_tag(tag: &str) -> Option<Duration> {
+  let [hours, minutes, seconds] =
+    tag.split(':').collect::<Vec<_>>().try_into().ok()?;
+  let minutes =
+    hours.parse::<u64>().ok()? * 60 + minutes.parse::<u64>().ok()?;
+  let seconds = seconds.parse::<f64>().ok()?;
+  Duration::try_from_secs_f64(minutes as f64 * 60.0 + seconds).ok()
+}
+
+// Move numbers count from White's first move, so a game where Black moves
+// first starts one ply in
+fn first_ply(player_color: Color) -> usize {
+  usize::from(player_color == Color::Black)
+}
+
+// Reads every game of a PGN file
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, String> {
+  let mut games = Vec::new();
+  let mut game = new_game();
+  let mut has_movetext = false;
+  let mut chars = text.chars().peekable();
+
+  while let Some(symbol) = chars.next() {
+    match symbol {
+      '[' => {
+        // Tags after moves start the next game, even without a result
+        if has_movetext {
+          games.push(result_from_tag(game));
+          game = new_game();
+          has_movetext = false;
+        }
+
+        game.tags.push(parse_tag(&mut chars)?);
+      }
+      '{' => {
+        if !chars.by_ref().any(|c| c == '}') {
+          return Err("Unterminated PGN comment".to_string());
+        }
+      }
+      ';' => {
+        chars.by_ref().find(|c| *c == '\n');
+      }
+      '(' => {
+        // Variations can nest, and hold comments with parentheses in them
+        let mut depth = 1;
+        while depth > 0 {
+          match chars.next() {
+            Some('(') => depth += 1,
+            Some(')') => depth -= 1,
+            Some('{') => {
+              chars.by_ref().find(|c| *c == '}');
+            }
+            Some(_) => (),
+            None => return Err("Unterminated PGN variation".to_string()),
+          }
+        }
+      }
+      symbol if symbol.is_whitespace() => (),
+      symbol => {
+        let mut token = symbol.to_string();
+        while let Some(next) = chars.peek() {
+          if next.is_whitespace() || matches!(next, '{' | '(' | ')' | ';') {
+            break;
+          }
+          token.push(chars.next().unwrap());
+        }
+
+        if let Some(result) = PgnResult::from_token(&token) {
+          game.result = result;
+          games.push(game);
+          game = new_game();
+          has_movetext = false;
+          continue;
+        }
+
+        has_movetext = true;
+        // Annotation glyphs ("$1") and move numbers ("12." or "12...")
+        if token.starts_with('$') {
+          continue;
+        }
+        let san = match token.rfind('.') {
+          Some(index) => &token[index + 1..],
+          None => &token,
+        };
+        if !san.is_empty() {
+          game.moves.push(san.to_string());
+        }
+      }
+    }
+  }
+
+  if has_movetext || !game.tags.is_empty() {
+    games.push(result_from_tag(game));
+  }
+  Ok(games)
+}
+
+// Reads a tag after its opening bracket: a name, then a quoted value in
+// which a backslash escapes the next character, so that quotes and
+// brackets can be part of it
+fn parse_tag(chars: &mut Peekable<Chars>) -> Result<(String, String), String> {
+  let skip_whitespace = |chars: &mut Peekable<Chars>| {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+  };
+
+  skip_whitespace(chars);
+  let mut name = String::new();
+  while let Some(c) =
+    chars.next_if(|c| !c.is_whitespace() && !matches!(c, '"' | ']'))
+  {
+    name.push(c);
+  }
+  skip_whitespace(chars);
+  if name.is_empty() || chars.next() != Some('"') {
+    return Err(format!("Invalid PGN tag: [{}", name));
+  }
+
+  let mut value = String::new();
+  loop {
+    match chars.next() {
+      Some('"') => break,
+      Some('\\') => value.extend(chars.next()),
+      Some(c) => value.push(c),
+      None => return Err(format!("Unterminated PGN tag: [{}", name)),
+    }
+  }
+  skip_whitespace(chars);
+  if chars.next() != Some(']') {
+    return Err(format!("Invalid PGN tag: [{} \"{}\"", name, value));
+  }
+  Ok((name, value))
+}
+
+// For games whose movetext doesn't end with a result
+fn result_from_tag(mut game: PgnGame) -> PgnGame {
+  if let Some(result) = game.tag("Result").and_then(PgnResult::from_token) {
+    game.result = result;
+  }
+  game
+}
+
+fn new_game() -> PgnGame {
+  PgnGame {
+    tags: Vec::new(),
+    moves: Vec::new(),
+    result: PgnResult::Unknown,
+  }
+}
@@ -0,0 +1,160 @@
+use crate::chess_move::Move;
+use crate::game::Game;
+use crate::move_generator::MoveGenerator;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::position::Position;
+
+impl Game {
+  // Reads a move in Standard Algebraic Notation (e.g. "Nf3", "exd5",
+  // "R1e2", "e8=Q+") for the player to move
+  pub fn parse_san(&self, san: &str) -> Result<Move, String> {
+    let invalid = || format!("Invalid move: {}", san);
+
+    let text = san.trim_end_matches(['+', '#', '!', '?']);
+    if matches!(text, "O-O" | "O-O-O" | "0-0" | "0-0-0") {
+      return Err(format!("Castling is not supported: {}", san));
+    }
+    if !text.is_ascii() {
+      return Err(invalid());
+    }
+
+    // Piece letter, then disambiguation and capture mark, then the target
+    // square and the piece a pawn is upgraded to
+    let (kind, rest) = match text.chars().next() {
+      Some(letter @ ('N' | 'B' | 'R' | 'Q' | 'K')) => (letter, &text[1..]),
+      _ => ('P', text),
+    };
+    let (rest, promotion) = match rest.chars().last() {
+      Some(letter @ ('P' | 'N' | 'B' | 'R' | 'Q')) if kind == 'P' => {
+        (rest[..rest.len() - 1].trim_end_matches('='), Some(letter))
+      }
+      _ => (rest, None),
+    };
+    if rest.len() < 2 {
+      return Err(invalid());
+    }
+
+    let (hint, target) = rest.split_at(rest.len() - 2);
+    let target = Position::from_algebraic(target).map_err(|_| invalid())?;
+
+    // What's left says which file and/or row the piece comes from
+    let mut from_file = None;
+    let mut from_row = None;
+    for symbol in hint.trim_end_matches('x').chars() {
+      match symbol {
+        'a'..='h' => from_file = Some(symbol as usize - 'a' as usize),
+        '1'..='8' => from_row = Some(symbol as usize - '1' as usize),
+        _ => return Err(invalid()),
+      }
+    }
+
+    let candidates: Vec<Move> = self
+      .legal_moves()
+      .into_iter()
+      .filter(|chess_move| {
+        let piece = self.chessboard().get_piece(chess_move.from()).unwrap();
+        let move_promotion = chess_move
+          .promotion()
+          .map(|piece| piece.symbol().to_ascii_uppercase());
+
+        chess_move.to() == target
+          && piece.symbol().to_ascii_uppercase() == kind
+          && move_promotion == promotion
+          && from_file.is_none_or(|file| chess_move.from().y() == file)
+          && from_row.is_none_or(|row| chess_move.from().x() == row)
+      })
+      .collect();
+
+    match candidates.as_slice() {
+      [chess_move] => Ok(*chess_move),
+      [] => Err(format!("Illegal move: {}", san)),
+      _ => Err(format!("Ambiguous move: {}", san)),
+    }
+  }
+
+  // The moves played so far in SAN, each written from the position it was
+  // played in. A pawn still waiting for its upgrade isn't listed yet.
+  pub fn san_moves(&self) -> Result<Vec<String>, String> {
+    let mut replay = Game::with_chessboard(
+      self.initial_chessboard().clone(),
+      self.initial_player_color(),
+    );
+    let pending = self.pending_upgrade().is_some() as usize;
+    let played = self.moves().len() - pending;
+    let mut sans = Vec::with_capacity(played);
+    for chess_move in &self.moves()[..played] {
+      let san = replay.to_san(chess_move);
+      replay
+        .play_move(*chess_move)
+        .map_err(|e| format!("Move {}: {}", san, e))?;
+      sans.push(san);
+    }
+    Ok(sans)
+  }
+
+  // Writes a legal move of the player to move in Standard Algebraic
+  // Notation, with the file or row of the moving piece only when another
+  // piece of the same kind could go to the same square
+  pub fn to_san(&self, chess_move: &Move) -> String {
+    let chessboard = self.chessboard();
+    let Some(piece) = chessboard.get_piece(chess_move.from()) else {
+      return chess_move.to_long_algebraic();
+    };
+    let from = chess_move.from();
+    let target = chess_move.to();
+    let capture = !chessboard.is_position_empty(target);
+
+    let mut san = String::new();
+    if let Piece::Pawn(_) = piece {
+      if capture {
+        san.push((b'a' + from.y() as u8) as char);
+      }
+    } else {
+      san.push(piece.symbol().to_ascii_uppercase());
+
+      let rivals: Vec<Position> = self
+        .legal_moves()
+        .into_iter()
+        .filter(|other| {
+          other.to() == target
+            && other.from() != from
+            && chessboard.get_piece(other.from()) == Some(piece)
+        })
+        .map(|other| other.from())
+        .collect();
+      let file = (b'a' + from.y() as u8) as char;
+      let row = (b'1' + from.x() as u8) as char;
+      if rivals.iter().any(|rival| rival.y() == from.y()) {
+        if rivals.iter().any(|rival| rival.x() == from.x()) {
+          san.push(file);
+        }
+        san.push(row);
+      } else if !rivals.is_empty() {
+        san.push(file);
+      }
+    }
+
+    if capture {
+      san.push('x');
+    }
+    san.push_str(&target.to_algebraic());
+    if let Some(promotion) = chess_move.promotion() {
+      san.push('=');
+      san.push(promotion.symbol().to_ascii_uppercase());
+    }
+
+    let mut child = chessboard.clone();
+    if child.apply_move(chess_move).is_ok() {
+      let opponent = self.player_color().next();
+      let move_generator = MoveGenerator::new(&child);
+      if move_generator.is_king_in_check(opponent) {
+        san.push(if move_generator.has_legal_moves(opponent) {
+          '+'
+        } else {
+          '#'
+        });
+      }
+    }
+    san
+  }
+}
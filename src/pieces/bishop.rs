@@ -3,7 +3,7 @@ use crate::pieces::types::color::Color;
 use crate::pieces::types::move_direction::{Direction, MovementPattern};
 use crate::pieces::types::position::Position;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Bishop {
   color: Color,
 }
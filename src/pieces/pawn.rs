@@ -13,7 +13,7 @@ const PAWN_X_START_POSITIONS: [usize; 2] = [
 const WHITE_PAWN_UPGRADE_X_POSITION: usize = 7;
 const BLACK_PAWN_UPGRADE_X_POSITION: usize = 0;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Pawn {
   color: Color,
 }
@@ -24,12 +24,7 @@ impl Pawn {
   }
 
   fn get_en_passant_movement_pattern(&self) -> MovementPattern {
-    let movement_directions = match self.color {
-      Color::White => vec![Direction::DownLeft, Direction::DownRight],
-      Color::Black => vec![Direction::UpLeft, Direction::UpRight],
-    };
-
-    MovementPattern::new_appliable_once(movement_directions)
+    MovementPattern::new_appliable_once(self.capture_directions())
   }
 }
 
@@ -38,6 +33,14 @@ impl Pawn {
     &self.color
   }
 
+  // Diagonal directions the pawn captures in
+  pub fn capture_directions(&self) -> Vec<Direction> {
+    match self.color {
+      Color::White => vec![Direction::DownLeft, Direction::DownRight],
+      Color::Black => vec![Direction::UpLeft, Direction::UpRight],
+    }
+  }
+
   pub fn can_upgrade(&self, current_position: Position) -> bool {
     match self.color {
       Color::White => current_position.x() == WHITE_PAWN_UPGRADE_X_POSITION,
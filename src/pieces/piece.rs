@@ -3,6 +3,15 @@ use crate::pieces::types::move_direction::{MovementPattern, SpecialMove};
 use crate::pieces::types::position::Position;
 use crate::pieces::{Bishop, King, Knight, Movable, Pawn, Queen, Rook};
 
+// Centipawn values used by the evaluation and move ordering
+pub const PAWN_VALUE: i32 = 100;
+pub const KNIGHT_VALUE: i32 = 320;
+pub const BISHOP_VALUE: i32 = 330;
+pub const ROOK_VALUE: i32 = 500;
+pub const QUEEN_VALUE: i32 = 900;
+pub const KING_VALUE: i32 = 20_000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Piece {
   Pawn(Pawn),
   Knight(Knight),
@@ -27,6 +36,57 @@ impl Piece {
   pub fn is_of_color(&self, color: Color) -> bool {
     self.color() == &color
   }
+
+  pub fn value(&self) -> i32 {
+    match self {
+      Piece::Pawn(_) => PAWN_VALUE,
+      Piece::Knight(_) => KNIGHT_VALUE,
+      Piece::Bishop(_) => BISHOP_VALUE,
+      Piece::Rook(_) => ROOK_VALUE,
+      Piece::Queen(_) => QUEEN_VALUE,
+      Piece::King(_) => KING_VALUE,
+    }
+  }
+
+  // FEN letter of the piece: uppercase for White, lowercase for Black
+  pub fn symbol(&self) -> char {
+    let symbol = match self {
+      Piece::Pawn(_) => 'p',
+      Piece::Knight(_) => 'n',
+      Piece::Bishop(_) => 'b',
+      Piece::Rook(_) => 'r',
+      Piece::Queen(_) => 'q',
+      Piece::King(_) => 'k',
+    };
+
+    match self.color() {
+      Color::White => symbol.to_ascii_uppercase(),
+      Color::Black => symbol,
+    }
+  }
+
+  pub fn from_symbol(symbol: char) -> Option<Piece> {
+    let color = if symbol.is_ascii_uppercase() {
+      Color::White
+    } else {
+      Color::Black
+    };
+
+    Piece::from_kind_symbol(symbol, color)
+  }
+
+  // Builds a piece of the given color from a letter, ignoring the letter case
+  pub fn from_kind_symbol(symbol: char, color: Color) -> Option<Piece> {
+    match symbol.to_ascii_lowercase() {
+      'p' => Some(Piece::Pawn(Pawn::new(color))),
+      'n' => Some(Piece::Knight(Knight::new(color))),
+      'b' => Some(Piece::Bishop(Bishop::new(color))),
+      'r' => Some(Piece::Rook(Rook::new(color))),
+      'q' => Some(Piece::Queen(Queen::new(color))),
+      'k' => Some(Piece::King(King::new(color))),
+      _ => None,
+    }
+  }
 }
 
 impl Movable for Piece {
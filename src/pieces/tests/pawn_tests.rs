@@ -193,13 +193,13 @@ fn test_pawn_upgrade() {
   let pawn = Pawn::new(Color::White);
   let position = Position::new(7, 4).unwrap(); // e7
 
-  assert_eq!(pawn.can_upgrade(position), true);
+  assert!(pawn.can_upgrade(position));
 
   let pawn = Pawn::new(Color::Black);
   let position = Position::new(0, 4).unwrap(); // e1
 
-  assert_eq!(pawn.can_upgrade(position), true);
+  assert!(pawn.can_upgrade(position));
 
   let invalid_position = Position::new(1, 4).unwrap(); // e2
-  assert_eq!(pawn.can_upgrade(invalid_position), false);
+  assert!(!pawn.can_upgrade(invalid_position));
 }
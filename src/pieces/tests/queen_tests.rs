@@ -14,23 +14,23 @@ fn test_queen_moves_from_center() {
 
   // Horizontal and vertical
   for i in 0..8 {
-    if i != pos.x() {
-      if queen.can_reach(
+    if i != pos.x()
+      && queen.can_reach(
         pos,
         Position::new(i, pos.y()).unwrap(),
         &can_step_into,
-      ) {
-        moves.push(Position::new(i, pos.y()).unwrap());
-      }
+      )
+    {
+      moves.push(Position::new(i, pos.y()).unwrap());
     }
-    if i != pos.y() {
-      if queen.can_reach(
+    if i != pos.y()
+      && queen.can_reach(
         pos,
         Position::new(pos.x(), i).unwrap(),
         &can_step_into,
-      ) {
-        moves.push(Position::new(pos.x(), i).unwrap());
-      }
+      )
+    {
+      moves.push(Position::new(pos.x(), i).unwrap());
     }
   }
 
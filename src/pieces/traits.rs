@@ -23,6 +23,7 @@ pub trait Movable {
     true
   }
 
+  #[allow(clippy::result_unit_err)]
   fn can_reach_via_special_move(
     &self,
     _: Position,
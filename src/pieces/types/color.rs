@@ -13,10 +13,9 @@ impl Color {
 }
 impl PartialEq for Color {
   fn eq(&self, other: &Self) -> bool {
-    match (self, other) {
-      (Color::White, Color::White) => true,
-      (Color::Black, Color::Black) => true,
-      _ => false,
-    }
+    matches!(
+      (self, other),
+      (Color::White, Color::White) | (Color::Black, Color::Black)
+    )
   }
 }
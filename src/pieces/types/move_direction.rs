@@ -48,7 +48,7 @@ pub enum Direction {
 }
 
 impl Direction {
-  pub fn to_offset(&self) -> Offset {
+  pub fn to_offset(self) -> Offset {
     match self {
       Direction::Up => Offset { dx: -1, dy: 0 },
       Direction::Down => Offset { dx: 1, dy: 0 },
@@ -92,6 +92,7 @@ impl Direction {
   }
 }
 
+#[allow(clippy::enum_variant_names)]
 pub enum MovementPattern {
   AppliableOnce(Vec<Direction>),
   AppliableTwice(Vec<Direction>), // Only for Pawn
@@ -124,16 +125,10 @@ impl MovementPattern {
         });
 
         match move_direction {
-          Some(direction) => {
-            if move_directions.contains(&direction) {
-              return Some(vec![target_position]);
-            } else {
-              return None;
-            }
-          }
-          None => {
-            return None;
+          Some(direction) if move_directions.contains(&direction) => {
+            Some(vec![target_position])
           }
+          _ => None,
         }
       }
       MovementPattern::AppliableTwice(moving_directions) => {
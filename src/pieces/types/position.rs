@@ -1,6 +1,6 @@
 use crate::pieces::types::BOARD_SIZE;
 
-#[derive(Debug, Clone, Copy, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Position {
   x: usize,
   y: usize,
@@ -19,6 +19,7 @@ impl Ord for Position {
 }
 
 impl Position {
+  #[allow(clippy::result_unit_err)]
   pub fn new(x: usize, y: usize) -> Result<Self, ()> {
     if x >= BOARD_SIZE || y >= BOARD_SIZE {
       return Err(());
@@ -34,21 +35,30 @@ impl Position {
     self.y
   }
 
+  // Same as `from_algebraic`, kept for callers of the old name
+  #[allow(clippy::should_implement_trait)]
   pub fn from_str(position: &str) -> Result<Self, String> {
-    if position.len() != 2 {
-      return Err("Invalid position format".to_string());
+    Self::from_algebraic(position)
+  }
+
+  // Parses squares written as file then rank (e.g. "e4"), as used by FEN and UCI
+  pub fn from_algebraic(square: &str) -> Result<Self, String> {
+    let bytes = square.as_bytes();
+    if bytes.len() != 2 {
+      return Err(format!("Invalid square: {}", square));
     }
-    let chars: Vec<char> = position.chars().collect();
 
-    let x = chars[0].to_digit(10).unwrap() as usize - 1;
-    let y = (chars[1] as u8 - b'A') as usize;
+    let file = bytes[0].to_ascii_lowercase();
+    let rank = bytes[1];
+    if !(b'a'..=b'h').contains(&file) || !(b'1'..=b'8').contains(&rank) {
+      return Err(format!("Invalid square: {}", square));
+    }
 
-    Position::new(x, y).map_err(|_| "Position out of bounds".to_string())
+    Position::new((rank - b'1') as usize, (file - b'a') as usize)
+      .map_err(|_| "Position out of bounds".to_string())
   }
-}
 
-impl PartialEq for Position {
-  fn eq(&self, other: &Self) -> bool {
-    self.x == other.x && self.y == other.y
+  pub fn to_algebraic(&self) -> String {
+    format!("{}{}", (b'a' + self.y as u8) as char, self.x + 1)
   }
 }
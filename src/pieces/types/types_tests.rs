@@ -22,9 +22,14 @@ fn test_position_new() {
 
 #[test]
 fn test_position_from_str() {
-  let pos = Position::from_str("3A").unwrap();
+  let pos = Position::from_str("a3").unwrap();
   assert_eq!(pos.x(), 2); // 3 - 1 = 2
-  assert_eq!(pos.y(), 0); // A - 1 = 0
+  assert_eq!(pos.y(), 0); // a - a = 0
+
+  // Same squares as typed at the move prompt, like "e2 e4"
+  assert_eq!(Position::from_str("E2"), Position::from_algebraic("e2"));
+  assert!(Position::from_str("3A").is_err());
+  assert!(Position::from_str("e9").is_err());
 }
 
 #[test]
@@ -1,7 +1,13 @@
+use std::array::from_fn;
 use std::fmt;
 
+use crate::clock::format_duration;
 use crate::game::Game;
 use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::presenters::style::{
+  BLACK_PIECE, DARK_SQUARE, Glyphs, LIGHT_SQUARE, RenderOptions, WHITE_PIECE,
+};
 use crate::{
   chessboard::Chessboard,
   pieces::{Bishop, King, Knight, Pawn, Queen, Rook, types::color::Color},
@@ -10,109 +16,185 @@ use crate::{
 use super::Presenter;
 
 impl Presenter for Pawn {
-  fn render(&self) {
-    match self.color() {
-      Color::White => print!("P "),
-      Color::Black => print!("p "),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    Piece::Pawn(*self).write_to(out, options)
   }
 }
 
 impl Presenter for Bishop {
-  fn render(&self) {
-    match self.color() {
-      Color::White => print!("B "),
-      Color::Black => print!("b "),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    Piece::Bishop(*self).write_to(out, options)
   }
 }
 
 impl Presenter for Knight {
-  fn render(&self) {
-    match self.color() {
-      Color::White => print!("N "),
-      Color::Black => print!("n "),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    Piece::Knight(*self).write_to(out, options)
   }
 }
 
 impl Presenter for Rook {
-  fn render(&self) {
-    match self.color() {
-      Color::White => print!("R "),
-      Color::Black => print!("r "),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    Piece::Rook(*self).write_to(out, options)
   }
 }
 
 impl Presenter for Queen {
-  fn render(&self) {
-    match self.color() {
-      Color::White => print!("Q "),
-      Color::Black => print!("q "),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    Piece::Queen(*self).write_to(out, options)
   }
 }
 
 impl Presenter for King {
-  fn render(&self) {
-    match self.color() {
-      Color::White => print!("K "),
-      Color::Black => print!("k "),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    Piece::King(*self).write_to(out, options)
+  }
+}
+
+// The piece's letter or chess symbol
+pub(crate) fn glyph(piece: &Piece, glyphs: Glyphs) -> char {
+  let symbol = piece.symbol();
+  match glyphs {
+    Glyphs::Ascii => symbol,
+    Glyphs::Unicode => match symbol {
+      'K' => '\u{2654}',
+      'Q' => '\u{2655}',
+      'R' => '\u{2656}',
+      'B' => '\u{2657}',
+      'N' => '\u{2658}',
+      'P' => '\u{2659}',
+      'k' => '\u{265A}',
+      'q' => '\u{265B}',
+      'r' => '\u{265C}',
+      'b' => '\u{265D}',
+      'n' => '\u{265E}',
+      _ => '\u{265F}',
+    },
   }
 }
 
 impl Presenter for Piece {
-  fn render(&self) {
-    match self {
-      Piece::Pawn(pawn) => pawn.render(),
-      Piece::Knight(knight) => knight.render(),
-      Piece::Bishop(bishop) => bishop.render(),
-      Piece::Rook(rook) => rook.render(),
-      Piece::Queen(queen) => queen.render(),
-      Piece::King(king) => king.render(),
-    }
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    write!(out, "{} ", glyph(self, options.glyphs))
   }
 }
 
 impl Presenter for Chessboard {
-  fn render(&self) {
-    for (i, row) in self.board().iter().enumerate() {
-      print!("{:3} ", 8 - i); // Add row numbers (123..)
-      for square in row.iter() {
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    // Rows and files in the order they're drawn, from the top left
+    let (rows, files) = board_order(options.orientation);
+    for &x in &rows {
+      write!(out, "{:3} ", x + 1)?; // Add row numbers (123..)
+      for &y in &files {
+        let square = self.board()[x][y].as_ref();
+        if options.color {
+          render_colored_square(out, square, x, y, options.glyphs)?;
+          continue;
+        }
         match square {
-          Some(piece) => {
-            piece.render();
-          }
-          None => {
-            print!(". "); // Empty square
-          }
+          Some(piece) => piece.write_to(out, options)?,
+          None => write!(out, ". ")?, // Empty square
         }
       }
-      println!(); // Move to the next line after each row
+      writeln!(out)?; // Move to the next line after each row
+    }
+    write!(out, "    ")?;
+    for &y in &files {
+      write!(out, "{} ", (b'a' + y as u8) as char)?; // Print column labels
+    }
+    writeln!(out)?; // Newline for column labels
+
+    write!(out, "White dead pieces: ")?;
+    for piece in self.white_dead_pieces() {
+      piece.write_to(out, options)?;
     }
-    print!("    ");
-    for c in b'a'..=b'h' {
-      print!("{} ", c as char); // Print column labels (a-h)
+    writeln!(out)?;
+    write!(out, "Black dead pieces: ")?;
+    for piece in self.black_dead_pieces() {
+      piece.write_to(out, options)?;
     }
-    println!(); // Newline for column labels
+    writeln!(out)
+  }
+}
 
-    print!("White dead pieces: ");
-    self.white_dead_pieces().iter().for_each(|p| p.render());
-    println!();
-    print!("Black dead pieces: ");
-    self.black_dead_pieces().iter().for_each(|p| p.render());
-    println!();
+// White sees the 8th row at the top and the a file on the left, Black the
+// opposite
+pub(crate) fn board_order(
+  orientation: Color,
+) -> ([usize; BOARD_SIZE], [usize; BOARD_SIZE]) {
+  let ascending: [usize; BOARD_SIZE] = from_fn(|index| index);
+  let descending: [usize; BOARD_SIZE] = from_fn(|index| BOARD_SIZE - 1 - index);
+  match orientation {
+    Color::White => (descending, ascending),
+    Color::Black => (ascending, descending),
   }
 }
 
-impl Presenter for Color {
-  fn render(&self) {
-    match self {
-      Color::White => print!("White"),
-      Color::Black => print!("Black"),
+// A square with its background, a1 being dark
+fn render_colored_square(
+  out: &mut dyn fmt::Write,
+  square: Option<&Piece>,
+  x: usize,
+  y: usize,
+  glyphs: Glyphs,
+) -> fmt::Result {
+  let background = match (x + y) % 2 {
+    0 => DARK_SQUARE,
+    _ => LIGHT_SQUARE,
+  };
+  let (symbol, foreground) = match square {
+    Some(piece) if piece.is_of_color(Color::White) => {
+      (glyph(piece, glyphs), WHITE_PIECE)
     }
+    Some(piece) => (glyph(piece, glyphs), BLACK_PIECE),
+    None => (' ', BLACK_PIECE),
+  };
+  write!(
+    out,
+    "\x1b[1;38;5;{};48;5;{}m{} \x1b[0m",
+    foreground, background, symbol
+  )
+}
+
+impl Presenter for Color {
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    _options: &RenderOptions,
+  ) -> fmt::Result {
+    write!(out, "{}", self)
   }
 }
 
@@ -125,9 +207,53 @@ impl fmt::Display for Color {
   }
 }
 
+// `{}` draws in ASCII letters and `{:#}` with chess symbols, without colors
+fn display_options(f: &fmt::Formatter<'_>) -> RenderOptions {
+  RenderOptions {
+    glyphs: match f.alternate() {
+      true => Glyphs::Unicode,
+      false => Glyphs::Ascii,
+    },
+    ..RenderOptions::default()
+  }
+}
+
+impl fmt::Display for Piece {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(&glyph(self, display_options(f).glyphs), f)
+  }
+}
+
+impl fmt::Display for Chessboard {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let options = display_options(f);
+    self.write_to(f, &options)
+  }
+}
+
+impl fmt::Display for Game {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let options = display_options(f);
+    self.write_to(f, &options)
+  }
+}
+
 impl Presenter for Game {
-  fn render(&self) {
-    println!("Current player: {}", self.player_color());
-    self.board_manager().chessboard().render(); // Render the chessboard
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result {
+    writeln!(out, "Current player: {}", self.player_color())?;
+    if let Some(clock) = self.clock() {
+      writeln!(
+        out,
+        "Clock: White {}  Black {}",
+        format_duration(clock.remaining(Color::White)),
+        format_duration(clock.remaining(Color::Black))
+      )?;
+    }
+    // Render the chessboard
+    self.board_manager().chessboard().write_to(out, options)
   }
 }
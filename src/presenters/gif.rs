@@ -0,0 +1,196 @@
+// Animated GIF of a game, one frame per position, encoded without
+// dependencies
+use crate::game::Game;
+use crate::presenters::png::BitWriter;
+use crate::presenters::raster::Image;
+use crate::presenters::svg::SvgOptions;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const MAX_COLORS: usize = 256;
+// Codes for pixels are 8 bits to start with
+const MIN_CODE_SIZE: u32 = 8;
+const CLEAR: u16 = 1 << MIN_CODE_SIZE;
+const END: u16 = CLEAR + 1;
+const MAX_CODES: u16 = 4096;
+const MAX_CODE_SIZE: u32 = 12;
+// The final position stays this many times longer before the game starts
+// over
+const FINAL_FRAME_HOLD: u32 = 4;
+
+impl Game {
+  // The game from its starting position, with each move highlighted as it
+  // is played, `delay` apart and looping forever
+  pub fn to_gif(&self, options: &SvgOptions, delay: Duration) -> Vec<u8> {
+    let mut replay = Game::with_chessboard(
+      self.initial_chessboard().clone(),
+      self.initial_player_color(),
+    );
+    // The options' own highlights are replaced by each move's squares
+    let start = SvgOptions {
+      highlights: Vec::new(),
+      ..options.clone()
+    };
+    let mut frames = vec![replay.chessboard().to_image(&start)];
+    for chess_move in self.moves() {
+      // A pawn still waiting for its upgrade ends the animation
+      if replay.play_move(*chess_move).is_err() {
+        break;
+      }
+      let options = SvgOptions {
+        highlights: vec![chess_move.from(), chess_move.to()],
+        ..options.clone()
+      };
+      frames.push(replay.chessboard().to_image(&options));
+    }
+    encode_gif(&frames, delay)
+  }
+}
+
+// Frames of the same size, shown `delay` apart, with the last one held
+// longer
+pub fn encode_gif(frames: &[Image], delay: Duration) -> Vec<u8> {
+  let Some(first) = frames.first() else {
+    return Vec::new();
+  };
+  let palette = palette(frames);
+  let mut gif = b"GIF89a".to_vec();
+  gif.extend((first.width() as u16).to_le_bytes());
+  gif.extend((first.height() as u16).to_le_bytes());
+  // A global table of 256 colors, and no background or aspect ratio
+  gif.extend([0xf7, 0, 0]);
+  for index in 0..MAX_COLORS {
+    gif.extend(palette.get(index).unwrap_or(&[0, 0, 0]));
+  }
+  // Loop forever
+  gif.extend([0x21, 0xff, 0x0b]);
+  gif.extend(b"NETSCAPE2.0");
+  gif.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+  let centiseconds = (delay.as_millis() / 10).min(u16::MAX as u128) as u32;
+  let mut indices = HashMap::new();
+  for (number, frame) in frames.iter().enumerate() {
+    let hold = match number + 1 == frames.len() {
+      true => FINAL_FRAME_HOLD,
+      false => 1,
+    };
+    let frame_delay = (centiseconds * hold).min(u16::MAX as u32) as u16;
+    // Graphic control: left in place for the next frame
+    gif.extend([0x21, 0xf9, 0x04, 0x04]);
+    gif.extend(frame_delay.to_le_bytes());
+    gif.extend([0x00, 0x00]);
+
+    // The frame covers the whole image, with the global colors
+    gif.push(0x2c);
+    gif.extend([0, 0, 0, 0]);
+    gif.extend((frame.width() as u16).to_le_bytes());
+    gif.extend((frame.height() as u16).to_le_bytes());
+    gif.push(0);
+
+    let pixels: Vec<u8> = frame
+      .pixels()
+      .iter()
+      .map(|&color| {
+        *indices
+          .entry(color)
+          .or_insert_with(|| nearest(&palette, color))
+      })
+      .collect();
+    gif.push(MIN_CODE_SIZE as u8);
+    for block in lzw(&pixels).chunks(255) {
+      gif.push(block.len() as u8);
+      gif.extend(block);
+    }
+    gif.push(0);
+  }
+  gif.push(0x3b);
+  gif
+}
+
+// The most used colors. Diagrams have few, apart from the blends on the
+// edges of shapes, which get the closest one.
+fn palette(frames: &[Image]) -> Vec<[u8; 3]> {
+  let mut counts: HashMap<[u8; 3], usize> = HashMap::new();
+  for color in frames.iter().flat_map(|frame| frame.pixels()) {
+    *counts.entry(*color).or_default() += 1;
+  }
+  let mut colors: Vec<([u8; 3], usize)> = counts.into_iter().collect();
+  colors.sort_by(|(color, count), (other_color, other_count)| {
+    other_count.cmp(count).then(color.cmp(other_color))
+  });
+  colors
+    .into_iter()
+    .take(MAX_COLORS)
+    .map(|(color, _)| color)
+    .collect()
+}
+
+fn nearest(palette: &[[u8; 3]], color: [u8; 3]) -> u8 {
+  let distance = |other: &[u8; 3]| -> i32 {
+    color
+      .iter()
+      .zip(other)
+      .map(|(&a, &b)| (a as i32 - b as i32).pow(2))
+      .sum()
+  };
+  (0..palette.len())
+    .min_by_key(|&index| distance(&palette[index]))
+    .unwrap_or(0) as u8
+}
+
+// Variable width LZW codes, starting over once the table is full
+fn lzw(pixels: &[u8]) -> Vec<u8> {
+  let mut writer = BitWriter::default();
+  let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+  let mut next_code = END + 1;
+  let mut code_size = MIN_CODE_SIZE + 1;
+  // The decoder adds its codes one step behind, and widens them when its
+  // table reaches the next power of two
+  let mut decoder_codes = END + 1;
+  let mut first_code = true;
+  let mut emit = |writer: &mut BitWriter, code: u16, code_size: &mut u32| {
+    writer.write_bits(code as u32, *code_size);
+    if code == CLEAR {
+      decoder_codes = END + 1;
+      first_code = true;
+      *code_size = MIN_CODE_SIZE + 1;
+      return;
+    }
+    if !first_code && decoder_codes < MAX_CODES {
+      decoder_codes += 1;
+      if decoder_codes == 1 << *code_size && *code_size < MAX_CODE_SIZE {
+        *code_size += 1;
+      }
+    }
+    first_code = false;
+  };
+
+  emit(&mut writer, CLEAR, &mut code_size);
+  let mut prefix: Option<u16> = None;
+  for &pixel in pixels {
+    let Some(code) = prefix else {
+      prefix = Some(pixel as u16);
+      continue;
+    };
+    if let Some(&longer) = table.get(&(code, pixel)) {
+      prefix = Some(longer);
+      continue;
+    }
+
+    emit(&mut writer, code, &mut code_size);
+    if next_code == MAX_CODES {
+      emit(&mut writer, CLEAR, &mut code_size);
+      table.clear();
+      next_code = END + 1;
+    } else {
+      table.insert((code, pixel), next_code);
+      next_code += 1;
+    }
+    prefix = Some(pixel as u16);
+  }
+  if let Some(code) = prefix {
+    emit(&mut writer, code, &mut code_size);
+  }
+  emit(&mut writer, END, &mut code_size);
+  writer.finish()
+}
@@ -1,5 +1,43 @@
 pub mod cmd;
+pub mod gif;
+pub mod png;
+pub mod raster;
+mod shapes;
+pub mod style;
+pub mod svg;
 
+use crate::presenters::style::RenderOptions;
+use std::fmt;
+use std::io;
+
+// Renders into any text sink: a `String`, a `fmt::Formatter` for `Display`,
+// or through `render_to` a file, socket or stdout
 pub trait Presenter {
-  fn render(&self);
+  fn write_to(
+    &self,
+    out: &mut dyn fmt::Write,
+    options: &RenderOptions,
+  ) -> fmt::Result;
+
+  fn render_to(
+    &self,
+    out: &mut dyn io::Write,
+    options: &RenderOptions,
+  ) -> io::Result<()> {
+    out.write_all(self.render_string(options).as_bytes())
+  }
+
+  fn render_string(&self, options: &RenderOptions) -> String {
+    let mut text = String::new();
+    // Writing to a String can't fail
+    self.write_to(&mut text, options).unwrap();
+    text
+  }
+
+  // Plain ASCII on stdout
+  fn render(&self) {
+    self
+      .render_to(&mut io::stdout(), &RenderOptions::default())
+      .expect("Failed to write to stdout");
+  }
 }
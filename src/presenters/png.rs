@@ -0,0 +1,242 @@
+// PNG encoding without dependencies: the pixels are compressed with
+// DEFLATE using the fixed Huffman codes and a simple LZ77 matcher, which
+// does well on the flat colors of board diagrams
+use crate::presenters::raster::Image;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+const WINDOW: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+// Earlier positions with the same hash tried for each match
+const MAX_CHAIN: usize = 32;
+
+const LENGTH_BASES: [u16; 29] = [
+  3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67,
+  83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+  0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5,
+  5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+  1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513,
+  769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+  0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11,
+  11, 12, 12, 13, 13,
+];
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+  let mut table = [0; 256];
+  let mut index = 0;
+  while index < 256 {
+    let mut crc = index as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = match crc & 1 {
+        1 => 0xedb88320 ^ (crc >> 1),
+        _ => crc >> 1,
+      };
+      bit += 1;
+    }
+    table[index] = crc;
+    index += 1;
+  }
+  table
+}
+
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+  let crc = bytes.iter().fold(0xffffffff, |crc: u32, &byte| {
+    CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+  });
+  crc ^ 0xffffffff
+}
+
+pub(crate) fn adler32(bytes: &[u8]) -> u32 {
+  let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+    let a = (a + byte as u32) % 65521;
+    (a, (b + a) % 65521)
+  });
+  (b << 16) | a
+}
+
+impl Image {
+  pub fn to_png(&self) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend((self.width() as u32).to_be_bytes());
+    header.extend((self.height() as u32).to_be_bytes());
+    // 8 bits per channel, RGB, then the default compression, filtering and
+    // no interlacing
+    header.extend([8, 2, 0, 0, 0]);
+
+    // Each row starts with its filter, none here: the matcher finds the
+    // repeats along and between rows
+    let mut data = Vec::with_capacity(self.height() * (self.width() * 3 + 1));
+    for row in self.pixels().chunks(self.width().max(1)) {
+      data.push(0);
+      data.extend(row.iter().flatten());
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib(&data));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+  }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+  png.extend((data.len() as u32).to_be_bytes());
+  let start = png.len();
+  png.extend(kind);
+  png.extend(data);
+  let crc = crc32(&png[start..]);
+  png.extend(crc.to_be_bytes());
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+  // 32K window, no dictionary, fastest compression level
+  let mut stream = vec![0x78, 0x01];
+  stream.extend(deflate(data));
+  stream.extend(adler32(data).to_be_bytes());
+  stream
+}
+
+// Bits are packed from the least significant one, as DEFLATE and GIF's
+// LZW want
+#[derive(Default)]
+pub(crate) struct BitWriter {
+  bytes: Vec<u8>,
+  buffer: u32,
+  count: u32,
+}
+
+impl BitWriter {
+  pub(crate) fn write_bits(&mut self, value: u32, bits: u32) {
+    self.buffer |= value << self.count;
+    self.count += bits;
+    while self.count >= 8 {
+      self.bytes.push(self.buffer as u8);
+      self.buffer >>= 8;
+      self.count -= 8;
+    }
+  }
+
+  // Huffman codes go most significant bit first
+  fn write_code(&mut self, code: u32, bits: u32) {
+    self.write_bits(code.reverse_bits() >> (32 - bits), bits);
+  }
+
+  pub(crate) fn finish(mut self) -> Vec<u8> {
+    if self.count > 0 {
+      self.bytes.push(self.buffer as u8);
+    }
+    self.bytes
+  }
+}
+
+// A literal byte, the end of the block or a match length's symbol
+fn write_symbol(writer: &mut BitWriter, symbol: u32) {
+  match symbol {
+    0..=143 => writer.write_code(0x30 + symbol, 8),
+    144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+    256..=279 => writer.write_code(symbol - 256, 7),
+    _ => writer.write_code(0xc0 + symbol - 280, 8),
+  }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+  let code = LENGTH_BASES
+    .iter()
+    .rposition(|&base| base as usize <= length)
+    .unwrap();
+  write_symbol(writer, 257 + code as u32);
+  let extra = (length - LENGTH_BASES[code] as usize) as u32;
+  writer.write_bits(extra, LENGTH_EXTRA_BITS[code] as u32);
+
+  let code = DISTANCE_BASES
+    .iter()
+    .rposition(|&base| base as usize <= distance)
+    .unwrap();
+  writer.write_code(code as u32, 5);
+  let extra = (distance - DISTANCE_BASES[code] as usize) as u32;
+  writer.write_bits(extra, DISTANCE_EXTRA_BITS[code] as u32);
+}
+
+fn hash(bytes: &[u8]) -> usize {
+  let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+  (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+// One final block with the fixed Huffman codes
+fn deflate(data: &[u8]) -> Vec<u8> {
+  let mut writer = BitWriter::default();
+  writer.write_bits(1, 1); // Last block
+  writer.write_bits(1, 2); // Fixed codes
+
+  // Latest position of each hash, and the one before it for each position
+  let mut head = vec![usize::MAX; 1 << HASH_BITS];
+  let mut previous = vec![usize::MAX; data.len()];
+  let insert = |position: usize, head: &mut [usize], previous: &mut [usize]| {
+    if position + MIN_MATCH <= data.len() {
+      let key = hash(&data[position..]);
+      previous[position] = head[key];
+      head[key] = position;
+    }
+  };
+
+  let mut position = 0;
+  while position < data.len() {
+    let (length, distance) = longest_match(data, position, &head, &previous);
+    if length >= MIN_MATCH {
+      write_match(&mut writer, length, distance);
+      for skipped in position..position + length {
+        insert(skipped, &mut head, &mut previous);
+      }
+      position += length;
+    } else {
+      write_symbol(&mut writer, data[position] as u32);
+      insert(position, &mut head, &mut previous);
+      position += 1;
+    }
+  }
+  write_symbol(&mut writer, 256);
+  writer.finish()
+}
+
+fn longest_match(
+  data: &[u8],
+  position: usize,
+  head: &[usize],
+  previous: &[usize],
+) -> (usize, usize) {
+  if position + MIN_MATCH > data.len() {
+    return (0, 0);
+  }
+  let max_length = MAX_MATCH.min(data.len() - position);
+  let mut best = (0, 0);
+  let mut candidate = head[hash(&data[position..])];
+  for _ in 0..MAX_CHAIN {
+    if candidate == usize::MAX || position - candidate > WINDOW {
+      break;
+    }
+    let length = data[candidate..]
+      .iter()
+      .zip(&data[position..position + max_length])
+      .take_while(|(a, b)| a == b)
+      .count();
+    if length > best.0 {
+      best = (length, position - candidate);
+      if length == max_length {
+        break;
+      }
+    }
+    candidate = previous[candidate];
+  }
+  best
+}
@@ -0,0 +1,290 @@
+// Draws board diagrams into pixels on the CPU, with the same geometry and
+// colors as the SVG ones, for the PNG and GIF encoders
+use crate::chessboard::Chessboard;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use crate::presenters::shapes::{SHAPE_SIZE, Shape, piece_shapes};
+use crate::presenters::svg::{
+  ARROW, ARROW_OPACITY, BACKGROUND, BLACK_PIECE, COORDINATES, DARK_SQUARE,
+  HIGHLIGHT, HIGHLIGHT_OPACITY, LIGHT_SQUARE, OUTLINE, OUTLINE_WIDTH,
+  SvgOptions, WHITE_PIECE, arrow,
+};
+use std::f32::consts::TAU;
+
+// Samples per pixel along each axis, for smooth edges
+const SUBSAMPLES: usize = 4;
+// Sides of the polygons drawn for circles
+const CIRCLE_SEGMENTS: usize = 32;
+
+// Coordinates in a 5 by 7 pixel font, one row per byte from the top
+#[rustfmt::skip]
+const FONT: [(char, [u8; 7]); 16] = [
+  ('a', [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111]),
+  ('b', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110]),
+  ('c', [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110]),
+  ('d', [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111]),
+  ('e', [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110]),
+  ('f', [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000]),
+  ('g', [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110]),
+  ('h', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001]),
+  ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+  ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+  ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+  ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+  ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+  ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+  ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+  ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+];
+
+// RGB pixels, row by row from the top left
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+  width: usize,
+  height: usize,
+  pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+  pub fn new(width: usize, height: usize, color: [u8; 3]) -> Self {
+    Image {
+      width,
+      height,
+      pixels: vec![color; width * height],
+    }
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+    self.pixels[y * self.width + x]
+  }
+
+  pub fn pixels(&self) -> &[[u8; 3]] {
+    &self.pixels
+  }
+
+  fn blend(&mut self, x: usize, y: usize, color: [u8; 3], alpha: f32) {
+    let pixel = &mut self.pixels[y * self.width + x];
+    for (channel, value) in pixel.iter_mut().zip(color) {
+      let mixed = *channel as f32 * (1.0 - alpha) + value as f32 * alpha;
+      *channel = mixed.round() as u8;
+    }
+  }
+
+  fn fill_rect(
+    &mut self,
+    (x, y): (f32, f32),
+    (width, height): (f32, f32),
+    color: [u8; 3],
+    opacity: f32,
+  ) {
+    let corners = [
+      (x, y),
+      (x + width, y),
+      (x + width, y + height),
+      (x, y + height),
+    ];
+    self.fill_polygon(&corners, color, opacity);
+  }
+
+  // Even-odd filling, each pixel covered in proportion to the samples
+  // inside the polygon
+  fn fill_polygon(
+    &mut self,
+    points: &[(f32, f32)],
+    color: [u8; 3],
+    opacity: f32,
+  ) {
+    if points.len() < 3 {
+      return;
+    }
+    let bound =
+      |value: f32, size: usize| value.clamp(0.0, size as f32) as usize;
+    let top = points.iter().map(|point| point.1).fold(f32::MAX, f32::min);
+    let bottom = points.iter().map(|point| point.1).fold(f32::MIN, f32::max);
+    let left = points.iter().map(|point| point.0).fold(f32::MAX, f32::min);
+    let right = points.iter().map(|point| point.0).fold(f32::MIN, f32::max);
+    let (top, bottom) =
+      (bound(top, self.height), bound(bottom.ceil(), self.height));
+    let (left, right) =
+      (bound(left, self.width), bound(right.ceil(), self.width));
+
+    let samples = SUBSAMPLES as f32;
+    let mut coverage = vec![0; right - left];
+    for row in top..bottom {
+      coverage.fill(0);
+      for sample_row in 0..SUBSAMPLES {
+        let y = row as f32 + (sample_row as f32 + 0.5) / samples;
+        let mut crossings: Vec<f32> = points
+          .iter()
+          .zip(points.iter().cycle().skip(1))
+          .filter(|((_, y1), (_, y2))| (*y1 <= y) != (*y2 <= y))
+          .map(|((x1, y1), (x2, y2))| x1 + (y - y1) * (x2 - x1) / (y2 - y1))
+          .collect();
+        crossings.sort_by(f32::total_cmp);
+
+        // Samples of the row between each pair of crossings
+        for span in crossings.chunks_exact(2) {
+          let first = (span[0] * samples - 0.5)
+            .ceil()
+            .max((left * SUBSAMPLES) as f32);
+          let end = (span[1] * samples - 0.5)
+            .ceil()
+            .min((right * SUBSAMPLES) as f32);
+          for sample in first as usize..end.max(first) as usize {
+            coverage[sample / SUBSAMPLES - left] += 1;
+          }
+        }
+      }
+
+      for (column, &covered) in coverage.iter().enumerate() {
+        if covered > 0 {
+          let alpha = covered as f32 / (samples * samples) * opacity;
+          self.blend(left + column, row, color, alpha);
+        }
+      }
+    }
+  }
+
+  fn fill_circle(&mut self, (x, y): (f32, f32), radius: f32, color: [u8; 3]) {
+    let points: Vec<(f32, f32)> = (0..CIRCLE_SEGMENTS)
+      .map(|index| {
+        let angle = index as f32 * TAU / CIRCLE_SEGMENTS as f32;
+        (x + radius * angle.cos(), y + radius * angle.sin())
+      })
+      .collect();
+    self.fill_polygon(&points, color, 1.0);
+  }
+
+  // Lines along the polygon's sides, rounded at the corners
+  fn stroke_polygon(
+    &mut self,
+    points: &[(f32, f32)],
+    width: f32,
+    color: [u8; 3],
+  ) {
+    let half = width / 2.0;
+    for (&(x1, y1), &(x2, y2)) in
+      points.iter().zip(points.iter().cycle().skip(1))
+    {
+      let length = (x2 - x1).hypot(y2 - y1);
+      if length > 0.0 {
+        let (nx, ny) = (-(y2 - y1) / length * half, (x2 - x1) / length * half);
+        let side = [
+          (x1 + nx, y1 + ny),
+          (x2 + nx, y2 + ny),
+          (x2 - nx, y2 - ny),
+          (x1 - nx, y1 - ny),
+        ];
+        self.fill_polygon(&side, color, 1.0);
+      }
+      self.fill_circle((x1, y1), half, color);
+    }
+  }
+
+  // A letter or digit of the coordinates, centered on (x, y)
+  fn draw_label(&mut self, (x, y): (f32, f32), label: char, height: f32) {
+    let Some((_, rows)) = FONT.iter().find(|(symbol, _)| *symbol == label)
+    else {
+      return;
+    };
+    let scale = (height / 7.0).round().max(1.0) as usize;
+    let left = (x - (5 * scale) as f32 / 2.0).round().max(0.0) as usize;
+    let top = (y - (7 * scale) as f32 / 2.0).round().max(0.0) as usize;
+    for (row, bits) in rows.iter().enumerate() {
+      for column in (0..5).filter(|column| bits & (0b10000 >> column) != 0) {
+        let (x, y) = (left + column * scale, top + row * scale);
+        let corner = (x as f32, y as f32);
+        self.fill_rect(corner, (scale as f32, scale as f32), COORDINATES, 1.0);
+      }
+    }
+  }
+}
+
+impl Chessboard {
+  // The same diagram as `to_svg`, in pixels
+  pub fn to_image(&self, options: &SvgOptions) -> Image {
+    let size = options.size().round() as usize;
+    let mut image = Image::new(size, size, BACKGROUND);
+    let square = (options.square(), options.square());
+
+    for position in self.get_all_positions() {
+      let color = match (position.x() + position.y()) % 2 {
+        0 => DARK_SQUARE,
+        _ => LIGHT_SQUARE,
+      };
+      image.fill_rect(options.corner(position), square, color, 1.0);
+    }
+    for &position in &options.highlights {
+      let corner = options.corner(position);
+      image.fill_rect(corner, square, HIGHLIGHT, HIGHLIGHT_OPACITY);
+    }
+    if options.coordinates {
+      draw_coordinates(&mut image, options);
+    }
+
+    let scale = options.square() / SHAPE_SIZE;
+    for position in self.get_all_positions() {
+      let Some(piece) = self.get_piece(position) else {
+        continue;
+      };
+      let fill = match piece.color() {
+        Color::White => WHITE_PIECE,
+        Color::Black => BLACK_PIECE,
+      };
+      let (left, top) = options.corner(position);
+      let place = |(x, y): (f32, f32)| (left + x * scale, top + y * scale);
+      let outline = OUTLINE_WIDTH * scale;
+
+      // Each shape filled then outlined, like SVG does
+      for shape in piece_shapes(piece) {
+        match shape {
+          Shape::Polygon(points) => {
+            let points: Vec<(f32, f32)> =
+              points.iter().map(|&point| place(point)).collect();
+            image.fill_polygon(&points, fill, 1.0);
+            image.stroke_polygon(&points, outline, OUTLINE);
+          }
+          Shape::Circle { x, y, radius } => {
+            let center = place((*x, *y));
+            image.fill_circle(center, radius * scale + outline / 2.0, OUTLINE);
+            image.fill_circle(center, radius * scale - outline / 2.0, fill);
+          }
+        }
+      }
+    }
+
+    for &(from, to) in &options.arrows {
+      if from != to {
+        image.fill_polygon(&arrow(options, from, to), ARROW, ARROW_OPACITY);
+      }
+    }
+    image
+  }
+
+  pub fn to_png(&self, options: &SvgOptions) -> Vec<u8> {
+    self.to_image(options).to_png()
+  }
+}
+
+// Files under the board and rows on its left, in the margin
+fn draw_coordinates(image: &mut Image, options: &SvgOptions) {
+  let margin = options.margin();
+  let bottom = margin + BOARD_SIZE as f32 * options.square() + margin / 2.0;
+  for index in 0..BOARD_SIZE {
+    let (file_x, _) = options.center(Position::new(0, index).unwrap());
+    let (_, row_y) = options.center(Position::new(index, 0).unwrap());
+    let file = (b'a' + index as u8) as char;
+    let row = (b'1' + index as u8) as char;
+    image.draw_label((file_x, bottom), file, margin * 0.6);
+    image.draw_label((margin / 2.0, row_y), row, margin * 0.6);
+  }
+}
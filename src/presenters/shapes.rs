@@ -0,0 +1,157 @@
+// Piece silhouettes made of polygons and circles on a 45 by 45 square, the
+// same for both colors. Diagrams scale them to their square size.
+use crate::pieces::piece::Piece;
+
+pub(crate) const SHAPE_SIZE: f32 = 45.0;
+
+pub(crate) enum Shape {
+  Polygon(&'static [(f32, f32)]),
+  Circle { x: f32, y: f32, radius: f32 },
+}
+
+const BASE: Shape =
+  Shape::Polygon(&[(10.0, 39.0), (35.0, 39.0), (35.0, 35.0), (10.0, 35.0)]);
+
+const PAWN: &[Shape] = &[
+  Shape::Polygon(&[(11.0, 39.0), (34.0, 39.0), (34.0, 36.0), (11.0, 36.0)]),
+  Shape::Polygon(&[(14.0, 36.0), (31.0, 36.0), (26.0, 22.0), (19.0, 22.0)]),
+  Shape::Circle {
+    x: 22.5,
+    y: 16.0,
+    radius: 6.0,
+  },
+];
+
+const KNIGHT: &[Shape] = &[
+  BASE,
+  Shape::Polygon(&[
+    (13.0, 35.0),
+    (33.0, 35.0),
+    (32.0, 25.0),
+    (29.0, 16.0),
+    (24.0, 11.0),
+    (22.0, 7.0),
+    (19.0, 11.0),
+    (15.0, 14.0),
+    (9.0, 23.0),
+    (11.0, 27.0),
+    (15.0, 26.0),
+    (20.0, 21.0),
+    (22.0, 24.0),
+    (15.0, 31.0),
+  ]),
+];
+
+const BISHOP: &[Shape] = &[
+  BASE,
+  Shape::Polygon(&[
+    (14.0, 35.0),
+    (31.0, 35.0),
+    (28.0, 26.0),
+    (30.0, 19.0),
+    (22.5, 10.0),
+    (15.0, 19.0),
+    (17.0, 26.0),
+  ]),
+  Shape::Circle {
+    x: 22.5,
+    y: 8.0,
+    radius: 3.0,
+  },
+];
+
+const ROOK: &[Shape] = &[
+  BASE,
+  Shape::Polygon(&[
+    (13.0, 35.0),
+    (32.0, 35.0),
+    (30.0, 17.0),
+    (34.0, 17.0),
+    (34.0, 9.0),
+    (30.0, 9.0),
+    (30.0, 12.0),
+    (25.0, 12.0),
+    (25.0, 9.0),
+    (20.0, 9.0),
+    (20.0, 12.0),
+    (15.0, 12.0),
+    (15.0, 9.0),
+    (11.0, 9.0),
+    (11.0, 17.0),
+    (15.0, 17.0),
+  ]),
+];
+
+const QUEEN: &[Shape] = &[
+  BASE,
+  Shape::Polygon(&[
+    (12.0, 35.0),
+    (33.0, 35.0),
+    (37.0, 14.0),
+    (30.0, 25.0),
+    (29.0, 10.0),
+    (25.0, 24.0),
+    (22.5, 8.0),
+    (20.0, 24.0),
+    (16.0, 10.0),
+    (15.0, 25.0),
+    (8.0, 14.0),
+  ]),
+  Shape::Circle {
+    x: 8.0,
+    y: 13.0,
+    radius: 2.5,
+  },
+  Shape::Circle {
+    x: 16.0,
+    y: 9.0,
+    radius: 2.5,
+  },
+  Shape::Circle {
+    x: 22.5,
+    y: 7.0,
+    radius: 2.5,
+  },
+  Shape::Circle {
+    x: 29.0,
+    y: 9.0,
+    radius: 2.5,
+  },
+  Shape::Circle {
+    x: 37.0,
+    y: 13.0,
+    radius: 2.5,
+  },
+];
+
+const KING: &[Shape] = &[
+  BASE,
+  Shape::Polygon(&[
+    (12.0, 35.0),
+    (33.0, 35.0),
+    (33.0, 30.0),
+    (37.0, 22.0),
+    (32.0, 17.0),
+    (26.0, 19.0),
+    (24.0, 14.0),
+    (21.0, 14.0),
+    (19.0, 19.0),
+    (13.0, 17.0),
+    (8.0, 22.0),
+    (12.0, 30.0),
+  ]),
+  // The cross
+  Shape::Polygon(&[(21.0, 3.0), (24.0, 3.0), (24.0, 14.0), (21.0, 14.0)]),
+  Shape::Polygon(&[(18.0, 6.0), (27.0, 6.0), (27.0, 9.0), (18.0, 9.0)]),
+];
+
+pub(crate) fn piece_shapes(piece: &Piece) -> &'static [Shape] {
+  match piece {
+    Piece::Pawn(_) => PAWN,
+    Piece::Knight(_) => KNIGHT,
+    Piece::Bishop(_) => BISHOP,
+    Piece::Rook(_) => ROOK,
+    Piece::Queen(_) => QUEEN,
+    Piece::King(_) => KING,
+  }
+}
@@ -0,0 +1,68 @@
+use crate::pieces::types::color::Color;
+use std::env;
+use std::io::{self, IsTerminal};
+
+// Square and piece colors from the 256 color palette
+pub(crate) const LIGHT_SQUARE: u8 = 180;
+pub(crate) const DARK_SQUARE: u8 = 137;
+pub(crate) const WHITE_PIECE: u8 = 231;
+pub(crate) const BLACK_PIECE: u8 = 16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Glyphs {
+  // FEN letters, uppercase for White
+  #[default]
+  Ascii,
+  // Chess symbols like ♔ and ♚
+  Unicode,
+}
+
+// How boards are drawn. The default is plain ASCII, which any terminal or
+// file can show.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RenderOptions {
+  pub glyphs: Glyphs,
+  // Light and dark square backgrounds with ANSI escape codes
+  pub color: bool,
+  // Side at the bottom of the board
+  pub orientation: Color,
+}
+
+impl Default for RenderOptions {
+  fn default() -> Self {
+    RenderOptions {
+      glyphs: Glyphs::Ascii,
+      color: false,
+      orientation: Color::White,
+    }
+  }
+}
+
+impl RenderOptions {
+  // Colors are on when stdout is a terminal, unless NO_COLOR is set
+  // (https://no-color.org). The flags win over both.
+  pub fn from_args(args: &[String]) -> Self {
+    let no_color =
+      env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    Self::parse(args, io::stdout().is_terminal() && !no_color)
+  }
+
+  // --unicode for chess symbols, --color or --no-color, and --ascii for
+  // plain letters without colors
+  pub fn parse(args: &[String], color: bool) -> Self {
+    let mut options = RenderOptions {
+      color,
+      ..RenderOptions::default()
+    };
+    for arg in args {
+      match arg.as_str() {
+        "--unicode" => options.glyphs = Glyphs::Unicode,
+        "--color" => options.color = true,
+        "--no-color" => options.color = false,
+        "--ascii" => options = RenderOptions::default(),
+        _ => (),
+      }
+    }
+    options
+  }
+}
@@ -0,0 +1,275 @@
+use crate::chessboard::Chessboard;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use crate::presenters::shapes::{SHAPE_SIZE, Shape, piece_shapes};
+use std::fmt::Write;
+
+// Colors shared with the PNG and GIF diagrams
+pub(crate) const LIGHT_SQUARE: [u8; 3] = [0xf0, 0xd9, 0xb5];
+pub(crate) const DARK_SQUARE: [u8; 3] = [0xb5, 0x88, 0x63];
+pub(crate) const HIGHLIGHT: [u8; 3] = [0xff, 0xff, 0x33];
+pub(crate) const HIGHLIGHT_OPACITY: f32 = 0.5;
+pub(crate) const ARROW: [u8; 3] = [0x15, 0x78, 0x1b];
+pub(crate) const ARROW_OPACITY: f32 = 0.8;
+pub(crate) const COORDINATES: [u8; 3] = [0x40, 0x40, 0x40];
+pub(crate) const BACKGROUND: [u8; 3] = [0xff, 0xff, 0xff];
+pub(crate) const WHITE_PIECE: [u8; 3] = [0xff, 0xff, 0xff];
+pub(crate) const BLACK_PIECE: [u8; 3] = [0x00, 0x00, 0x00];
+pub(crate) const OUTLINE: [u8; 3] = [0x00, 0x00, 0x00];
+// Width of the pieces' outline, on the shapes' 45 by 45 square
+pub(crate) const OUTLINE_WIDTH: f32 = 1.5;
+
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+  // Width of a square in pixels
+  pub square_size: u32,
+  // Side at the bottom of the board
+  pub orientation: Color,
+  // Files and rows written in a margin around the board
+  pub coordinates: bool,
+  // Squares tinted yellow, like the last move
+  pub highlights: Vec<Position>,
+  // Arrows from one square's center to another's
+  pub arrows: Vec<(Position, Position)>,
+}
+
+impl Default for SvgOptions {
+  fn default() -> Self {
+    SvgOptions {
+      square_size: 45,
+      orientation: Color::White,
+      coordinates: true,
+      highlights: Vec::new(),
+      arrows: Vec::new(),
+    }
+  }
+}
+
+impl SvgOptions {
+  pub(crate) fn square(&self) -> f32 {
+    self.square_size as f32
+  }
+
+  // Width and height of the whole diagram
+  pub(crate) fn size(&self) -> f32 {
+    BOARD_SIZE as f32 * self.square() + 2.0 * self.margin()
+  }
+
+  pub(crate) fn margin(&self) -> f32 {
+    match self.coordinates {
+      true => self.square() / 2.0,
+      false => 0.0,
+    }
+  }
+
+  // Top left corner of a square
+  pub(crate) fn corner(&self, position: Position) -> (f32, f32) {
+    let last = BOARD_SIZE - 1;
+    let (column, row) = match self.orientation {
+      Color::White => (position.y(), last - position.x()),
+      Color::Black => (last - position.y(), position.x()),
+    };
+    (
+      self.margin() + column as f32 * self.square(),
+      self.margin() + row as f32 * self.square(),
+    )
+  }
+
+  pub(crate) fn center(&self, position: Position) -> (f32, f32) {
+    let (x, y) = self.corner(position);
+    (x + self.square() / 2.0, y + self.square() / 2.0)
+  }
+}
+
+impl Chessboard {
+  // A standalone SVG diagram of the board, with the pieces drawn in it so
+  // it needs no fonts or images
+  pub fn to_svg(&self, options: &SvgOptions) -> String {
+    let size = number(options.size());
+    let mut svg = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(
+      svg,
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" \
+       viewBox=\"0 0 {0} {0}\">",
+      size
+    );
+    if options.coordinates {
+      let _ = writeln!(
+        svg,
+        "<rect width=\"{0}\" height=\"{0}\" fill=\"{1}\"/>",
+        size,
+        hex(BACKGROUND)
+      );
+    }
+
+    for position in self.get_all_positions() {
+      let fill = match (position.x() + position.y()) % 2 {
+        0 => DARK_SQUARE,
+        _ => LIGHT_SQUARE,
+      };
+      square_rect(&mut svg, options, position, fill, 1.0);
+    }
+    for &position in &options.highlights {
+      square_rect(&mut svg, options, position, HIGHLIGHT, HIGHLIGHT_OPACITY);
+    }
+    if options.coordinates {
+      write_coordinates(&mut svg, options);
+    }
+
+    for position in self.get_all_positions() {
+      let Some(piece) = self.get_piece(position) else {
+        continue;
+      };
+      let (x, y) = options.corner(position);
+      let fill = match piece.color() {
+        Color::White => WHITE_PIECE,
+        Color::Black => BLACK_PIECE,
+      };
+      let _ = writeln!(
+        svg,
+        "<g transform=\"translate({} {}) scale({})\" fill=\"{}\" \
+         stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"round\">",
+        number(x),
+        number(y),
+        number(options.square() / SHAPE_SIZE),
+        hex(fill),
+        hex(OUTLINE),
+        number(OUTLINE_WIDTH)
+      );
+      for shape in piece_shapes(piece) {
+        let _ = match shape {
+          Shape::Polygon(points) => {
+            writeln!(svg, "<polygon points=\"{}\"/>", points_list(points))
+          }
+          Shape::Circle { x, y, radius } => writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>",
+            number(*x),
+            number(*y),
+            number(*radius)
+          ),
+        };
+      }
+      let _ = writeln!(svg, "</g>");
+    }
+
+    for &(from, to) in &options.arrows {
+      if from == to {
+        continue;
+      }
+      let _ = writeln!(
+        svg,
+        "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+        points_list(&arrow(options, from, to)),
+        hex(ARROW),
+        number(ARROW_OPACITY)
+      );
+    }
+    svg.push_str("</svg>\n");
+    svg
+  }
+}
+
+fn square_rect(
+  svg: &mut String,
+  options: &SvgOptions,
+  position: Position,
+  fill: [u8; 3],
+  opacity: f32,
+) {
+  let (x, y) = options.corner(position);
+  let size = number(options.square());
+  let fill = hex(fill);
+  let attributes = match opacity {
+    1.0 => String::new(),
+    _ => format!(" fill-opacity=\"{}\"", number(opacity)),
+  };
+  let _ = writeln!(
+    svg,
+    "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" \
+     fill=\"{fill}\"{attributes}/>",
+    number(x),
+    number(y),
+  );
+}
+
+// Files under the board and rows on its left, in the margin
+fn write_coordinates(svg: &mut String, options: &SvgOptions) {
+  let margin = options.margin();
+  let bottom = margin + BOARD_SIZE as f32 * options.square() + margin / 2.0;
+  for index in 0..BOARD_SIZE {
+    // Squares on the bottom row and the left column
+    let (file_x, _) = options.center(Position::new(0, index).unwrap());
+    let (_, row_y) = options.center(Position::new(index, 0).unwrap());
+    let labels = [
+      (file_x, bottom, (b'a' + index as u8) as char),
+      (margin / 2.0, row_y, (b'1' + index as u8) as char),
+    ];
+    for (x, y, label) in labels {
+      let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" \
+         text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>",
+        number(x),
+        number(y),
+        number(margin * 0.6),
+        hex(COORDINATES),
+        label
+      );
+    }
+  }
+}
+
+// An arrow from the center of one square, its tip on the center of the
+// other
+pub(crate) fn arrow(
+  options: &SvgOptions,
+  from: Position,
+  to: Position,
+) -> Vec<(f32, f32)> {
+  let (start_x, start_y) = options.center(from);
+  let (end_x, end_y) = options.center(to);
+  let length = (end_x - start_x).hypot(end_y - start_y);
+  // Along the arrow and across it
+  let (dx, dy) = ((end_x - start_x) / length, (end_y - start_y) / length);
+  let (nx, ny) = (-dy, dx);
+
+  let shaft = options.square() * 0.1;
+  let head = options.square() * 0.25;
+  let head_length = options.square() * 0.4;
+  let (base_x, base_y) = (end_x - dx * head_length, end_y - dy * head_length);
+  vec![
+    (start_x + nx * shaft, start_y + ny * shaft),
+    (base_x + nx * shaft, base_y + ny * shaft),
+    (base_x + nx * head, base_y + ny * head),
+    (end_x, end_y),
+    (base_x - nx * head, base_y - ny * head),
+    (base_x - nx * shaft, base_y - ny * shaft),
+    (start_x - nx * shaft, start_y - ny * shaft),
+  ]
+}
+
+fn points_list(points: &[(f32, f32)]) -> String {
+  let points: Vec<String> = points
+    .iter()
+    .map(|(x, y)| format!("{},{}", number(*x), number(*y)))
+    .collect();
+  points.join(" ")
+}
+
+fn hex([red, green, blue]: [u8; 3]) -> String {
+  format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+// At most two decimals, without trailing zeros, so diagrams stay small and
+// the same from one run to the next
+fn number(value: f32) -> String {
+  let text = format!("{:.2}", value);
+  let text = text.trim_end_matches('0').trim_end_matches('.');
+  match text {
+    "-0" => "0".to_string(),
+    text => text.to_string(),
+  }
+}
@@ -0,0 +1,13 @@
+pub mod uci;
+pub mod xboard;
+
+use std::io::Write;
+use std::sync::Mutex;
+
+// Writes one protocol line and flushes it right away, as GUIs wait for it
+fn send_line<W: Write>(output: &Mutex<W>, line: &str) {
+  let mut output = output.lock().unwrap();
+  // The GUI going away isn't something the engine can recover from
+  let _ = writeln!(output, "{}", line);
+  let _ = output.flush();
+}
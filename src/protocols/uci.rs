@@ -0,0 +1,340 @@
+use crate::chess_move::Move;
+use crate::engine::bench::{self, DEFAULT_BENCH_DEPTH};
+use crate::engine::search::{Score, SearchInfo, SearchLimits};
+use crate::engine::transposition_table;
+use crate::engine::{Engine, MAX_MULTI_PV, MAX_THREADS};
+use crate::game::Game;
+use crate::pieces::types::color::Color;
+use crate::protocols::send_line;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const ENGINE_NAME: &str = "rust_chess";
+const ENGINE_AUTHOR: &str = "the rust_chess authors";
+
+const PRUNING_OPTIONS: [&str; 5] = [
+  "NullMovePruning",
+  "LateMoveReductions",
+  "FutilityPruning",
+  "ReverseFutilityPruning",
+  "AspirationWindows",
+];
+
+// Words of `go`, which end the move list of `searchmoves`
+const GO_KEYWORDS: [&str; 12] = [
+  "searchmoves",
+  "ponder",
+  "wtime",
+  "btime",
+  "winc",
+  "binc",
+  "movestogo",
+  "depth",
+  "nodes",
+  "mate",
+  "movetime",
+  "infinite",
+];
+
+// Speaks the Universal Chess Interface: commands are read line by line and
+// the search runs on its own thread so that `stop` can interrupt it
+pub struct Uci<W: Write + Send + 'static> {
+  game: Game,
+  engine: Arc<Mutex<Engine>>,
+  output: Arc<Mutex<W>>,
+  stop: Arc<AtomicBool>,
+  search_thread: Option<JoinHandle<()>>,
+  // Whether the running search only ends when told to
+  infinite: bool,
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+  pub fn new(output: W) -> Self {
+    Uci {
+      game: Game::new(Color::White),
+      engine: Arc::new(Mutex::new(Engine::new())),
+      output: Arc::new(Mutex::new(output)),
+      stop: Arc::new(AtomicBool::new(false)),
+      search_thread: None,
+      infinite: false,
+    }
+  }
+
+  pub fn run(&mut self, input: impl BufRead) {
+    for line in input.lines() {
+      let Ok(line) = line else { break };
+      if !self.handle_command(&line) {
+        return;
+      }
+    }
+
+    // Input is closed: let a bounded search finish and report its move, and
+    // stop one that would otherwise never end
+    if self.infinite {
+      self.stop_search();
+    } else {
+      self.wait_for_search();
+    }
+  }
+
+  // Returns false once the engine should exit
+  pub fn handle_command(&mut self, line: &str) -> bool {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let Some((command, args)) = tokens.split_first() else {
+      return true;
+    };
+
+    let res = match *command {
+      "uci" => {
+        self.send(&format!("id name {}", ENGINE_NAME));
+        self.send(&format!("id author {}", ENGINE_AUTHOR));
+        self.send(&format!(
+          "option name Hash type spin default {} min 1 max {}",
+          transposition_table::DEFAULT_SIZE_MB,
+          transposition_table::MAX_SIZE_MB
+        ));
+        self.send(&format!(
+          "option name Threads type spin default 1 min 1 max {}",
+          MAX_THREADS
+        ));
+        self.send(&format!(
+          "option name MultiPV type spin default 1 min 1 max {}",
+          MAX_MULTI_PV
+        ));
+        self.send("option name OwnBook type check default true");
+        self.send("option name BookFile type string default <empty>");
+        self.send(
+          "option name BookChoice type combo default weighted var weighted \
+           var best",
+        );
+        self.send("option name SyzygyPath type string default <empty>");
+        self.send("option name BitbaseFile type string default <empty>");
+        // Search techniques that can be turned off for testing
+        for name in PRUNING_OPTIONS {
+          self.send(&format!("option name {} type check default true", name));
+        }
+        self.send("uciok");
+        Ok(())
+      }
+      "isready" => {
+        self.send("readyok");
+        Ok(())
+      }
+      "ucinewgame" => {
+        self.stop_search();
+        self.game = Game::new(Color::White);
+        self.engine.lock().unwrap().new_game();
+        Ok(())
+      }
+      "position" => self.set_position(args),
+      "go" => self.go(args),
+      // The engine doesn't offer to ponder, so a GUI that does anyway gets
+      // the move found so far when the opponent plays the expected move
+      "stop" | "ponderhit" => {
+        self.stop_search();
+        Ok(())
+      }
+      "setoption" => self.set_option(args),
+      "bench" => self.bench(args),
+      "quit" => {
+        self.stop_search();
+        return false;
+      }
+      _ => Err(format!("Unknown command: {}", command)),
+    };
+
+    if let Err(e) = res {
+      self.send(&format!("info string {}", e));
+    }
+    true
+  }
+
+  fn set_position(&mut self, args: &[&str]) -> Result<(), String> {
+    let moves_index = args.iter().position(|arg| *arg == "moves");
+    let (setup, moves) = match moves_index {
+      Some(index) => (&args[..index], &args[index + 1..]),
+      None => (args, &[][..]),
+    };
+
+    let mut game = match setup.split_first() {
+      Some((&"startpos", _)) => Game::new(Color::White),
+      Some((&"fen", fen)) => Game::from_fen(&fen.join(" "))?,
+      _ => return Err("Expected startpos or fen".to_string()),
+    };
+
+    for text in moves {
+      let chess_move = Move::from_long_algebraic(text, game.player_color())?;
+      game
+        .play_move(chess_move)
+        .map_err(|e| format!("{}: {}", e, text))?;
+    }
+
+    self.game = game;
+    Ok(())
+  }
+
+  fn go(&mut self, args: &[&str]) -> Result<(), String> {
+    let limits = self.parse_limits(args)?;
+
+    self.stop_search();
+    self.stop.store(false, Ordering::Relaxed);
+    self.infinite = limits.infinite;
+
+    let game = self.game.clone();
+    let engine = Arc::clone(&self.engine);
+    let output = Arc::clone(&self.output);
+    let stop = Arc::clone(&self.stop);
+
+    self.search_thread = Some(thread::spawn(move || {
+      let mut engine = engine.lock().unwrap();
+      let result = engine.search(&game, limits, stop, &mut |info| {
+        send_line(&output, &format_info(info));
+      });
+
+      let best_move =
+        result.best_move.map_or("0000".to_string(), |chess_move| {
+          chess_move.to_long_algebraic()
+        });
+      send_line(&output, &format!("bestmove {}", best_move));
+    }));
+
+    Ok(())
+  }
+
+  fn parse_limits(&self, args: &[&str]) -> Result<SearchLimits, String> {
+    let mut limits = SearchLimits::default();
+    let (time_key, increment_key) = match self.game.player_color() {
+      Color::White => ("wtime", "winc"),
+      Color::Black => ("btime", "binc"),
+    };
+
+    // Bare `go` searches until told to stop
+    limits.infinite = args.is_empty();
+
+    let mut i = 0;
+    while i < args.len() {
+      let key = args[i];
+      // A ponder search runs on the opponent's time until `ponderhit` or
+      // `stop`, so it can't end on its own
+      if matches!(key, "infinite" | "ponder") {
+        limits.infinite = true;
+        i += 1;
+        continue;
+      }
+      // Every root move is searched, so the moves listed are skipped
+      if key == "searchmoves" {
+        i += 1;
+        while args.get(i).is_some_and(|arg| !GO_KEYWORDS.contains(arg)) {
+          i += 1;
+        }
+        continue;
+      }
+
+      let value = args
+        .get(i + 1)
+        .ok_or(format!("Missing value for {}", key))?;
+      // Clocks can go negative in some GUIs when a flag is about to fall
+      let number = value
+        .parse::<i64>()
+        .map_err(|_| format!("Invalid value for {}: {}", key, value))?
+        .max(0) as u64;
+
+      match key {
+        "depth" => limits.depth = Some(number as u32),
+        "movetime" => limits.movetime = Some(Duration::from_millis(number)),
+        "nodes" => limits.nodes = Some(number),
+        "movestogo" => limits.moves_to_go = Some(number as u32),
+        key if key == time_key => {
+          limits.time_left = Some(Duration::from_millis(number))
+        }
+        key if key == increment_key => {
+          limits.increment = Duration::from_millis(number)
+        }
+        _ => (),
+      }
+      i += 2;
+    }
+
+    Ok(limits)
+  }
+
+  fn set_option(&mut self, args: &[&str]) -> Result<(), String> {
+    // setoption name <name with spaces> [value <value with spaces>]
+    let name_index = args.iter().position(|arg| *arg == "name");
+    let value_index = args.iter().position(|arg| *arg == "value");
+
+    let Some(name_index) = name_index else {
+      return Err("Missing option name".to_string());
+    };
+
+    let name_end = value_index.unwrap_or(args.len());
+    let name = args[name_index + 1..name_end].join(" ");
+    let value =
+      value_index.map_or(String::new(), |index| args[index + 1..].join(" "));
+
+    self.engine.lock().unwrap().set_option(&name, &value)
+  }
+
+  // Not part of UCI: searches a fixed set of positions and reports the node
+  // count, to compare versions of the search
+  fn bench(&mut self, args: &[&str]) -> Result<(), String> {
+    let depth = match args.first() {
+      Some(depth) => depth
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid bench depth: {}", depth))?,
+      None => DEFAULT_BENCH_DEPTH,
+    };
+
+    self.stop_search();
+    let search_options = self.engine.lock().unwrap().search_options();
+    let result = bench::bench(depth, search_options);
+
+    let millis = result.elapsed.as_millis().max(1) as u64;
+    self.send(&format!(
+      "info string bench depth {} nodes {} time {} nps {}",
+      depth,
+      result.nodes,
+      result.elapsed.as_millis(),
+      result.nodes * 1000 / millis
+    ));
+    Ok(())
+  }
+
+  fn stop_search(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    self.wait_for_search();
+  }
+
+  fn wait_for_search(&mut self) {
+    if let Some(handle) = self.search_thread.take() {
+      handle.join().unwrap();
+    }
+  }
+
+  fn send(&self, line: &str) {
+    send_line(&self.output, line);
+  }
+}
+
+fn format_info(info: &SearchInfo) -> String {
+  let score = match info.score {
+    Score::Centipawns(cp) => format!("cp {}", cp),
+    Score::Mate(moves) => format!("mate {}", moves),
+  };
+  let millis = info.elapsed.as_millis().max(1) as u64;
+  let pv: Vec<String> = info.pv.iter().map(|m| m.to_long_algebraic()).collect();
+
+  format!(
+    "info depth {} multipv {} score {} nodes {} nps {} time {} pv {}",
+    info.depth,
+    info.multipv,
+    score,
+    info.nodes,
+    info.nodes * 1000 / millis,
+    info.elapsed.as_millis(),
+    pv.join(" ")
+  )
+}
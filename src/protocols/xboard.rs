@@ -0,0 +1,392 @@
+use crate::chess_move::Move;
+use crate::engine::Engine;
+use crate::engine::search::{Score, SearchInfo, SearchLimits};
+use crate::game::{Game, GameResult};
+use crate::pieces::types::color::Color;
+use crate::protocols::send_line;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const ENGINE_NAME: &str = "rust_chess";
+
+// Thinking time per move when no time control was given
+const DEFAULT_MOVE_TIME: Duration = Duration::from_secs(5);
+
+// Commands xboard sends that don't change anything for this engine
+const IGNORED_COMMANDS: [&str; 7] = [
+  "xboard", "accepted", "rejected", "hard", "easy", "random", "computer",
+];
+
+#[derive(Clone, Copy)]
+struct TimeControl {
+  // 0 means the whole game has to be played within the base time
+  moves_per_session: u32,
+  base: Duration,
+  increment: Duration,
+}
+
+// Speaks the Chess Engine Communication Protocol (version 2) used by xboard
+// and WinBoard
+pub struct XBoard<W: Write + Send + 'static> {
+  game: Arc<Mutex<Game>>,
+  engine: Arc<Mutex<Engine>>,
+  output: Arc<Mutex<W>>,
+  stop: Arc<AtomicBool>,
+  // Set when the search is interrupted and its move must be thrown away
+  abort: Arc<AtomicBool>,
+  search_thread: Option<JoinHandle<()>>,
+  force_mode: bool,
+  engine_color: Color,
+  post: bool,
+  time_control: Option<TimeControl>,
+  engine_time: Option<Duration>,
+  depth_limit: Option<u32>,
+}
+
+impl<W: Write + Send + 'static> XBoard<W> {
+  pub fn new(output: W) -> Self {
+    XBoard {
+      game: Arc::new(Mutex::new(Game::new(Color::White))),
+      engine: Arc::new(Mutex::new(Engine::new())),
+      output: Arc::new(Mutex::new(output)),
+      stop: Arc::new(AtomicBool::new(false)),
+      abort: Arc::new(AtomicBool::new(false)),
+      search_thread: None,
+      force_mode: false,
+      engine_color: Color::Black,
+      post: false,
+      time_control: None,
+      engine_time: None,
+      depth_limit: None,
+    }
+  }
+
+  pub fn run(&mut self, input: impl BufRead) {
+    for line in input.lines() {
+      let Ok(line) = line else { break };
+      if !self.handle_command(&line) {
+        return;
+      }
+    }
+
+    self.wait_for_search();
+  }
+
+  // Returns false once the engine should exit
+  pub fn handle_command(&mut self, line: &str) -> bool {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let Some((command, args)) = tokens.split_first() else {
+      return true;
+    };
+
+    let res = match *command {
+      "protover" => {
+        self.send(&format!(
+          "feature myname=\"{}\" usermove=1 setboard=1 ping=1 colors=0 \
+           smp=1 egt=\"syzygy\" sigint=0 sigterm=0 done=1",
+          ENGINE_NAME
+        ));
+        Ok(())
+      }
+      "new" => {
+        self.abort_search();
+        *self.game.lock().unwrap() = Game::new(Color::White);
+        self.engine.lock().unwrap().new_game();
+        self.force_mode = false;
+        self.engine_color = Color::Black;
+        self.engine_time = None;
+        self.depth_limit = None;
+        Ok(())
+      }
+      "setboard" => {
+        self.abort_search();
+        Game::from_fen(&args.join(" "))
+          .map(|game| *self.game.lock().unwrap() = game)
+      }
+      "usermove" => self.user_move(args),
+      "go" => {
+        self.force_mode = false;
+        self.engine_color = self.game.lock().unwrap().player_color();
+        self.think();
+        Ok(())
+      }
+      "force" => {
+        self.abort_search();
+        self.force_mode = true;
+        Ok(())
+      }
+      "?" => {
+        // Move now with the best move found so far
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait_for_search();
+        Ok(())
+      }
+      "level" => self.set_level(args),
+      "time" => self.parse_centiseconds(args).map(|time| {
+        self.engine_time = Some(time);
+      }),
+      "otim" => self.parse_centiseconds(args).map(|_| ()),
+      "cores" => {
+        self.abort_search();
+        let cores = args.first().copied().unwrap_or("");
+        self
+          .engine
+          .lock()
+          .unwrap()
+          .set_option("Threads", cores)
+          .map_err(|_| format!("Error (invalid core count): {}", cores))
+      }
+      "egtpath" => {
+        self.abort_search();
+        // egtpath <type> <path>, where the path may contain spaces
+        match args.split_first() {
+          Some((&"syzygy", path)) => self
+            .engine
+            .lock()
+            .unwrap()
+            .set_option("SyzygyPath", &path.join(" "))
+            .map_err(|e| format!("Error ({}): egtpath", e)),
+          _ => Err(format!("Error (unsupported tablebases): {}", line)),
+        }
+      }
+      "sd" => args
+        .first()
+        .and_then(|arg| arg.parse::<u32>().ok())
+        .map(|depth| self.depth_limit = Some(depth))
+        .ok_or(format!("Error (invalid depth): {}", args.join(" "))),
+      "undo" => {
+        self.abort_search();
+        self
+          .game
+          .lock()
+          .unwrap()
+          .undo()
+          .map(|_| ())
+          .map_err(|_| "Error (no move to undo): undo".to_string())
+      }
+      "result" => {
+        self.abort_search();
+        self.force_mode = true;
+        Ok(())
+      }
+      "ping" => {
+        self.send(&format!("pong {}", args.join(" ")));
+        Ok(())
+      }
+      "post" | "nopost" => {
+        self.post = *command == "post";
+        Ok(())
+      }
+      "quit" => {
+        self.abort_search();
+        return false;
+      }
+      command if IGNORED_COMMANDS.contains(&command) => Ok(()),
+      _ => Err(format!("Error (unknown command): {}", command)),
+    };
+
+    if let Err(e) = res {
+      self.send(&e);
+    }
+    true
+  }
+
+  fn user_move(&mut self, args: &[&str]) -> Result<(), String> {
+    let text = args.first().ok_or("Error (missing move): usermove")?;
+
+    {
+      let mut game = self.game.lock().unwrap();
+      let chess_move = Move::from_long_algebraic(text, game.player_color())
+        .map_err(|_| format!("Illegal move: {}", text))?;
+      game
+        .play_move(chess_move)
+        .map_err(|_| format!("Illegal move: {}", text))?;
+
+      if let Some(result) = game.result() {
+        drop(game);
+        self.send(&format_result(result));
+        return Ok(());
+      }
+    }
+
+    if !self.force_mode
+      && self.game.lock().unwrap().player_color() == self.engine_color
+    {
+      self.think();
+    }
+    Ok(())
+  }
+
+  fn think(&mut self) {
+    self.stop_search();
+
+    let game = self.game.lock().unwrap().clone();
+    if game.result().is_some() {
+      return;
+    }
+
+    let limits = self.search_limits(&game);
+    let shared_game = Arc::clone(&self.game);
+    let engine = Arc::clone(&self.engine);
+    let output = Arc::clone(&self.output);
+    let stop = Arc::clone(&self.stop);
+    let abort = Arc::clone(&self.abort);
+    let post = self.post;
+
+    self.stop.store(false, Ordering::Relaxed);
+    self.abort.store(false, Ordering::Relaxed);
+
+    self.search_thread = Some(thread::spawn(move || {
+      let mut engine = engine.lock().unwrap();
+      let result = engine.search(&game, limits, stop, &mut |info| {
+        if post {
+          send_line(&output, &format_thinking(info));
+        }
+      });
+
+      if abort.load(Ordering::Relaxed) {
+        return;
+      }
+      let Some(best_move) = result.best_move else {
+        return;
+      };
+
+      let mut game = shared_game.lock().unwrap();
+      if game.play_move(best_move).is_err() {
+        return;
+      }
+      send_line(&output, &format!("move {}", best_move.to_long_algebraic()));
+      if let Some(result) = game.result() {
+        send_line(&output, &format_result(result));
+      }
+    }));
+  }
+
+  fn search_limits(&self, game: &Game) -> SearchLimits {
+    let Some(time_control) = self.time_control else {
+      return SearchLimits {
+        depth: self.depth_limit,
+        movetime: Some(DEFAULT_MOVE_TIME),
+        ..Default::default()
+      };
+    };
+
+    let moves_to_go = match time_control.moves_per_session {
+      0 => None,
+      moves_per_session => {
+        // Moves the engine has played since the start of the game
+        let engine_moves = (game.moves().len() / 2) as u32;
+        Some(moves_per_session - engine_moves % moves_per_session)
+      }
+    };
+
+    SearchLimits {
+      depth: self.depth_limit,
+      time_left: Some(self.engine_time.unwrap_or(time_control.base)),
+      increment: time_control.increment,
+      moves_to_go,
+      ..Default::default()
+    }
+  }
+
+  // level <moves per session> <base as minutes[:seconds]> <increment seconds>
+  fn set_level(&mut self, args: &[&str]) -> Result<(), String> {
+    let error = || format!("Error (invalid level): {}", args.join(" "));
+    if args.len() != 3 {
+      return Err(error());
+    }
+
+    let moves_per_session = args[0].parse::<u32>().map_err(|_| error())?;
+    let base = match args[1].split_once(':') {
+      Some((minutes, seconds)) => {
+        let minutes = minutes.parse::<u64>().map_err(|_| error())?;
+        let seconds = seconds.parse::<u64>().map_err(|_| error())?;
+        Duration::from_secs(minutes * 60 + seconds)
+      }
+      None => {
+        Duration::from_secs(args[1].parse::<u64>().map_err(|_| error())? * 60)
+      }
+    };
+    let increment = args[2].parse::<f64>().map_err(|_| error())?;
+
+    self.time_control = Some(TimeControl {
+      moves_per_session,
+      base,
+      increment: Duration::from_secs_f64(increment.max(0.0)),
+    });
+    Ok(())
+  }
+
+  fn parse_centiseconds(&self, args: &[&str]) -> Result<Duration, String> {
+    let centiseconds = args
+      .first()
+      .and_then(|arg| arg.parse::<i64>().ok())
+      .ok_or(format!("Error (invalid time): {}", args.join(" ")))?;
+    Ok(Duration::from_millis(centiseconds.max(0) as u64 * 10))
+  }
+
+  // Interrupts the search without playing its move
+  fn abort_search(&mut self) {
+    self.abort.store(true, Ordering::Relaxed);
+    self.stop_search();
+  }
+
+  fn stop_search(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    self.wait_for_search();
+  }
+
+  fn wait_for_search(&mut self) {
+    if let Some(handle) = self.search_thread.take() {
+      handle.join().unwrap();
+    }
+  }
+
+  fn send(&self, line: &str) {
+    send_line(&self.output, line);
+  }
+}
+
+fn format_result(result: GameResult) -> String {
+  match result {
+    GameResult::Checkmate {
+      winner: Color::White,
+    } => "1-0 {White mates}".to_string(),
+    GameResult::Checkmate {
+      winner: Color::Black,
+    } => "0-1 {Black mates}".to_string(),
+    GameResult::Stalemate => "1/2-1/2 {Stalemate}".to_string(),
+    GameResult::Timeout {
+      winner: Color::White,
+    } => "1-0 {Black forfeits on time}".to_string(),
+    GameResult::Timeout {
+      winner: Color::Black,
+    } => "0-1 {White forfeits on time}".to_string(),
+    GameResult::TimeoutDraw => {
+      "1/2-1/2 {Time forfeit with insufficient material}".to_string()
+    }
+  }
+}
+
+// Thinking output: ply, score, time in centiseconds, nodes and the PV
+fn format_thinking(info: &SearchInfo) -> String {
+  let score = match info.score {
+    Score::Centipawns(cp) => cp,
+    // xboard's convention for mate scores
+    Score::Mate(moves) if moves > 0 => 100_000 + moves,
+    Score::Mate(moves) => -100_000 + moves,
+  };
+  let pv: Vec<String> = info.pv.iter().map(|m| m.to_long_algebraic()).collect();
+
+  format!(
+    "{} {} {} {} {}",
+    info.depth,
+    score,
+    info.elapsed.as_millis() / 10,
+    info.nodes,
+    pv.join(" ")
+  )
+}
@@ -0,0 +1,239 @@
+// Serde support behind the `serde` feature. The JSON is meant to stay
+// stable: squares are written like "e4", pieces as their FEN letter and
+// colors as "white" or "black".
+use crate::chess_move::Move;
+use crate::chessboard::{Chessboard, ChessboardType, MoveResult};
+use crate::game::Game;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::array::from_fn;
+use std::collections::BTreeMap;
+
+impl Serialize for Position {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_algebraic())
+  }
+}
+
+impl<'de> Deserialize<'de> for Position {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let square = String::deserialize(deserializer)?;
+    Position::from_algebraic(&square).map_err(D::Error::custom)
+  }
+}
+
+impl Serialize for Color {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match self {
+      Color::White => "white",
+      Color::Black => "black",
+    })
+  }
+}
+
+impl<'de> Deserialize<'de> for Color {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+      "white" => Ok(Color::White),
+      "black" => Ok(Color::Black),
+      color => Err(D::Error::custom(format!("Invalid color: {}", color))),
+    }
+  }
+}
+
+impl Serialize for Piece {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_char(self.symbol())
+  }
+}
+
+impl<'de> Deserialize<'de> for Piece {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let symbol = String::deserialize(deserializer)?;
+    let mut chars = symbol.chars();
+    match (chars.next().and_then(Piece::from_symbol), chars.next()) {
+      (Some(piece), None) => Ok(piece),
+      _ => Err(D::Error::custom(format!("Invalid piece: {}", symbol))),
+    }
+  }
+}
+
+// {"from": "e7", "to": "e8", "promotion": "Q"}, without the promotion for
+// other moves
+#[derive(Serialize, Deserialize)]
+struct MoveFields {
+  from: Position,
+  to: Position,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  promotion: Option<Piece>,
+}
+
+impl Serialize for Move {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    MoveFields {
+      from: self.from(),
+      to: self.to(),
+      promotion: self.promotion().copied(),
+    }
+    .serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Move {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let fields = MoveFields::deserialize(deserializer)?;
+    Ok(match fields.promotion {
+      Some(promotion) => {
+        Move::with_promotion(fields.from, fields.to, promotion)
+      }
+      None => Move::new(fields.from, fields.to),
+    })
+  }
+}
+
+impl Serialize for MoveResult {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match self {
+      MoveResult::None => "none",
+      MoveResult::CanUpgradePiece => "can_upgrade_piece",
+      MoveResult::CheckKing => "check_king",
+    })
+  }
+}
+
+impl<'de> Deserialize<'de> for MoveResult {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+      "none" => Ok(MoveResult::None),
+      "can_upgrade_piece" => Ok(MoveResult::CanUpgradePiece),
+      "check_king" => Ok(MoveResult::CheckKing),
+      result => {
+        Err(D::Error::custom(format!("Invalid move result: {}", result)))
+      }
+    }
+  }
+}
+
+// Occupied squares only, from a1 to h8 by rows, then the dead pieces of
+// each side
+#[derive(Serialize, Deserialize)]
+struct ChessboardFields {
+  pieces: BTreeMap<Position, Piece>,
+  white_dead_pieces: Vec<Piece>,
+  black_dead_pieces: Vec<Piece>,
+}
+
+impl Serialize for Chessboard {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let pieces = self
+      .get_all_positions()
+      .into_iter()
+      .filter_map(|position| Some((position, *self.get_piece(position)?)))
+      .collect();
+    ChessboardFields {
+      pieces,
+      white_dead_pieces: self.white_dead_pieces().clone(),
+      black_dead_pieces: self.black_dead_pieces().clone(),
+    }
+    .serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Chessboard {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let fields = ChessboardFields::deserialize(deserializer)?;
+    let dead_pieces = |pieces: Vec<Piece>, color: Color| match pieces
+      .iter()
+      .find(|piece| !piece.is_of_color(color))
+    {
+      Some(piece) => Err(D::Error::custom(format!(
+        "Invalid dead piece for {}: {}",
+        color,
+        piece.symbol()
+      ))),
+      None => Ok(pieces),
+    };
+
+    let mut board: ChessboardType = from_fn(|_| from_fn(|_| None));
+    for (position, piece) in fields.pieces {
+      board[position.x()][position.y()] = Some(piece);
+    }
+    Ok(Chessboard::new(
+      board,
+      dead_pieces(fields.white_dead_pieces, Color::White)?,
+      dead_pieces(fields.black_dead_pieces, Color::Black)?,
+    ))
+  }
+}
+
+// The game is stored as its starting position and moves, from which the
+// history is rebuilt. The current position is there for readers of the
+// JSON and has to agree with the moves.
+#[derive(Serialize, Deserialize)]
+struct GameFields {
+  initial_board: Chessboard,
+  initial_player_color: Color,
+  moves: Vec<Move>,
+  board: Chessboard,
+  player_color: Color,
+}
+
+impl Serialize for Game {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    GameFields {
+      initial_board: self.initial_chessboard().clone(),
+      initial_player_color: self.initial_player_color(),
+      moves: self.moves().to_vec(),
+      board: self.chessboard().clone(),
+      player_color: self.player_color(),
+    }
+    .serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Game {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let fields = GameFields::deserialize(deserializer)?;
+    let mut game =
+      Game::with_chessboard(fields.initial_board, fields.initial_player_color);
+    for (ply, chess_move) in fields.moves.into_iter().enumerate() {
+      game.play_move(chess_move).map_err(|e| {
+        D::Error::custom(format!(
+          "Move {} ({}): {}",
+          ply + 1,
+          chess_move.to_long_algebraic(),
+          e
+        ))
+      })?;
+    }
+
+    let board = &fields.board;
+    if game.player_color() != fields.player_color
+      || game.chessboard().to_fen_placement() != board.to_fen_placement()
+      || game.chessboard().white_dead_pieces() != board.white_dead_pieces()
+      || game.chessboard().black_dead_pieces() != board.black_dead_pieces()
+    {
+      return Err(D::Error::custom(
+        "The board and player color don't match the moves",
+      ));
+    }
+    Ok(game)
+  }
+}
@@ -0,0 +1,456 @@
+pub mod table;
+
+use crate::chess_move::Move;
+use crate::chessboard::Chessboard;
+use crate::move_generator::MoveGenerator;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::tablebase::table::{
+  BLACK, KING, MAX_PIECES, PAWN, Table, TableKind,
+};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+// Piece letters of table names, in the order they are written
+const NAME_PIECES: [(char, u8); 6] = [
+  ('K', KING),
+  ('Q', 5),
+  ('R', 4),
+  ('B', 3),
+  ('N', 2),
+  ('P', PAWN),
+];
+
+// Result for the side to move. Cursed wins and blessed losses are the ones
+// the fifty move rule turns into draws in regular chess; this game has no
+// such rule, so they are still wins and losses here.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Wdl {
+  Loss = -2,
+  BlessedLoss = -1,
+  Draw = 0,
+  CursedWin = 1,
+  Win = 2,
+}
+
+impl Wdl {
+  fn from_value(value: i32) -> Self {
+    match value {
+      ..=-2 => Wdl::Loss,
+      -1 => Wdl::BlessedLoss,
+      0 => Wdl::Draw,
+      1 => Wdl::CursedWin,
+      _ => Wdl::Win,
+    }
+  }
+
+  fn value(self) -> i32 {
+    self as i32
+  }
+
+  // 1 for wins, -1 for losses and 0 for draws
+  pub fn signum(self) -> i32 {
+    self.value().signum()
+  }
+
+  fn negate(self) -> Self {
+    Self::from_value(-self.value())
+  }
+}
+
+// A legal move and the result it leads to. `dtz` counts the plies until
+// the next capture or pawn move for the winning side, and is negative when
+// losing; the game goes on from a smaller table after that.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RootMove {
+  pub chess_move: Move,
+  pub wdl: Wdl,
+  pub dtz: i32,
+}
+
+struct TableFiles {
+  wdl: Table,
+  dtz: Option<Table>,
+}
+
+// Syzygy endgame tablebases: WDL tables tell whether a position is won,
+// drawn or lost, and DTZ tables how to make progress. Files are found by
+// name in the given directories and read when first needed.
+pub struct Tablebase {
+  // By table name, e.g. "KRvK"
+  tables: HashMap<String, TableFiles>,
+  max_pieces: usize,
+}
+
+impl Tablebase {
+  // Directories are separated like in the PATH environment variable
+  pub fn open(paths: &str) -> Result<Self, String> {
+    let mut tables = HashMap::new();
+    let mut max_pieces = 0;
+
+    let directories: Vec<_> = env::split_paths(paths).collect();
+    for directory in &directories {
+      let entries = fs::read_dir(directory).map_err(|e| {
+        format!("Failed to read {}: {}", directory.display(), e)
+      })?;
+
+      for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "rtbw") {
+          continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+          continue;
+        };
+        let Some(material) = parse_name(name) else {
+          continue;
+        };
+
+        let dtz = directories
+          .iter()
+          .map(|directory| directory.join(format!("{}.rtbz", name)))
+          .find(|path| path.is_file())
+          .map(|path| Table::new(TableKind::Dtz, &path, &material));
+
+        max_pieces = max_pieces.max(material.iter().flatten().sum());
+        tables.insert(
+          name.to_string(),
+          TableFiles {
+            wdl: Table::new(TableKind::Wdl, &path, &material),
+            dtz,
+          },
+        );
+      }
+    }
+
+    Ok(Tablebase { tables, max_pieces })
+  }
+
+  pub fn len(&self) -> usize {
+    self.tables.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.tables.is_empty()
+  }
+
+  // Most pieces in any table found
+  pub fn max_pieces(&self) -> usize {
+    self.max_pieces
+  }
+
+  // Cheap check to skip probing positions with too many pieces
+  pub fn covers(&self, chessboard: &Chessboard) -> bool {
+    let pieces = chessboard.board().iter().flatten().flatten().count();
+    pieces <= self.max_pieces
+  }
+
+  // Result for the side to move
+  pub fn probe_wdl(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+  ) -> Result<Wdl, String> {
+    self.search(chessboard, color, false).map(|(wdl, _)| wdl)
+  }
+
+  // Plies until the next capture or pawn move with the best play, positive
+  // when winning and negative when losing, or 0 for draws. A win is ahead
+  // right after a capture or pawn move when this is 1, and a mate when it
+  // is -1. The count can be one ply too high.
+  pub fn probe_dtz(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+  ) -> Result<i32, String> {
+    let (wdl, zeroing_is_best) = self.search(chessboard, color, true)?;
+    if wdl == Wdl::Draw {
+      return Ok(0);
+    }
+    // The table doesn't store the best value in that case
+    if zeroing_is_best {
+      return Ok(dtz_before_zeroing(wdl));
+    }
+
+    if let Some(dtz) =
+      self.probe_table(chessboard, color, TableKind::Dtz, wdl)?
+    {
+      let cursed = matches!(wdl, Wdl::CursedWin | Wdl::BlessedLoss);
+      return Ok((dtz + if cursed { 100 } else { 0 }) * wdl.signum());
+    }
+
+    // The table only has the other side to move, so look one move ahead
+    // for the quickest win or the slowest loss
+    let mut min_dtz = i32::MAX;
+    let move_generator = MoveGenerator::new(chessboard);
+    for chess_move in move_generator.legal_moves(color) {
+      let zeroing = is_zeroing(chessboard, &chess_move);
+      let Some(child) = play(chessboard, &chess_move) else {
+        continue;
+      };
+
+      let mut dtz = if zeroing {
+        -dtz_before_zeroing(self.probe_wdl(&child, color.next())?)
+      } else {
+        -self.probe_dtz(&child, color.next())?
+      };
+
+      if dtz == 1 && is_mate(&child, color.next()) {
+        min_dtz = 1;
+      }
+      if !zeroing {
+        dtz += dtz.signum();
+      }
+      if dtz < min_dtz && dtz.signum() == wdl.signum() {
+        min_dtz = dtz;
+      }
+    }
+
+    // No legal move: mated
+    Ok(if min_dtz == i32::MAX { -1 } else { min_dtz })
+  }
+
+  // Every legal move with the result it leads to, best first: the fastest
+  // wins, then draws, then the slowest losses
+  pub fn rank_moves(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+  ) -> Result<Vec<RootMove>, String> {
+    let mut root_moves = Vec::new();
+
+    for chess_move in MoveGenerator::new(chessboard).legal_moves(color) {
+      let zeroing = is_zeroing(chessboard, &chess_move);
+      let Some(child) = play(chessboard, &chess_move) else {
+        continue;
+      };
+
+      let wdl = self.probe_wdl(&child, color.next())?.negate();
+      let mut dtz = if zeroing {
+        dtz_before_zeroing(wdl)
+      } else {
+        // One ply more from here
+        let dtz = -self.probe_dtz(&child, color.next())?;
+        dtz + dtz.signum()
+      };
+      if dtz == 2 && is_mate(&child, color.next()) {
+        dtz = 1;
+      }
+
+      root_moves.push(RootMove {
+        chess_move,
+        wdl,
+        dtz,
+      });
+    }
+
+    root_moves.sort_by_key(|root_move| {
+      std::cmp::Reverse((root_move.wdl, -root_move.dtz))
+    });
+    Ok(root_moves)
+  }
+
+  pub fn best_move(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+  ) -> Result<Option<RootMove>, String> {
+    self
+      .rank_moves(chessboard, color)
+      .map(|root_moves| root_moves.first().copied())
+  }
+
+  // Tables don't store positions where a capture wins, or where a capture
+  // or pawn move for DTZ tables draws, since those are found by playing
+  // them. Returns the result and whether such a move is the best one.
+  fn search(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+    include_pawn_moves: bool,
+  ) -> Result<(Wdl, bool), String> {
+    let moves = MoveGenerator::new(chessboard).legal_moves(color);
+    let mut best = Wdl::Loss;
+    let mut searched = 0;
+
+    for chess_move in &moves {
+      let capture = !chessboard.is_position_empty(chess_move.to());
+      let pawn_move = matches!(
+        chessboard.get_piece(chess_move.from()),
+        Some(Piece::Pawn(_))
+      );
+      if !(capture || include_pawn_moves && pawn_move) {
+        continue;
+      }
+      let Some(child) = play(chessboard, chess_move) else {
+        continue;
+      };
+
+      searched += 1;
+      let wdl = self.search(&child, color.next(), false)?.0.negate();
+      if wdl > best {
+        best = wdl;
+        if wdl == Wdl::Win {
+          return Ok((wdl, true));
+        }
+      }
+    }
+
+    // Nothing left to look up when every move was tried
+    let no_more_moves = searched > 0 && searched == moves.len();
+    let wdl = if no_more_moves {
+      best
+    } else {
+      let value = self.probe_table(chessboard, color, TableKind::Wdl, best)?;
+      Wdl::from_value(value.unwrap_or(0))
+    };
+
+    if best >= wdl {
+      return Ok((best, best > Wdl::Draw || no_more_moves));
+    }
+    Ok((wdl, false))
+  }
+
+  fn probe_table(
+    &self,
+    chessboard: &Chessboard,
+    color: Color,
+    kind: TableKind,
+    wdl: Wdl,
+  ) -> Result<Option<i32>, String> {
+    let mut pieces = Vec::new();
+    let mut names = [String::new(), String::new()];
+    for (letter, code) in NAME_PIECES {
+      for position in chessboard.get_all_positions() {
+        let Some(piece) = chessboard.get_piece(position) else {
+          continue;
+        };
+        if piece_code(piece) & !BLACK != code {
+          continue;
+        }
+
+        let side = usize::from(*piece.color() == Color::Black);
+        names[side].push(letter);
+        pieces
+          .push((position.x() * BOARD_SIZE + position.y(), piece_code(piece)));
+
+        if code == PAWN && [0, BOARD_SIZE - 1].contains(&position.x()) {
+          return Err(
+            "Pawns on the last row are not in tablebases".to_string(),
+          );
+        }
+      }
+    }
+    if pieces.len() > self.max_pieces.min(MAX_PIECES) {
+      return Err(format!("Too many pieces for tablebases: {}", pieces.len()));
+    }
+    if names.iter().any(|name| name.matches('K').count() != 1) {
+      return Err("Tablebases need one king per side".to_string());
+    }
+    // King against king
+    if pieces.len() == 2 {
+      return Ok(Some(0));
+    }
+
+    // Tables are stored with one side as white, usually the stronger one
+    let name = format!("{}v{}", names[0], names[1]);
+    let flipped_name = format!("{}v{}", names[1], names[0]);
+    let (files, flipped) = match self.tables.get(&name) {
+      Some(files) => (files, false),
+      None => (
+        self
+          .tables
+          .get(&flipped_name)
+          .ok_or(format!("Missing table: {}", name))?,
+        true,
+      ),
+    };
+    let table = match kind {
+      TableKind::Wdl => &files.wdl,
+      TableKind::Dtz => files
+        .dtz
+        .as_ref()
+        .ok_or(format!("Missing DTZ table: {}", name))?,
+    };
+
+    pieces.sort_unstable();
+    table.probe(&pieces, color == Color::Black, flipped, wdl.value())
+  }
+}
+
+// Pieces by code on the white and black sides of a name like "KRPvKR"
+fn parse_name(name: &str) -> Option<[[usize; 7]; 2]> {
+  let (white, black) = name.split_once('v')?;
+  let mut material = [[0; 7]; 2];
+
+  for (side, pieces) in [white, black].into_iter().enumerate() {
+    for letter in pieces.chars() {
+      let (_, code) = NAME_PIECES.iter().find(|(l, _)| *l == letter)?;
+      material[side][*code as usize] += 1;
+    }
+    if material[side][KING as usize] != 1 {
+      return None;
+    }
+  }
+
+  let pieces: usize = material.iter().flatten().sum();
+  (pieces <= MAX_PIECES).then_some(material)
+}
+
+fn piece_code(piece: &Piece) -> u8 {
+  let code = match piece {
+    Piece::Pawn(_) => PAWN,
+    Piece::Knight(_) => 2,
+    Piece::Bishop(_) => 3,
+    Piece::Rook(_) => 4,
+    Piece::Queen(_) => 5,
+    Piece::King(_) => KING,
+  };
+  if *piece.color() == Color::Black {
+    code | BLACK
+  } else {
+    code
+  }
+}
+
+// Captures and pawn moves, after which the count to zeroing restarts
+fn is_zeroing(chessboard: &Chessboard, chess_move: &Move) -> bool {
+  !chessboard.is_position_empty(chess_move.to())
+    || matches!(
+      chessboard.get_piece(chess_move.from()),
+      Some(Piece::Pawn(_))
+    )
+}
+
+// Leaves out moves to positions the tables don't have: a pawn upgraded to
+// a dead pawn stays on the last row
+fn play(chessboard: &Chessboard, chess_move: &Move) -> Option<Chessboard> {
+  let mut child = chessboard.clone();
+  child.apply_move(chess_move).ok()?;
+
+  let last_rows = [&child.board()[0], &child.board()[BOARD_SIZE - 1]];
+  let stuck_pawn = last_rows
+    .into_iter()
+    .flatten()
+    .any(|square| matches!(square, Some(Piece::Pawn(_))));
+  (!stuck_pawn).then_some(child)
+}
+
+fn is_mate(chessboard: &Chessboard, color: Color) -> bool {
+  let move_generator = MoveGenerator::new(chessboard);
+  move_generator.is_king_in_check(color)
+    && !move_generator.has_legal_moves(color)
+}
+
+// DTZ of a position whose best move is a capture or pawn move
+fn dtz_before_zeroing(wdl: Wdl) -> i32 {
+  match wdl {
+    Wdl::Win => 1,
+    Wdl::CursedWin => 101,
+    Wdl::BlessedLoss => -101,
+    Wdl::Loss => -1,
+    Wdl::Draw => 0,
+  }
+}
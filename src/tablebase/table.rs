@@ -0,0 +1,868 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Largest tables published, with 7 pieces
+pub const MAX_PIECES: usize = 7;
+
+// Piece codes as stored in the tables: white pawn to king, then black
+pub const PAWN: u8 = 1;
+pub const KING: u8 = 6;
+pub const BLACK: u8 = 8;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+// First byte of a table file
+const HEADER_HAS_PAWNS: u8 = 2;
+
+// Flags of each sub-table
+const FLAG_STM: u8 = 1;
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_WIDE: u8 = 16;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+// Sparse index entries: a block number and an offset within the block
+const SPARSE_ENTRY_SIZE: usize = 6;
+// Huffman tree nodes: two 12 bit symbols
+const TREE_NODE_SIZE: usize = 3;
+// Right symbol of a leaf
+const LEAF: usize = 0xFFF;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TableKind {
+  Wdl,
+  Dtz,
+}
+
+// Compression and indexing data of one sub-table. WDL tables have one per
+// side to move unless both sides have the same pieces, DTZ tables store one
+// side only, and tables with pawns have one per file of the leading pawn.
+#[derive(Clone, Default)]
+struct PairsData {
+  flags: u8,
+  // Pieces in the order they are encoded, which groups them
+  pieces: [u8; MAX_PIECES],
+  group_len: [usize; MAX_PIECES + 1],
+  // Multiplier of each group's index, and the table size after the last
+  group_idx: [u64; MAX_PIECES + 1],
+  block_size: usize,
+  // There's a sparse index entry about every `span` values
+  span: u64,
+  num_blocks: usize,
+  max_sym_len: u32,
+  // Also the value of single value tables
+  min_sym_len: u32,
+  // Offsets into the file
+  lowest_sym: usize,
+  tree: usize,
+  sparse_index: usize,
+  sparse_index_size: usize,
+  block_lengths: usize,
+  block_lengths_size: usize,
+  data: usize,
+  // Lowest code of each length, left aligned
+  base64: Vec<u64>,
+  // Number of values, minus one, each symbol expands to
+  symlen: Vec<u8>,
+  // Where DTZ values are mapped back for wins, losses, cursed wins and
+  // blessed losses
+  map_idx: [usize; 4],
+}
+
+struct TableData {
+  bytes: Vec<u8>,
+  // Indexed by side to move, then by file of the leading pawn
+  pairs: Vec<Vec<PairsData>>,
+  map: usize,
+}
+
+// A `.rtbw` or `.rtbz` file, read on first use
+pub struct Table {
+  kind: TableKind,
+  path: PathBuf,
+  piece_count: usize,
+  has_pawns: bool,
+  // Some piece other than a king is alone of its kind and color
+  has_unique_pieces: bool,
+  // Pawns of the leading color, the one with fewer pawns, then the other
+  pawn_count: [usize; 2],
+  // Both sides have the same pieces, so only white to move is stored
+  symmetric: bool,
+  data: OnceLock<Result<TableData, String>>,
+}
+
+impl Table {
+  // `material` counts the pieces of the table's white and black sides by
+  // code, e.g. from its file name
+  pub fn new(kind: TableKind, path: &Path, material: &[[usize; 7]; 2]) -> Self {
+    let pawns = [material[0][PAWN as usize], material[1][PAWN as usize]];
+    let leading = if pawns[1] == 0 || (pawns[0] > 0 && pawns[1] >= pawns[0]) {
+      0
+    } else {
+      1
+    };
+
+    Table {
+      kind,
+      path: path.to_path_buf(),
+      piece_count: material.iter().flatten().sum(),
+      has_pawns: pawns[0] + pawns[1] > 0,
+      has_unique_pieces: material
+        .iter()
+        .any(|side| side[PAWN as usize..KING as usize].contains(&1)),
+      pawn_count: [pawns[leading], pawns[1 - leading]],
+      symmetric: material[0] == material[1],
+      data: OnceLock::new(),
+    }
+  }
+
+  // Looks up a position given as (square, piece code) pairs, with squares
+  // numbered from a1 to h8 by rows. `flipped` tells that the position's
+  // white pieces are the table's black ones. For DTZ tables, `wdl` is the
+  // position's result and None means that the table only stores the other
+  // side to move.
+  pub fn probe(
+    &self,
+    pieces: &[(usize, u8)],
+    black_to_move: bool,
+    flipped: bool,
+    wdl: i32,
+  ) -> Result<Option<i32>, String> {
+    let data = self
+      .data
+      .get_or_init(|| self.load())
+      .as_ref()
+      .map_err(Clone::clone)?;
+    let encoding = encoding();
+
+    let flip = flipped || (self.symmetric && black_to_move);
+    let flip_color = if flip { BLACK } else { 0 };
+    let flip_squares = if flip { 56 } else { 0 };
+    let stm = usize::from(flip) ^ usize::from(black_to_move);
+
+    let mut squares = Vec::with_capacity(pieces.len());
+    let mut codes = Vec::with_capacity(pieces.len());
+
+    // Tables with pawns are split by the file of the leading pawn, the one
+    // most toward the edge and then lowest
+    let mut lead_pawns = 0;
+    let mut file = 0;
+    let lead_pawn = data.pairs[0][0].pieces[0] ^ flip_color;
+    if self.has_pawns {
+      for &(square, code) in pieces {
+        if code == lead_pawn {
+          squares.push(square ^ flip_squares);
+          codes.push(code ^ flip_color);
+        }
+      }
+      lead_pawns = squares.len();
+      let lead = (0..lead_pawns)
+        .max_by_key(|&i| encoding.map_pawns[squares[i]])
+        .ok_or_else(|| self.corrupted())?;
+      squares.swap(0, lead);
+      file = (squares[0] & 7).min(7 - (squares[0] & 7));
+    }
+
+    if self.kind == TableKind::Dtz {
+      let flags = data.pairs[0][file].flags;
+      // Symmetric tables without pawns store both sides the same way
+      let both_sides = self.symmetric && !self.has_pawns;
+      if !both_sides && usize::from(flags & FLAG_STM) != stm {
+        return Ok(None);
+      }
+    }
+
+    for &(square, code) in pieces {
+      if !(self.has_pawns && code == lead_pawn) {
+        squares.push(square ^ flip_squares);
+        codes.push(code ^ flip_color);
+      }
+    }
+    if squares.len() != self.piece_count {
+      return Err(format!("Wrong material for {}", self.path.display()));
+    }
+
+    let d = &data.pairs[stm % data.pairs.len()][file];
+
+    // Same order as the table's pieces
+    for i in lead_pawns..squares.len().saturating_sub(1) {
+      if let Some(j) = (i + 1..squares.len()).find(|&j| codes[j] == d.pieces[i])
+      {
+        codes.swap(i, j);
+        squares.swap(i, j);
+      }
+    }
+
+    // The leading piece goes to the a1-d1-d4 triangle
+    if squares[0] & 7 > 3 {
+      squares.iter_mut().for_each(|square| *square ^= 7);
+    }
+
+    let mut idx;
+    if self.has_pawns {
+      idx = encoding.lead_pawn_idx[lead_pawns][squares[0]];
+      squares[1..lead_pawns].sort_by_key(|&square| encoding.map_pawns[square]);
+      for (i, &square) in squares.iter().enumerate().take(lead_pawns).skip(1) {
+        idx += encoding.binomial[i][encoding.map_pawns[square]];
+      }
+    } else {
+      if squares[0] >> 3 > 3 {
+        squares.iter_mut().for_each(|square| *square ^= 56);
+      }
+
+      // The first leading piece off the a1-h8 diagonal goes below it
+      for i in 0..d.group_len[0] {
+        match off_diagonal(squares[i]) {
+          0 => continue,
+          off if off > 0 => {
+            for square in &mut squares[i..] {
+              *square = ((*square >> 3) | (*square << 3)) & 63;
+            }
+          }
+          _ => (),
+        }
+        break;
+      }
+
+      idx = if self.has_unique_pieces {
+        self.encode_unique_pieces(&squares)
+      } else {
+        encoding.map_kk[encoding.map_a1d1d4[squares[0]]][squares[1]]
+      };
+    }
+
+    // Then every other group, as combinations of the squares left
+    idx *= d.group_idx[0];
+    let mut start = d.group_len[0];
+    let mut remaining_pawns = self.has_pawns && self.pawn_count[1] > 0;
+    let mut next = 1;
+    while d.group_len[next] > 0 {
+      let len = d.group_len[next];
+      squares[start..start + len].sort_unstable();
+
+      let mut n = 0;
+      for i in 0..len {
+        let square = squares[start + i];
+        let taken = squares[..start].iter().filter(|&&s| square > s).count();
+        let pawn_rows = if remaining_pawns { 8 } else { 0 };
+        n += encoding.binomial[i + 1][square - taken - pawn_rows];
+      }
+
+      remaining_pawns = false;
+      idx += n * d.group_idx[next];
+      start += len;
+      next += 1;
+    }
+
+    let value =
+      decompress(d, &data.bytes, idx).ok_or_else(|| self.corrupted())?;
+    match self.kind {
+      TableKind::Wdl => Ok(Some(value as i32 - 2)),
+      TableKind::Dtz => map_dtz(d, data, value, wdl)
+        .map(Some)
+        .ok_or_else(|| self.corrupted()),
+    }
+  }
+
+  // Three unique pieces, kings included, are encoded together
+  fn encode_unique_pieces(&self, squares: &[usize]) -> u64 {
+    let encoding = encoding();
+    let [s0, s1, s2] = [squares[0], squares[1], squares[2]];
+    let adjust1 = usize::from(s1 > s0);
+    let adjust2 = usize::from(s2 > s0) + usize::from(s2 > s1);
+    let rank = |square: usize| square >> 3;
+
+    let idx = if off_diagonal(s0) != 0 {
+      (encoding.map_a1d1d4[s0] * 63 + (s1 - adjust1)) * 62 + s2 - adjust2
+    } else if off_diagonal(s1) != 0 {
+      (6 * 63 + rank(s0) * 28 + encoding.map_b1h1h7[s1]) * 62 + s2 - adjust2
+    } else if off_diagonal(s2) != 0 {
+      6 * 63 * 62
+        + 4 * 28 * 62
+        + rank(s0) * 7 * 28
+        + (rank(s1) - adjust1) * 28
+        + encoding.map_b1h1h7[s2]
+    } else {
+      6 * 63 * 62
+        + 4 * 28 * 62
+        + 4 * 7 * 28
+        + rank(s0) * 7 * 6
+        + (rank(s1) - adjust1) * 6
+        + (rank(s2) - adjust2)
+    };
+    idx as u64
+  }
+
+  fn corrupted(&self) -> String {
+    format!("Corrupted table: {}", self.path.display())
+  }
+
+  fn load(&self) -> Result<TableData, String> {
+    let bytes = fs::read(&self.path)
+      .map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?;
+    let magic = match self.kind {
+      TableKind::Wdl => WDL_MAGIC,
+      TableKind::Dtz => DTZ_MAGIC,
+    };
+    if !bytes.starts_with(&magic) {
+      return Err(self.corrupted());
+    }
+    self.parse(bytes).ok_or_else(|| self.corrupted())
+  }
+
+  fn parse(&self, bytes: Vec<u8>) -> Option<TableData> {
+    let header = *bytes.get(4)?;
+    if (header & HEADER_HAS_PAWNS != 0) != self.has_pawns {
+      return None;
+    }
+
+    let sides = match self.kind {
+      TableKind::Wdl if !self.symmetric => 2,
+      _ => 1,
+    };
+    let files = if self.has_pawns { 4 } else { 1 };
+    let both_pawns = self.has_pawns && self.pawn_count[1] > 0;
+    let mut pairs = vec![vec![PairsData::default(); files]; sides];
+
+    let mut offset = 5;
+    for file in 0..files {
+      let order_bytes = [*bytes.get(offset)?, *bytes.get(offset + 1)?];
+      let order = |side: u32| {
+        let remaining = if both_pawns {
+          order_bytes[1] >> (4 * side) & 0xF
+        } else {
+          0xF
+        };
+        [
+          (order_bytes[0] >> (4 * side) & 0xF) as usize,
+          remaining as usize,
+        ]
+      };
+      offset += 1 + usize::from(both_pawns);
+
+      for k in 0..self.piece_count {
+        let byte = *bytes.get(offset)?;
+        for (side, side_pairs) in pairs.iter_mut().enumerate() {
+          side_pairs[file].pieces[k] = byte >> (4 * side) & 0xF;
+        }
+        offset += 1;
+      }
+
+      for (side, side_pairs) in pairs.iter_mut().enumerate() {
+        self.set_groups(&mut side_pairs[file], order(side as u32), file)?;
+      }
+    }
+    offset += offset & 1;
+
+    for file in 0..files {
+      for side_pairs in pairs.iter_mut() {
+        offset = set_sizes(&mut side_pairs[file], &bytes, offset)?;
+      }
+    }
+
+    let map = offset;
+    if self.kind == TableKind::Dtz {
+      offset = set_dtz_map(&mut pairs[0], &bytes, offset)?;
+    }
+
+    for file in 0..files {
+      for side_pairs in pairs.iter_mut() {
+        let d = &mut side_pairs[file];
+        d.sparse_index = offset;
+        offset += d.sparse_index_size * SPARSE_ENTRY_SIZE;
+      }
+    }
+    for file in 0..files {
+      for side_pairs in pairs.iter_mut() {
+        let d = &mut side_pairs[file];
+        d.block_lengths = offset;
+        offset += d.block_lengths_size * 2;
+      }
+    }
+    if offset > bytes.len() {
+      return None;
+    }
+
+    for file in 0..files {
+      for side_pairs in pairs.iter_mut() {
+        let d = &mut side_pairs[file];
+        // Compressed blocks are aligned on 64 bytes
+        offset = (offset + 0x3F) & !0x3F;
+        d.data = offset;
+        offset += d.num_blocks * d.block_size;
+      }
+    }
+
+    Some(TableData { bytes, pairs, map })
+  }
+
+  // Splits the pieces into groups encoded together, and works out each
+  // group's multiplier in the index following the order the table gives
+  fn set_groups(
+    &self,
+    d: &mut PairsData,
+    order: [usize; 2],
+    file: usize,
+  ) -> Option<()> {
+    let encoding = encoding();
+    let mut first_len: i32 = if self.has_pawns {
+      0
+    } else if self.has_unique_pieces {
+      3
+    } else {
+      2
+    };
+
+    let mut n = 0;
+    d.group_len[0] = 1;
+    for i in 1..self.piece_count {
+      first_len -= 1;
+      if first_len > 0 || d.pieces[i] == d.pieces[i - 1] {
+        d.group_len[n] += 1;
+      } else {
+        n += 1;
+        d.group_len[n] = 1;
+      }
+    }
+    n += 1;
+    d.group_len[n] = 0;
+
+    let both_pawns = self.has_pawns && self.pawn_count[1] > 0;
+    let mut next = if both_pawns { 2 } else { 1 };
+    let mut free_squares =
+      64 - d.group_len[0] - if both_pawns { d.group_len[1] } else { 0 };
+    let mut idx: u64 = 1;
+
+    let mut k = 0;
+    while next < n || k == order[0] || k == order[1] {
+      if k == order[0] {
+        d.group_idx[0] = idx;
+        idx *= if self.has_pawns {
+          *encoding.lead_pawns_size.get(d.group_len[0])?.get(file)?
+        } else if self.has_unique_pieces {
+          31332
+        } else {
+          462
+        };
+      } else if k == order[1] {
+        d.group_idx[1] = idx;
+        idx *= encoding.binomial.get(d.group_len[1])?[48 - d.group_len[0]];
+      } else {
+        d.group_idx[next] = idx;
+        idx *= encoding.binomial.get(d.group_len[next])?[free_squares];
+        free_squares -= d.group_len[next];
+        next += 1;
+      }
+      k += 1;
+      if k > MAX_PIECES + 1 {
+        return None;
+      }
+    }
+    d.group_idx[n] = idx;
+    Some(())
+  }
+}
+
+// Reads the Huffman code and symbol tree of a sub-table
+fn set_sizes(d: &mut PairsData, bytes: &[u8], offset: usize) -> Option<usize> {
+  let mut offset = offset;
+  d.flags = *bytes.get(offset)?;
+  offset += 1;
+
+  if d.flags & FLAG_SINGLE_VALUE != 0 {
+    d.min_sym_len = *bytes.get(offset)? as u32;
+    return Some(offset + 1);
+  }
+
+  let groups = d.group_len.iter().position(|len| *len == 0)?;
+  let table_size = d.group_idx[groups];
+
+  d.block_size = 1usize.checked_shl(*bytes.get(offset)? as u32)?;
+  d.span = 1u64.checked_shl(*bytes.get(offset + 1)? as u32)?;
+  d.sparse_index_size = table_size.div_ceil(d.span) as usize;
+  let padding = *bytes.get(offset + 2)? as usize;
+  d.num_blocks = read_u32_le(bytes, offset + 3)? as usize;
+  // Padded so that the sparse index never points past the end
+  d.block_lengths_size = d.num_blocks + padding;
+  d.max_sym_len = *bytes.get(offset + 7)? as u32;
+  d.min_sym_len = *bytes.get(offset + 8)? as u32;
+  offset += 9;
+  if d.min_sym_len == 0 || d.max_sym_len < d.min_sym_len {
+    return None;
+  }
+
+  // Canonical Huffman code: longer codes have lower values, so the lowest
+  // code of each length, left aligned to 64 bits, tells the length of the
+  // code at the start of a bit buffer
+  d.lowest_sym = offset;
+  let lengths = (d.max_sym_len - d.min_sym_len + 1) as usize;
+  d.base64 = vec![0; lengths];
+  for i in (0..lengths - 1).rev() {
+    let lowest = read_u16_le(bytes, d.lowest_sym + 2 * i)? as u64;
+    let next_lowest = read_u16_le(bytes, d.lowest_sym + 2 * i + 2)? as u64;
+    d.base64[i] = d.base64[i + 1]
+      .wrapping_add(lowest)
+      .wrapping_sub(next_lowest)
+      / 2;
+  }
+  if d.max_sym_len > 64 {
+    return None;
+  }
+  for (i, base) in d.base64.iter_mut().enumerate() {
+    *base = base.checked_shl(64 - i as u32 - d.min_sym_len).unwrap_or(0);
+  }
+  offset += lengths * 2;
+
+  let symbols = read_u16_le(bytes, offset)? as usize;
+  offset += 2;
+  d.tree = offset;
+  if bytes.len() < d.tree + symbols * TREE_NODE_SIZE {
+    return None;
+  }
+
+  // Symbols stand for pairs of symbols, recursively
+  d.symlen = vec![0; symbols];
+  let mut visited = vec![false; symbols];
+  for symbol in 0..symbols {
+    if !visited[symbol] {
+      d.symlen[symbol] = set_symlen(d, bytes, symbol, &mut visited)?;
+    }
+  }
+
+  Some(offset + symbols * TREE_NODE_SIZE + (symbols & 1))
+}
+
+fn set_symlen(
+  d: &mut PairsData,
+  bytes: &[u8],
+  symbol: usize,
+  visited: &mut [bool],
+) -> Option<u8> {
+  visited[symbol] = true;
+  let (left, right) = tree_node(d, bytes, symbol)?;
+  if right == LEAF {
+    return Some(0);
+  }
+  if left >= d.symlen.len() || right >= d.symlen.len() {
+    return None;
+  }
+
+  for child in [left, right] {
+    if !visited[child] {
+      d.symlen[child] = set_symlen(d, bytes, child, visited)?;
+    }
+  }
+  Some(d.symlen[left].wrapping_add(d.symlen[right]).wrapping_add(1))
+}
+
+// DTZ values are stored as their rank by frequency, with one mapping back
+// for each result
+fn set_dtz_map(
+  pairs: &mut [PairsData],
+  bytes: &[u8],
+  offset: usize,
+) -> Option<usize> {
+  let map = offset;
+  let mut offset = offset;
+
+  for d in pairs.iter_mut() {
+    if d.flags & FLAG_MAPPED == 0 {
+      continue;
+    }
+    if d.flags & FLAG_WIDE != 0 {
+      offset += offset & 1;
+      for i in 0..4 {
+        d.map_idx[i] = (offset - map) / 2 + 1;
+        offset += 2 * read_u16_le(bytes, offset)? as usize + 2;
+      }
+    } else {
+      for i in 0..4 {
+        d.map_idx[i] = offset - map + 1;
+        offset += *bytes.get(offset)? as usize + 1;
+      }
+    }
+  }
+
+  Some(offset + (offset & 1))
+}
+
+fn map_dtz(
+  d: &PairsData,
+  data: &TableData,
+  value: u32,
+  wdl: i32,
+) -> Option<i32> {
+  // Map index for losses, blessed losses, draws, cursed wins and wins
+  const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+
+  let mut value = value as usize;
+  if d.flags & FLAG_MAPPED != 0 {
+    let idx = d.map_idx[WDL_MAP[(wdl + 2) as usize]];
+    value = if d.flags & FLAG_WIDE != 0 {
+      read_u16_le(&data.bytes, data.map + 2 * (idx + value))? as usize
+    } else {
+      *data.bytes.get(data.map + idx + value)? as usize
+    };
+  }
+
+  // Tables count moves rather than plies where that's exact enough
+  let in_plies = match wdl {
+    2 => d.flags & FLAG_WIN_PLIES != 0,
+    -2 => d.flags & FLAG_LOSS_PLIES != 0,
+    _ => false,
+  };
+  if !in_plies {
+    value *= 2;
+  }
+  Some(value as i32 + 1)
+}
+
+// Finds the value at `idx`: first its block through the sparse index, then
+// its symbol in the block's Huffman code, then the value among the ones the
+// symbol expands to
+fn decompress(d: &PairsData, bytes: &[u8], idx: u64) -> Option<u32> {
+  if d.flags & FLAG_SINGLE_VALUE != 0 {
+    return Some(d.min_sym_len);
+  }
+
+  // Sparse index entry k points at the value k * span + span / 2
+  let k = (idx / d.span) as usize;
+  if k >= d.sparse_index_size {
+    return None;
+  }
+  let entry = d.sparse_index + k * SPARSE_ENTRY_SIZE;
+  let mut block = read_u32_le(bytes, entry)? as usize;
+  let mut offset = read_u16_le(bytes, entry + 4)? as i64;
+  offset += (idx % d.span) as i64 - (d.span / 2) as i64;
+
+  let block_length = |block: usize| {
+    if block >= d.block_lengths_size {
+      return None;
+    }
+    read_u16_le(bytes, d.block_lengths + 2 * block).map(i64::from)
+  };
+  while offset < 0 {
+    block = block.checked_sub(1)?;
+    offset += block_length(block)? + 1;
+  }
+  while offset > block_length(block)? {
+    offset -= block_length(block)? + 1;
+    block += 1;
+  }
+
+  let mut ptr = d.data + block * d.block_size;
+  let mut buffer = read_u64_be(bytes, ptr)?;
+  ptr += 8;
+  let mut buffer_size: u32 = 64;
+  let mut symbol;
+  loop {
+    let mut len = 0;
+    while buffer < *d.base64.get(len)? {
+      len += 1;
+    }
+
+    let code_len = len as u32 + d.min_sym_len;
+    symbol = ((buffer - d.base64[len]) >> (64 - code_len)) as usize;
+    symbol += read_u16_le(bytes, d.lowest_sym + 2 * len)? as usize;
+    let symlen = *d.symlen.get(symbol)? as i64;
+    if offset < symlen + 1 {
+      break;
+    }
+
+    offset -= symlen + 1;
+    buffer = buffer.checked_shl(code_len).unwrap_or(0);
+    buffer_size = buffer_size.checked_sub(code_len)?;
+    if buffer_size <= 32 {
+      buffer_size += 32;
+      let refill = read_u32_be(bytes, ptr).unwrap_or(0) as u64;
+      buffer |= refill << (64 - buffer_size);
+      ptr += 4;
+    }
+  }
+
+  // Then down the pairs to the one value
+  while d.symlen[symbol] > 0 {
+    let (left, right) = tree_node(d, bytes, symbol)?;
+    let left_len = *d.symlen.get(left)? as i64;
+    if offset < left_len + 1 {
+      symbol = left;
+    } else {
+      offset -= left_len + 1;
+      symbol = right;
+    }
+    if symbol >= d.symlen.len() {
+      return None;
+    }
+  }
+
+  tree_node(d, bytes, symbol).map(|(left, _)| left as u32)
+}
+
+fn tree_node(
+  d: &PairsData,
+  bytes: &[u8],
+  symbol: usize,
+) -> Option<(usize, usize)> {
+  let node = bytes.get(d.tree + symbol * TREE_NODE_SIZE..)?.get(..3)?;
+  let left = ((node[1] as usize & 0xF) << 8) | node[0] as usize;
+  let right = ((node[2] as usize) << 4) | (node[1] as usize >> 4);
+  Some((left, right))
+}
+
+fn read_u16_le(bytes: &[u8], offset: usize) -> Option<u16> {
+  Some(u16::from_le_bytes(
+    bytes.get(offset..offset + 2)?.try_into().ok()?,
+  ))
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+  Some(u32::from_le_bytes(
+    bytes.get(offset..offset + 4)?.try_into().ok()?,
+  ))
+}
+
+fn read_u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+  Some(u32::from_be_bytes(
+    bytes.get(offset..offset + 4)?.try_into().ok()?,
+  ))
+}
+
+fn read_u64_be(bytes: &[u8], offset: usize) -> Option<u64> {
+  Some(u64::from_be_bytes(
+    bytes.get(offset..offset + 8)?.try_into().ok()?,
+  ))
+}
+
+// Rows above the a1-h8 diagonal are positive, below negative
+fn off_diagonal(square: usize) -> i32 {
+  (square >> 3) as i32 - (square & 7) as i32
+}
+
+// Lookup tables for turning piece placements into indices
+struct Encoding {
+  // a2-h7 to 0..47, higher toward the edges and lower rows, which makes
+  // the highest one the leading pawn
+  map_pawns: [usize; 64],
+  // Squares below the a1-h8 diagonal to 0..27
+  map_b1h1h7: [usize; 64],
+  // The a1-d1-d4 triangle to 0..9, diagonal last
+  map_a1d1d4: [usize; 64],
+  // The 462 placements of two kings with the first in the triangle
+  map_kk: [[u64; 64]; 10],
+  // binomial[k][n]: ways to choose k of n squares
+  binomial: [[u64; 64]; 6],
+  lead_pawn_idx: [[u64; 64]; 6],
+  lead_pawns_size: [[u64; 4]; 6],
+}
+
+fn encoding() -> &'static Encoding {
+  static ENCODING: OnceLock<Encoding> = OnceLock::new();
+  ENCODING.get_or_init(Encoding::new)
+}
+
+impl Encoding {
+  fn new() -> Self {
+    let mut encoding = Encoding {
+      map_pawns: [0; 64],
+      map_b1h1h7: [0; 64],
+      map_a1d1d4: [0; 64],
+      map_kk: [[0; 64]; 10],
+      binomial: [[0; 64]; 6],
+      lead_pawn_idx: [[0; 64]; 6],
+      lead_pawns_size: [[0; 4]; 6],
+    };
+    let file = |square: usize| square & 7;
+
+    let mut code = 0;
+    for square in 0..64 {
+      if off_diagonal(square) < 0 {
+        encoding.map_b1h1h7[square] = code;
+        code += 1;
+      }
+    }
+
+    let mut code = 0;
+    let mut diagonal = Vec::new();
+    for square in 0..=27 {
+      if off_diagonal(square) < 0 && file(square) <= 3 {
+        encoding.map_a1d1d4[square] = code;
+        code += 1;
+      } else if off_diagonal(square) == 0 && file(square) <= 3 {
+        diagonal.push(square);
+      }
+    }
+    for square in diagonal {
+      encoding.map_a1d1d4[square] = code;
+      code += 1;
+    }
+
+    // Kings can't touch, and with the first king on the diagonal the
+    // second one can't be above it
+    let mut code = 0;
+    let mut both_on_diagonal = Vec::new();
+    for idx in 0..10 {
+      for s1 in 0..=27 {
+        if encoding.map_a1d1d4[s1] != idx || (idx == 0 && s1 != 1) {
+          continue;
+        }
+        for s2 in 0..64 {
+          let touching = (s1 >> 3).abs_diff(s2 >> 3) <= 1
+            && file(s1).abs_diff(file(s2)) <= 1;
+          if touching || (off_diagonal(s1) == 0 && off_diagonal(s2) > 0) {
+            continue;
+          }
+          if off_diagonal(s1) == 0 && off_diagonal(s2) == 0 {
+            both_on_diagonal.push((idx, s2));
+          } else {
+            encoding.map_kk[idx][s2] = code;
+            code += 1;
+          }
+        }
+      }
+    }
+    for (idx, s2) in both_on_diagonal {
+      encoding.map_kk[idx][s2] = code;
+      code += 1;
+    }
+
+    encoding.binomial[0][0] = 1;
+    for n in 1..64 {
+      for k in 0..6.min(n + 1) {
+        let with = if k > 0 {
+          encoding.binomial[k - 1][n - 1]
+        } else {
+          0
+        };
+        let without = if k < n {
+          encoding.binomial[k][n - 1]
+        } else {
+          0
+        };
+        encoding.binomial[k][n] = with + without;
+      }
+    }
+
+    let mut available = 47;
+    for lead_pawns in 1..=5 {
+      for file in 0..4 {
+        let mut idx = 0;
+        for row in 1..=6 {
+          let square = row * 8 + file;
+          if lead_pawns == 1 {
+            encoding.map_pawns[square] = available;
+            encoding.map_pawns[square ^ 7] = available.saturating_sub(1);
+            available = available.saturating_sub(2);
+          }
+          encoding.lead_pawn_idx[lead_pawns][square] = idx;
+          idx += encoding.binomial[lead_pawns - 1][encoding.map_pawns[square]];
+        }
+        encoding.lead_pawns_size[lead_pawns][file] = idx;
+      }
+    }
+
+    encoding
+  }
+}
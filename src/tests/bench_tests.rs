@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+  use crate::engine::bench::bench;
+  use crate::engine::move_ordering::MoveOrderingOptions;
+  use crate::engine::search::SearchOptions;
+
+  const DEPTH: u32 = 4;
+
+  fn bench_nodes(move_ordering: MoveOrderingOptions) -> u64 {
+    let search_options = SearchOptions {
+      move_ordering,
+      ..SearchOptions::without_pruning()
+    };
+    bench(DEPTH, search_options).nodes
+  }
+
+  #[test]
+  fn test_move_ordering_heuristics_reduce_node_count() {
+    let without = bench_nodes(MoveOrderingOptions::none());
+    let with_all = bench_nodes(MoveOrderingOptions::default());
+    let killers_only = bench_nodes(MoveOrderingOptions {
+      killer_moves: true,
+      ..MoveOrderingOptions::none()
+    });
+    let history_only = bench_nodes(MoveOrderingOptions {
+      history: true,
+      ..MoveOrderingOptions::none()
+    });
+    let countermoves_only = bench_nodes(MoveOrderingOptions {
+      countermoves: true,
+      ..MoveOrderingOptions::none()
+    });
+
+    assert!(with_all < without);
+    assert!(killers_only < without);
+    assert!(history_only < without);
+    assert!(countermoves_only < without);
+  }
+
+  #[test]
+  fn test_pruning_reduces_node_count() {
+    let without = bench(DEPTH, SearchOptions::without_pruning()).nodes;
+    let with_all = bench(DEPTH, SearchOptions::default()).nodes;
+
+    assert!(with_all < without);
+  }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+  use crate::bitbase::{Bitbases, Endgame};
+  use crate::engine::Engine;
+  use crate::engine::evaluation::{Evaluator, KNOWN_WIN};
+  use crate::game::Game;
+  use crate::tablebase::Wdl;
+  use std::sync::OnceLock;
+
+  // Generating takes a moment, so every test shares the same bitbases
+  fn bitbases() -> &'static Bitbases {
+    static BITBASES: OnceLock<Bitbases> = OnceLock::new();
+    BITBASES.get_or_init(Bitbases::generate)
+  }
+
+  fn probe(fen: &str) -> Option<Wdl> {
+    let game = Game::from_fen(fen).unwrap();
+    bitbases().probe(game.chessboard(), game.player_color())
+  }
+
+  #[test]
+  fn test_kqk_and_krk() {
+    assert_eq!(probe("k7/8/8/8/8/8/8/KQ6 w - - 0 1"), Some(Wdl::Win));
+    assert_eq!(probe("k7/8/8/8/8/8/8/KQ6 b - - 0 1"), Some(Wdl::Loss));
+    assert_eq!(probe("7K/8/8/8/8/8/8/kr6 b - - 0 1"), Some(Wdl::Win));
+    // The queen or rook can be taken
+    assert_eq!(probe("kQ6/8/8/8/8/8/8/K7 b - - 0 1"), Some(Wdl::Draw));
+    assert_eq!(probe("kR6/8/8/8/8/8/8/K7 b - - 0 1"), Some(Wdl::Draw));
+    // Stalemate
+    assert_eq!(probe("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1"), Some(Wdl::Draw));
+    // Mate
+    assert_eq!(probe("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1"), Some(Wdl::Loss));
+  }
+
+  #[test]
+  fn test_kpk() {
+    assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"), Some(Wdl::Win));
+    assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1"), Some(Wdl::Loss));
+    // Rook pawns don't win against a king in the corner
+    assert_eq!(probe("k7/8/K7/P7/8/8/8/8 w - - 0 1"), Some(Wdl::Draw));
+    // Stalemate in front of the pawn
+    assert_eq!(probe("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1"), Some(Wdl::Draw));
+    // The king is too far to catch the pawn
+    assert_eq!(probe("7k/8/8/8/8/8/P7/K7 b - - 0 1"), Some(Wdl::Loss));
+    // Same for black, up the board the other way
+    assert_eq!(probe("8/8/8/8/8/3kp3/8/4K3 b - - 0 1"), Some(Wdl::Win));
+    assert_eq!(probe("8/8/8/8/8/8/8/k1K5 w - - 0 1"), None);
+  }
+
+  #[test]
+  fn test_win_counts() {
+    let bitbases = bitbases();
+    let wins = |endgame| bitbases.get(endgame).count_wins();
+
+    // With the move, the queen or rook wins each of its 72254 and 87584
+    // legal positions; the rest are the lone king's lost positions
+    assert_eq!(wins(Endgame::Kqk), 72254 + 100448);
+    assert_eq!(wins(Endgame::Krk), 87584 + 100850);
+    assert_eq!(wins(Endgame::Kpk), 62480 + 48802);
+  }
+
+  #[test]
+  fn test_serialization() {
+    let bytes = bitbases().to_bytes();
+    assert_eq!(Bitbases::from_bytes(&bytes).as_ref(), Ok(bitbases()));
+
+    assert_eq!(
+      Bitbases::from_bytes(b"RCBX"),
+      Err("Invalid bitbases: wrong magic".to_string())
+    );
+    let mut other_version = bytes.clone();
+    other_version[4] = 9;
+    assert_eq!(
+      Bitbases::from_bytes(&other_version),
+      Err("Unsupported bitbases version: 9".to_string())
+    );
+    assert!(
+      Bitbases::from_bytes(&bytes[..bytes.len() - 1])
+        .unwrap_err()
+        .starts_with("Invalid bitbases: ")
+    );
+    assert!(Bitbases::open("/nonexistent/bitbases.bin").is_err());
+  }
+
+  #[test]
+  fn test_evaluator_uses_bitbases() {
+    let evaluate = |evaluator: Evaluator, fen: &str| {
+      let game = Game::from_fen(fen).unwrap();
+      evaluator.evaluate(game.chessboard(), game.player_color())
+    };
+    let with_bitbases = Evaluator::with_bitbases(Some(bitbases()));
+
+    let drawn = "k7/8/K7/P7/8/8/8/8 w - - 0 1";
+    assert!(evaluate(Evaluator::default(), drawn) > 0);
+    assert_eq!(evaluate(with_bitbases, drawn), 0);
+
+    let won = "4k3/8/4K3/4P3/8/8/8/8 b - - 0 1";
+    assert!(evaluate(with_bitbases, won) <= -KNOWN_WIN);
+    // Closer to mate scores higher
+    let far = evaluate(with_bitbases, "8/8/8/3k4/8/8/8/KR6 w - - 0 1");
+    let near = evaluate(with_bitbases, "3k4/8/3K4/8/8/8/8/1R6 w - - 0 1");
+    assert!(KNOWN_WIN <= far && far < near);
+  }
+
+  #[test]
+  fn test_engine_bitbase_file() {
+    let path = std::env::temp_dir()
+      .join(format!("rust_chess_bitbases_{}.bin", std::process::id()));
+    bitbases().write(&path).unwrap();
+
+    let mut engine = Engine::new();
+    engine
+      .set_option("BitbaseFile", path.to_str().unwrap())
+      .unwrap();
+    assert_eq!(engine.bitbases(), Some(bitbases()));
+    engine.set_option("BitbaseFile", "<empty>").unwrap();
+    assert!(engine.bitbases().is_none());
+    assert!(engine.set_option("BitbaseFile", "/nonexistent").is_err());
+    std::fs::remove_file(path).unwrap();
+  }
+}
@@ -143,11 +143,10 @@ mod tests {
 
     // Confirm the upgrade replaced the pawn on the board
     assert!(board_manager.chessboard().board()[7][1].is_some());
-    let is_pawn =
-      match board_manager.chessboard().board()[7][1].as_ref().unwrap() {
-        Piece::Pawn(_) => true,
-        _ => false,
-      };
+    let is_pawn = matches!(
+      board_manager.chessboard().board()[7][1].as_ref().unwrap(),
+      Piece::Pawn(_)
+    );
     assert!(!is_pawn,); // make sure it's not a pawn anymore
   }
 
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+  use crate::book::OpeningBook;
+  use crate::book::builder::{BookBuilder, BookBuilderOptions};
+  use crate::game::Game;
+  use crate::notation::pgn::PgnResult;
+  use crate::pieces::types::color::Color;
+
+  const GAMES: &str = "
+1. e4 e5 2. Nf3 Nc6 1-0
+1. e4 c5 2. Nf3 d6 0-1
+1. d4 d5 2. c4 e6 1/2-1/2
+1. e4 e5 2. Bc4 Nf6 1-0
+1. c4 e5 *
+";
+
+  fn build(options: BookBuilderOptions) -> OpeningBook {
+    let mut builder = BookBuilder::new(options);
+    builder.add_pgn(GAMES).unwrap();
+    OpeningBook::from_bytes(&builder.to_bytes()).unwrap()
+  }
+
+  fn weights(book: &OpeningBook, game: &Game) -> Vec<(String, u16)> {
+    book
+      .moves(game)
+      .into_iter()
+      .map(|m| (m.chess_move.to_long_algebraic(), m.weight))
+      .collect()
+  }
+
+  fn after(moves: &[&str]) -> Game {
+    let mut game = Game::new(Color::White);
+    for san in moves {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+    game
+  }
+
+  #[test]
+  fn test_weights_follow_results() {
+    let mut builder = BookBuilder::new(BookBuilderOptions::default());
+    // The unfinished game can't weigh its moves
+    assert_eq!(builder.add_pgn(GAMES).unwrap(), 4);
+    let book = OpeningBook::from_bytes(&builder.to_bytes()).unwrap();
+
+    // Two wins and a loss for e4, a draw for d4
+    let start = Game::new(Color::White);
+    assert_eq!(
+      weights(&book, &start),
+      [("e2e4".to_string(), 4), ("d2d4".to_string(), 1)]
+    );
+
+    // Black only scored with c5
+    let game = after(&["e4"]);
+    assert_eq!(weights(&book, &game), [("c7c5".to_string(), 2)]);
+  }
+
+  #[test]
+  fn test_max_ply() {
+    let book = build(BookBuilderOptions {
+      max_ply: 1,
+      ..Default::default()
+    });
+
+    assert!(!book.moves(&Game::new(Color::White)).is_empty());
+    assert!(book.moves(&after(&["e4"])).is_empty());
+  }
+
+  #[test]
+  fn test_min_games() {
+    let book = build(BookBuilderOptions {
+      min_games: 2,
+      ..Default::default()
+    });
+
+    assert_eq!(
+      weights(&book, &Game::new(Color::White)),
+      [("e2e4".to_string(), 4)]
+    );
+    assert_eq!(weights(&book, &after(&["e4"])), []);
+  }
+
+  #[test]
+  fn test_result_filter() {
+    let book = build(BookBuilderOptions {
+      results: vec![PgnResult::Draw],
+      ..Default::default()
+    });
+
+    assert_eq!(
+      weights(&book, &Game::new(Color::White)),
+      [("d2d4".to_string(), 1)]
+    );
+    assert_eq!(weights(&book, &after(&["d4"])), [("d7d5".to_string(), 1)]);
+  }
+}
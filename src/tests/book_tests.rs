@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+  use crate::book::{BookChoice, OpeningBook};
+  use crate::engine::Engine;
+  use crate::engine::search::SearchLimits;
+  use crate::game::Game;
+  use crate::pieces::types::{color::Color, position::Position};
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+
+  const START_KEY: u64 = 0x463b_9618_1691_fc9c;
+
+  fn play(game: &mut Game, moves: &[&str]) {
+    for chess_move in moves {
+      let (from, to) = chess_move.split_at(2);
+      game
+        .play(
+          Position::from_algebraic(from).unwrap(),
+          Position::from_algebraic(to).unwrap(),
+        )
+        .unwrap();
+    }
+  }
+
+  // Polyglot move bits: to file, to row, from file, from row
+  fn encode(chess_move: &str) -> u16 {
+    let square = |name: &str| {
+      let position = Position::from_algebraic(name).unwrap();
+      (position.x() << 3 | position.y()) as u16
+    };
+    square(&chess_move[2..4]) | square(&chess_move[0..2]) << 6
+  }
+
+  fn book(entries: &[(u64, &str, u16)]) -> OpeningBook {
+    let mut bytes = Vec::new();
+    for (key, chess_move, weight) in entries {
+      bytes.extend(key.to_be_bytes());
+      bytes.extend(encode(chess_move).to_be_bytes());
+      bytes.extend(weight.to_be_bytes());
+      bytes.extend(0u32.to_be_bytes());
+    }
+    OpeningBook::from_bytes(&bytes).unwrap()
+  }
+
+  #[test]
+  fn test_polyglot_keys() {
+    let mut game = Game::new(Color::White);
+    assert_eq!(game.polyglot_key(), START_KEY);
+
+    let keys = [
+      ("e2e4", 0x823c_9b50_fd11_4196),
+      ("d7d5", 0x0756_b944_61c5_0fb0),
+      ("e4e5", 0x662f_afb9_65db_29d4),
+      // The pawn on e5 could take en passant
+      ("f7f5", 0x22a4_8b5a_8e47_ff78),
+      // Moving the kings loses the castling rights
+      ("e1e2", 0x652a_607c_a3f2_42c1),
+      ("e8f7", 0x00fd_d303_c946_bdd9),
+    ];
+    for (chess_move, key) in keys {
+      play(&mut game, &[chess_move]);
+      assert_eq!(game.polyglot_key(), key, "after {}", chess_move);
+    }
+
+    let game = Game::from_fen(
+      "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 1 4",
+    )
+    .unwrap();
+    assert_eq!(game.polyglot_key(), 0x5c3f_9b82_9b27_9560);
+  }
+
+  #[test]
+  fn test_book_moves() {
+    let book = book(&[
+      (1, "a2a3", 10),
+      (START_KEY, "d2d4", 1),
+      (START_KEY, "e2e4", 3),
+      // Castling isn't possible in this game
+      (START_KEY, "e1h1", 5),
+    ]);
+    let game = Game::new(Color::White);
+
+    let moves: Vec<(String, u16)> = book
+      .moves(&game)
+      .iter()
+      .map(|m| (m.chess_move.to_long_algebraic(), m.weight))
+      .collect();
+    assert_eq!(
+      moves,
+      vec![("e2e4".to_string(), 3), ("d2d4".to_string(), 1)]
+    );
+
+    let mut out_of_book = Game::new(Color::White);
+    play(&mut out_of_book, &["g1f3"]);
+    assert!(book.moves(&out_of_book).is_empty());
+  }
+
+  #[test]
+  fn test_book_choices() {
+    let mut book = book(&[
+      (START_KEY, "e2e4", 3),
+      (START_KEY, "d2d4", 1),
+      (START_KEY, "c2c4", 0),
+    ]);
+    book.set_seed(7);
+    let game = Game::new(Color::White);
+
+    let best = book.choose(&game, BookChoice::Best).unwrap();
+    assert_eq!(best.to_long_algebraic(), "e2e4");
+
+    let picks: Vec<String> = (0..100)
+      .map(|_| {
+        let chess_move = book.choose(&game, BookChoice::WeightedRandom);
+        chess_move.unwrap().to_long_algebraic()
+      })
+      .collect();
+    let e4_count = picks.iter().filter(|m| *m == "e2e4").count();
+    assert!(e4_count > 50 && e4_count < 100);
+    // Moves without weight are never played
+    assert!(!picks.contains(&"c2c4".to_string()));
+  }
+
+  #[test]
+  fn test_invalid_book() {
+    assert!(OpeningBook::from_bytes(&[0; 20]).is_err());
+    assert!(OpeningBook::open("/nonexistent/book.bin").is_err());
+  }
+
+  #[test]
+  fn test_engine_plays_book_moves() {
+    let game = Game::new(Color::White);
+    let mut engine = Engine::new();
+    engine.set_book(Some(book(&[(START_KEY, "b1c3", 1)])));
+    let search = |engine: &mut Engine| {
+      let stop = Arc::new(AtomicBool::new(false));
+      engine.search(&game, SearchLimits::depth(2), stop, &mut |_| {})
+    };
+
+    let result = search(&mut engine);
+    assert_eq!(result.best_move.unwrap().to_long_algebraic(), "b1c3");
+    assert_eq!(result.nodes, 0);
+
+    engine.set_option("OwnBook", "false").unwrap();
+    let result = search(&mut engine);
+    assert!(result.nodes > 0);
+  }
+}
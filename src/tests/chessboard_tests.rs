@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
   use crate::chessboard::Chessboard;
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
   use crate::pieces::types::position::Position;
 
   #[test]
@@ -37,4 +39,12 @@ mod tests {
       assert!(board.get_piece(Position::new(6, y).unwrap()).is_some());
     }
   }
+
+  #[test]
+  fn test_has_pieces_besides_pawns() {
+    let game = Game::from_fen("4k3/pp6/8/8/8/8/6PP/4KN2 w - - 0 1").unwrap();
+
+    assert!(game.chessboard().has_pieces_besides_pawns(Color::White));
+    assert!(!game.chessboard().has_pieces_besides_pawns(Color::Black));
+  }
 }
@@ -0,0 +1,301 @@
+#[cfg(test)]
+mod tests {
+  use crate::clock::time_control::{Increment, Stage, TimeControl};
+  use crate::clock::{Clock, ManualTime, format_duration};
+  use crate::game::{Game, GameResult};
+  use crate::pieces::types::color::Color;
+  use crate::tests::fixtures::square;
+  use std::sync::Arc;
+  use std::time::Duration;
+
+  fn seconds(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+  }
+
+  fn start_clock(time_control: &str) -> (Clock, ManualTime) {
+    let time = ManualTime::new();
+    let time_control = TimeControl::parse(time_control).unwrap();
+    let mut clock =
+      Clock::with_time_source(time_control, Arc::new(time.clone()));
+    clock.start(Color::White);
+    (clock, time)
+  }
+
+  // Each player in turn thinks for the given time, then presses the clock
+  fn play(clock: &mut Clock, time: &ManualTime, thinking: &[u64]) {
+    for &spent in thinking {
+      time.advance(seconds(spent));
+      clock.press().unwrap();
+    }
+  }
+
+  #[test]
+  fn test_parse_time_control() {
+    assert_eq!(
+      TimeControl::parse("40/90+30:30+30").unwrap().stages(),
+      &[
+        Stage {
+          moves: Some(40),
+          time: seconds(90 * 60),
+          increment: Increment::Fischer(seconds(30)),
+        },
+        Stage {
+          moves: None,
+          time: seconds(30 * 60),
+          increment: Increment::Fischer(seconds(30)),
+        },
+      ]
+    );
+    assert_eq!(
+      TimeControl::parse("5+3d").unwrap(),
+      TimeControl::with_increment(seconds(300), Increment::Delay(seconds(3)))
+    );
+    assert_eq!(
+      TimeControl::parse("0.5+2b").unwrap(),
+      TimeControl::with_increment(
+        seconds(30),
+        Increment::Bronstein(seconds(2))
+      )
+    );
+    for text in ["40/90+30:30+30", "5", "3+2", "5+3d", "40/120"] {
+      let time_control = TimeControl::parse(text).unwrap();
+      assert_eq!(time_control.to_string(), text);
+    }
+
+    assert_eq!(
+      TimeControl::parse("5+x"),
+      Err("Invalid time control 5+x: invalid increment x".to_string())
+    );
+    assert_eq!(
+      TimeControl::parse("0"),
+      Err("Invalid time control 0: a stage needs some time".to_string())
+    );
+    assert!(TimeControl::parse("x/5").is_err());
+    assert!(TimeControl::parse("5:40/90").is_err());
+    assert!(TimeControl::parse("0/5").is_err());
+  }
+
+  #[test]
+  fn test_pgn_time_control_tag() {
+    let time_control = TimeControl::parse("40/90+30:30+30").unwrap();
+    assert_eq!(time_control.to_pgn_tag(), "40/5400+30:1800+30");
+    assert_eq!(
+      TimeControl::from_pgn_tag("40/5400+30:1800+30"),
+      Ok(time_control)
+    );
+    assert_eq!(
+      TimeControl::from_pgn_tag("180+2d"),
+      Ok(TimeControl::with_increment(
+        seconds(180),
+        Increment::Delay(seconds(2))
+      ))
+    );
+    assert_eq!(
+      TimeControl::from_pgn_tag("-"),
+      Err("Invalid time control -: invalid seconds -".to_string())
+    );
+  }
+
+  #[test]
+  fn test_sudden_death_flag_fall() {
+    let (mut clock, time) = start_clock("1");
+
+    play(&mut clock, &time, &[20, 10]);
+    assert_eq!(clock.remaining(Color::White), seconds(40));
+    assert_eq!(clock.remaining(Color::Black), seconds(50));
+    assert_eq!(clock.running(), Some(Color::White));
+
+    time.advance(seconds(39));
+    assert_eq!(clock.flagged(), None);
+    time.advance(seconds(1));
+    assert_eq!(clock.flagged(), Some(Color::White));
+    assert_eq!(clock.press(), Err("White has run out of time".to_string()));
+  }
+
+  #[test]
+  fn test_fischer_increment() {
+    let (mut clock, time) = start_clock("1+5");
+
+    // The increment is given even when it is more than the move took
+    play(&mut clock, &time, &[2, 20]);
+    assert_eq!(clock.remaining(Color::White), seconds(63));
+    assert_eq!(clock.remaining(Color::Black), seconds(45));
+  }
+
+  #[test]
+  fn test_bronstein_delay() {
+    let (mut clock, time) = start_clock("1+5b");
+
+    // Only the time the move took is given back, up to the delay
+    play(&mut clock, &time, &[2, 20]);
+    assert_eq!(clock.remaining(Color::White), seconds(60));
+    assert_eq!(clock.remaining(Color::Black), seconds(45));
+  }
+
+  #[test]
+  fn test_us_delay() {
+    let (mut clock, time) = start_clock("1+5d");
+
+    // The time doesn't run during the delay
+    time.advance(seconds(4));
+    assert_eq!(clock.remaining(Color::White), seconds(60));
+    time.advance(seconds(3));
+    assert_eq!(clock.remaining(Color::White), seconds(58));
+    clock.press().unwrap();
+    assert_eq!(clock.remaining(Color::White), seconds(58));
+
+    // Nor does the flag fall before the time left is used up after it
+    time.advance(seconds(64));
+    assert_eq!(clock.flagged(), None);
+    time.advance(seconds(1));
+    assert_eq!(clock.flagged(), Some(Color::Black));
+  }
+
+  #[test]
+  fn test_multi_stage_time_control() {
+    let (mut clock, time) = start_clock("2/1:1+10");
+
+    // Two moves in a minute, then a minute more for the rest of the game
+    // with increments from then on
+    play(&mut clock, &time, &[10, 10, 10]);
+    assert_eq!(clock.remaining(Color::White), seconds(100));
+    assert_eq!(clock.remaining(Color::Black), seconds(50));
+    play(&mut clock, &time, &[10]);
+    assert_eq!(clock.remaining(Color::Black), seconds(100));
+    play(&mut clock, &time, &[10, 10]);
+    assert_eq!(clock.remaining(Color::White), seconds(100));
+    assert_eq!(clock.remaining(Color::Black), seconds(100));
+    assert_eq!(clock.moves(Color::White), 3);
+
+    // A last stage with a move count repeats
+    let (mut clock, time) = start_clock("1/1");
+    play(&mut clock, &time, &[30, 0, 30]);
+    assert_eq!(clock.remaining(Color::White), seconds(120));
+  }
+
+  #[test]
+  fn test_stop_and_start() {
+    let (mut clock, time) = start_clock("1");
+    time.advance(seconds(10));
+    clock.stop();
+    time.advance(seconds(10));
+    assert_eq!(clock.remaining(Color::White), seconds(50));
+    assert_eq!(clock.running(), None);
+    assert!(clock.press().is_err());
+
+    clock.start(Color::Black);
+    time.advance(seconds(10));
+    assert_eq!(clock.remaining(Color::Black), seconds(50));
+  }
+
+  #[test]
+  fn test_game_on_the_clock() {
+    let (clock, time) = start_clock("1");
+    let mut game = Game::new(Color::White);
+    game.set_clock(Some(clock));
+
+    time.advance(seconds(10));
+    game.play(square("e2"), square("e4")).unwrap();
+    assert_eq!(game.clock().unwrap().running(), Some(Color::Black));
+    assert_eq!(game.result(), None);
+
+    time.advance(seconds(60));
+    assert_eq!(
+      game.result(),
+      Some(GameResult::Timeout {
+        winner: Color::White
+      })
+    );
+    assert_eq!(
+      game.play(square("e7"), square("e5")),
+      Err("Black has run out of time".to_string())
+    );
+    assert_eq!(game.moves().len(), 1);
+  }
+
+  #[test]
+  fn test_save_and_load_clock() {
+    let time = ManualTime::new();
+    let time_control = TimeControl::parse("5+3").unwrap();
+    let mut game = Game::new(Color::White);
+    game.set_clock(Some(Clock::with_time_source(
+      time_control.clone(),
+      Arc::new(time.clone()),
+    )));
+    for (spent, san) in [(10, "e4"), (20, "e5"), (1, "Nf3")] {
+      time.advance(Duration::from_millis(spent * 1000 + 500));
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+    time.advance(seconds(40));
+
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.contains("[TimeControl \"300+3\"]\n"));
+    assert!(pgn.contains("[WhiteClock \"0:04:54\"]\n"));
+    assert!(pgn.contains("[BlackClock \"0:04:02.500\"]\n"));
+
+    let loaded = Game::from_pgn(&pgn).unwrap();
+    let clock = loaded.clock().unwrap();
+    assert_eq!(clock.time_control(), &time_control);
+    assert_eq!(clock.remaining(Color::White), seconds(294));
+    assert!(clock.remaining(Color::Black) <= Duration::from_millis(242_500));
+    assert!(clock.remaining(Color::Black) > seconds(240));
+    assert_eq!(clock.running(), Some(Color::Black));
+    assert_eq!(
+      (clock.moves(Color::White), clock.moves(Color::Black)),
+      (2, 1)
+    );
+
+    // Without the time left the clock isn't restored
+    let pgn = pgn.replace("[WhiteClock \"0:04:54\"]\n", "");
+    assert!(Game::from_pgn(&pgn).unwrap().clock().is_none());
+    let pgn = pgn.replace(
+      "[BlackClock \"0:04:02.500\"]",
+      "[WhiteClock \"x\"]\n[BlackClock \"1\"]",
+    );
+    assert_eq!(
+      Game::from_pgn(&pgn).err(),
+      Some("Invalid clock time: x".to_string())
+    );
+
+    // Games without a time control, or with an unknown one, have no clock
+    for tag in ["-", "?"] {
+      let pgn = format!(
+        "[TimeControl \"{}\"]\n[WhiteClock \"0:01:00\"]\n\
+         [BlackClock \"0:01:00\"]\n\n1. e4 *",
+        tag
+      );
+      assert!(Game::from_pgn(&pgn).unwrap().clock().is_none());
+    }
+  }
+
+  #[test]
+  fn test_timeout_without_mating_material() {
+    // Black to move with thirty seconds left, which run out
+    let flag_black = |fen: &str| {
+      let (clock, time) = start_clock("0.5");
+      let mut game = Game::from_fen(fen).unwrap();
+      game.set_clock(Some(clock));
+      time.advance(seconds(30));
+      game.result()
+    };
+
+    assert_eq!(
+      flag_black("4k3/8/8/8/8/8/3PN3/4K3 b - - 0 1"),
+      Some(GameResult::Timeout {
+        winner: Color::White
+      })
+    );
+    // A lone knight can't mate
+    assert_eq!(
+      flag_black("4k3/8/8/8/8/8/4N3/4K3 b - - 0 1"),
+      Some(GameResult::TimeoutDraw)
+    );
+  }
+
+  #[test]
+  fn test_format_duration() {
+    assert_eq!(format_duration(seconds(5400)), "1:30:00");
+    assert_eq!(format_duration(seconds(299)), "4:59");
+    assert_eq!(format_duration(Duration::from_millis(9_450)), "0:09.4");
+  }
+}
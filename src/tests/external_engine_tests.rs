@@ -0,0 +1,169 @@
+#[cfg(all(test, unix))]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::engine::search::{Score, SearchLimits};
+  use crate::external_engine::ExternalEngine;
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use crate::tests::fixtures::square;
+  use std::fs;
+  use std::os::unix::fs::PermissionsExt;
+  use std::path::PathBuf;
+  use std::sync::mpsc;
+  use std::thread;
+
+  // Stand-in engine that records the last position it was given and always
+  // answers e7e5
+  const STAND_IN_ENGINE: &str = r#"#!/bin/sh
+while read -r line; do
+  case "$line" in
+    uci) echo "id name Stand-in"; echo "option name Hash type spin"; echo "uciok" ;;
+    isready) echo "readyok" ;;
+    position*) echo "$line" > "$0.position" ;;
+    go*)
+      echo "info depth 1 score cp 25 nodes 20 time 3 pv e7e5 g1f3"
+      echo "info string done thinking"
+      echo "info depth 2 currmove e7e5"
+      echo "bestmove e7e5"
+      ;;
+    quit) exit 0 ;;
+  esac
+done
+"#;
+
+  fn write_engine(name: &str, script: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+      "rust_chess_{}_{}",
+      name,
+      std::process::id()
+    ));
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+  }
+
+  #[test]
+  fn test_handshake_and_search() {
+    let path = write_engine("search", STAND_IN_ENGINE);
+    let mut engine = ExternalEngine::spawn(&path).unwrap();
+    assert_eq!(engine.name(), Some("Stand-in"));
+    engine.new_game().unwrap();
+
+    let mut game = Game::new(Color::White);
+    game.play(square("e2"), square("e4")).unwrap();
+
+    let mut infos = Vec::new();
+    let result = engine
+      .search(&game, &SearchLimits::depth(1), &mut |info| {
+        infos.push(info.score)
+      })
+      .unwrap();
+
+    assert_eq!(
+      result.best_move,
+      Some(Move::new(square("e7"), square("e5")))
+    );
+    assert_eq!(infos, vec![Score::Centipawns(25)]);
+    assert_eq!(result.info.unwrap().pv.len(), 2);
+
+    let position = fs::read_to_string(path.with_extension("position"));
+    drop(engine);
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(path.with_extension("position"));
+    assert_eq!(position.unwrap().trim(), "position startpos moves e2e4");
+  }
+
+  #[test]
+  fn test_position_from_fen() {
+    let path = write_engine("fen", STAND_IN_ENGINE);
+    let mut engine = ExternalEngine::spawn(&path).unwrap();
+
+    let fen = "4k3/4p3/8/8/8/8/8/4K3 b - - 0 1";
+    let game = Game::from_fen(fen).unwrap();
+    engine
+      .search(&game, &SearchLimits::depth(1), &mut |_| {})
+      .unwrap();
+
+    let position = fs::read_to_string(path.with_extension("position"));
+    drop(engine);
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(path.with_extension("position"));
+    assert_eq!(position.unwrap().trim(), format!("position fen {}", fen));
+  }
+
+  #[test]
+  fn test_engine_exiting_is_an_error() {
+    let path = write_engine(
+      "crash",
+      "#!/bin/sh\nread -r line\necho uciok\nread -r line\nread -r line\n",
+    );
+    let mut engine = ExternalEngine::spawn(&path).unwrap();
+
+    let result = engine.search(
+      &Game::new(Color::White),
+      &SearchLimits::depth(1),
+      &mut |_| {},
+    );
+
+    drop(engine);
+    let _ = fs::remove_file(&path);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_infinite_search_until_stopped() {
+    // Sends two lines for `go infinite` and its move only once stopped
+    let path = write_engine(
+      "infinite",
+      r#"#!/bin/sh
+while read -r line; do
+  case "$line" in
+    uci) echo "uciok" ;;
+    isready) echo "readyok" ;;
+    "go infinite")
+      echo "info depth 3 multipv 1 score cp 30 nodes 900 time 5 pv e2e4"
+      echo "info depth 3 multipv 2 score cp 20 nodes 900 time 5 pv d2d4"
+      ;;
+    stop) echo "bestmove e2e4" ;;
+    quit) exit 0 ;;
+  esac
+done
+"#,
+    );
+    let mut engine = ExternalEngine::spawn(&path).unwrap();
+    engine.set_option("MultiPV", "2").unwrap();
+    let stop_handle = engine.stop_handle();
+    let game = Game::new(Color::White);
+    let limits = SearchLimits {
+      infinite: true,
+      ..Default::default()
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let result = thread::scope(|scope| {
+      let engine = &mut engine;
+      let search = scope.spawn(move || {
+        engine.search(&game, &limits, &mut |info| {
+          sender.send(info.multipv).unwrap()
+        })
+      });
+      // Stopped once it has sent its lines, as with a user pressing Enter
+      let lines: Vec<usize> = receiver.iter().take(2).collect();
+      stop_handle.stop().unwrap();
+      (lines, search.join().unwrap())
+    });
+
+    drop(engine);
+    let _ = fs::remove_file(&path);
+    assert_eq!(result.0, vec![1, 2]);
+    assert_eq!(
+      result.1.unwrap().best_move,
+      Some(Move::new(square("e2"), square("e4")))
+    );
+  }
+
+  #[test]
+  fn test_missing_engine() {
+    assert!(ExternalEngine::spawn("/nonexistent/rust_chess_engine").is_err());
+  }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+  use crate::chessboard::Chessboard;
+  use crate::game::Game;
+  use crate::notation::fen::STARTING_FEN;
+  use crate::pieces::types::color::Color;
+
+  #[test]
+  fn test_standard_board_fen() {
+    assert_eq!(Game::new(Color::White).to_fen(), STARTING_FEN);
+  }
+
+  #[test]
+  fn test_fen_round_trip() {
+    let fen = "r3k2r/pp3ppp/2n5/3q4/8/2N5/PP3PPP/R2Q1RK1 b - - 0 1";
+    let game = Game::from_fen(fen).unwrap();
+
+    assert_eq!(game.player_color(), Color::Black);
+    assert_eq!(game.to_fen(), fen);
+  }
+
+  #[test]
+  fn test_missing_pieces_are_dead() {
+    let board = Chessboard::from_fen_placement("4k3/8/8/8/8/8/8/4K3").unwrap();
+
+    assert_eq!(board.white_dead_pieces().len(), 15);
+    assert_eq!(board.black_dead_pieces().len(), 15);
+  }
+
+  #[test]
+  fn test_invalid_fen() {
+    assert!(Game::from_fen("").is_err());
+    assert!(Game::from_fen("8/8/8 w - - 0 1").is_err());
+    assert!(Game::from_fen("9/8/8/8/8/8/8/8 w - - 0 1").is_err());
+    assert!(Game::from_fen("x7/8/8/8/8/8/8/8 w - - 0 1").is_err());
+    assert!(Game::from_fen(&STARTING_FEN.replace(" w ", " x ")).is_err());
+  }
+}
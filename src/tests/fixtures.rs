@@ -0,0 +1,13 @@
+use crate::chessboard::Chessboard;
+use crate::pieces::types::position::Position;
+
+// Helpers shared by the test modules
+
+pub fn square(algebraic: &str) -> Position {
+  Position::from_algebraic(algebraic).unwrap()
+}
+
+// Kings on their squares and White's rook on h1, for board drawings
+pub fn endgame() -> Chessboard {
+  Chessboard::from_fen_placement("4k3/8/8/8/8/8/8/4K2R").unwrap()
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::game::{Game, GameResult};
+  use crate::pieces::piece::Piece;
+  use crate::pieces::types::color::Color;
+  use crate::tests::fixtures::square;
+
+  #[test]
+  fn test_undo_restores_board_and_player() {
+    let mut game = Game::new(Color::White);
+    let fen = game.to_fen();
+
+    game.play(square("e2"), square("e4")).unwrap();
+    assert_eq!(game.moves(), &[Move::new(square("e2"), square("e4"))]);
+
+    let undone = game.undo().unwrap();
+    assert_eq!(undone, Move::new(square("e2"), square("e4")));
+    assert_eq!(game.to_fen(), fen);
+    assert_eq!(game.player_color(), Color::White);
+    assert!(game.undo().is_err());
+  }
+
+  #[test]
+  fn test_upgrade_is_recorded_in_move() {
+    let mut game = Game::from_fen("7k/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let queen = Piece::from_symbol('Q').unwrap();
+
+    game
+      .play_move(Move::with_promotion(square("a7"), square("a8"), queen))
+      .unwrap();
+
+    assert_eq!(
+      game.moves().last(),
+      Some(&Move::with_promotion(square("a7"), square("a8"), queen))
+    );
+    assert_eq!(game.chessboard().get_piece(square("a8")), Some(&queen));
+  }
+
+  #[test]
+  fn test_result() {
+    let game = Game::new(Color::White);
+    assert_eq!(game.result(), None);
+
+    let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    assert_eq!(
+      game.result(),
+      Some(GameResult::Checkmate {
+        winner: Color::White
+      })
+    );
+
+    let game = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(game.result(), Some(GameResult::Stalemate));
+  }
+
+  #[test]
+  fn test_save_and_load() {
+    let path = std::env::temp_dir()
+      .join(format!("rust_chess_game_{}.pgn", std::process::id()));
+    let mut game = Game::new(Color::White);
+    game.play(square("e2"), square("e4")).unwrap();
+    game.play(square("d7"), square("d5")).unwrap();
+    game.play(square("e4"), square("d5")).unwrap();
+
+    game.save(&path).unwrap();
+    let loaded = Game::load(&path).unwrap();
+    assert_eq!(loaded.moves(), game.moves());
+    assert_eq!(loaded.to_fen(), game.to_fen());
+    assert_eq!(loaded.player_color(), Color::Black);
+
+    std::fs::write(&path, "1. e4 e5 2. Qxf7 *").unwrap();
+    assert_eq!(
+      Game::load(&path).err(),
+      Some(format!(
+        "Invalid game in {}: Move 2. Qxf7: Illegal move: Qxf7",
+        path.display()
+      ))
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+      Game::load(&path)
+        .err()
+        .is_some_and(|e| e.starts_with("Failed to read"))
+    );
+  }
+
+  #[test]
+  fn test_save_and_load_upgrade_to_pawn() {
+    let path = std::env::temp_dir()
+      .join(format!("rust_chess_upgrade_{}.pgn", std::process::id()));
+    // White's only dead piece is a pawn, which is all b7 can become
+    let mut game =
+      Game::from_fen("rnbqkbnr/pPpppppp/8/8/8/8/2PPPPPP/RNBQKBNR w").unwrap();
+    game.play(square("b7"), square("a8")).unwrap();
+    game.upgrade_piece(0, square("a8")).unwrap();
+    game.play(square("e7"), square("e5")).unwrap();
+    assert_eq!(game.san_moves().unwrap(), vec!["bxa8=P", "e5"]);
+
+    game.save(&path).unwrap();
+    let loaded = Game::load(&path).unwrap();
+    assert_eq!(loaded.moves(), game.moves());
+    assert_eq!(loaded.to_fen(), game.to_fen());
+    std::fs::remove_file(&path).unwrap();
+  }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::engine::hint::{Hint, explain, quick_move};
+  use crate::game::Game;
+  use crate::pieces::types::position::Position;
+
+  fn chess_move(from: &str, to: &str) -> Move {
+    Move::new(
+      Position::from_algebraic(from).unwrap(),
+      Position::from_algebraic(to).unwrap(),
+    )
+  }
+
+  fn reason(fen: &str, from: &str, to: &str) -> String {
+    let game = Game::from_fen(fen).unwrap();
+    explain(game.chessboard(), &chess_move(from, to))
+  }
+
+  #[test]
+  fn test_captures_and_mates() {
+    assert_eq!(
+      reason("4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1", "d1", "d5"),
+      "captures the knight on d5"
+    );
+    assert_eq!(
+      reason("k7/8/1K6/8/8/8/8/7R w - - 0 1", "h1", "h8"),
+      "checkmates"
+    );
+    assert_eq!(
+      reason("8/5k2/4p3/3p4/8/8/8/3QK3 w - - 0 1", "d1", "d5"),
+      "captures the pawn on d5 but the queen can be taken"
+    );
+  }
+
+  #[test]
+  fn test_checks_and_threats() {
+    assert_eq!(
+      reason("r3k3/8/8/1N6/8/8/8/4K3 w - - 0 1", "b5", "c7"),
+      "gives check and threatens the rook on a8"
+    );
+    assert_eq!(
+      reason("4k3/8/8/r3q3/8/8/1N6/7K w - - 0 1", "b2", "c4"),
+      "forks the rook on a5 and the queen on e5"
+    );
+  }
+
+  #[test]
+  fn test_quiet_moves() {
+    assert_eq!(
+      reason("4k3/8/8/3p4/4N3/8/8/4K3 w - - 0 1", "e4", "g3"),
+      "moves the attacked knight away"
+    );
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+    assert_eq!(reason(start, "e2", "e4"), "advances the pawn");
+    assert_eq!(reason(start, "g1", "f3"), "improves the knight's position");
+  }
+
+  #[test]
+  fn test_quick_move() {
+    let game = Game::from_fen("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1").unwrap();
+    assert_eq!(quick_move(&game), Some(chess_move("d1", "d5")));
+
+    let game = Game::from_fen("k7/8/1K6/8/8/8/8/7R w - - 0 1").unwrap();
+    let hint = Hint::new(&game, quick_move(&game).unwrap());
+    assert_eq!(hint.san, "Rh8#");
+    assert_eq!(hint.reason, "checkmates");
+  }
+}
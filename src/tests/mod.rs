@@ -1,2 +1,31 @@
+mod bench_tests;
+mod bitbase_tests;
 mod board_manager_tests;
+mod book_builder_tests;
+mod book_tests;
 mod chessboard_tests;
+mod clock_tests;
+mod external_engine_tests;
+mod fen_tests;
+#[cfg(test)]
+mod fixtures;
+mod game_tests;
+mod hint_tests;
+mod move_generator_tests;
+mod pgn_tests;
+mod presenter_tests;
+mod raster_tests;
+mod replay_tests;
+mod search_tests;
+mod see_tests;
+mod serialization_tests;
+#[cfg(test)]
+mod shared_output;
+mod smp_tests;
+mod svg_tests;
+mod tablebase_tests;
+mod time_manager_tests;
+mod transposition_table_tests;
+mod tui_tests;
+mod uci_tests;
+mod xboard_tests;
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::chessboard::Chessboard;
+  use crate::game::Game;
+  use crate::move_generator::MoveGenerator;
+  use crate::pieces::piece::Piece;
+  use crate::pieces::types::color::Color;
+  use crate::tests::fixtures::square;
+
+  #[test]
+  fn test_standard_board_has_twenty_moves() {
+    let board = Chessboard::standard();
+    let move_generator = MoveGenerator::new(&board);
+
+    assert_eq!(move_generator.legal_moves(Color::White).len(), 20);
+    assert_eq!(move_generator.legal_moves(Color::Black).len(), 20);
+  }
+
+  #[test]
+  fn test_pinned_piece_cannot_move() {
+    // The white knight on e2 is pinned by the black rook on e8
+    let game = Game::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    let moves = game.legal_moves();
+
+    assert!(moves.iter().all(|m| m.from() != square("e2")));
+  }
+
+  #[test]
+  fn test_pawn_cannot_capture_forward() {
+    let game = Game::from_fen("4k3/8/8/8/4p3/4P3/8/4K3 w - - 0 1").unwrap();
+    let moves = game.legal_moves();
+
+    assert!(moves.iter().all(|m| m.from() != square("e3")));
+  }
+
+  #[test]
+  fn test_upgrade_moves_use_dead_pieces() {
+    // White is missing a queen and both rooks, so those can come back
+    let game = Game::from_fen("7k/P7/8/8/8/8/8/1NB1KBN1 w - - 0 1").unwrap();
+    let upgrades: Vec<Move> = game
+      .legal_moves()
+      .into_iter()
+      .filter(|m| m.from() == square("a7"))
+      .collect();
+
+    // One move per kind of dead piece: queen, rook and pawn
+    assert_eq!(upgrades.len(), 3);
+    assert!(upgrades.contains(&Move::with_promotion(
+      square("a7"),
+      square("a8"),
+      Piece::from_symbol('Q').unwrap()
+    )));
+  }
+
+  #[test]
+  fn test_checkmate_has_no_legal_moves() {
+    let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    let move_generator = MoveGenerator::new(game.chessboard());
+
+    assert!(move_generator.is_king_in_check(Color::Black));
+    assert!(!move_generator.has_legal_moves(Color::Black));
+  }
+}
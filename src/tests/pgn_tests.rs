@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::game::Game;
+  use crate::notation::pgn::{PgnResult, parse_pgn};
+  use crate::pieces::piece::Piece;
+  use crate::pieces::types::color::Color;
+  use crate::tests::fixtures::square;
+
+  #[test]
+  fn test_parse_tags_comments_and_variations() {
+    let text = r#"
+[Event "Casual"]
+[White "Alice"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) Nc6 $1
+3. Bb5; the Spanish
+a6 1-0
+
+[Event "Second"]
+
+1. d4 d5 1/2-1/2
+"#;
+    let games = parse_pgn(text).unwrap();
+
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].tag("White"), Some("Alice"));
+    assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+    assert_eq!(games[0].result, PgnResult::WhiteWins);
+    assert_eq!(games[1].tag("Event"), Some("Second"));
+    assert_eq!(games[1].moves, ["d4", "d5"]);
+    assert_eq!(games[1].result, PgnResult::Draw);
+  }
+
+  #[test]
+  fn test_result_falls_back_to_tag() {
+    let games = parse_pgn("[Result \"0-1\"]\n1. f3 e5 2. g4 Qh4#").unwrap();
+
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].result, PgnResult::BlackWins);
+    assert!(parse_pgn("{ no end").is_err());
+  }
+
+  #[test]
+  fn test_quoted_tag_values() {
+    let text = r#"[Event "Club [open] \"A\" \\ B"]
+[Site"Paris"]
+[Round   "2" ]
+1. e4 *"#;
+    let games = parse_pgn(text).unwrap();
+
+    assert_eq!(games[0].tag("Event"), Some(r#"Club [open] "A" \ B"#));
+    assert_eq!(games[0].tag("Site"), Some("Paris"));
+    assert_eq!(games[0].tag("Round"), Some("2"));
+    assert_eq!(games[0].moves, ["e4"]);
+
+    assert!(parse_pgn("[Event Casual]").is_err());
+    assert!(parse_pgn("[Event \"Casual\" 1. e4").is_err());
+    assert!(parse_pgn("[Event \"Casual]\n1. e4 *").is_err());
+  }
+
+  #[test]
+  fn test_replay_plays_main_line() {
+    let games = parse_pgn("1. f3 e5 2. g4 Qh4# 0-1").unwrap();
+    let game = games[0].replay(|_, _| true).unwrap();
+
+    assert_eq!(game.moves().len(), 4);
+    assert!(game.result().is_some());
+
+    // Stopping early leaves the game where it was
+    let game = games[0].replay(|game, _| game.moves().len() < 2).unwrap();
+    assert_eq!(game.moves().len(), 2);
+  }
+
+  #[test]
+  fn test_parse_san() {
+    let game = Game::new(Color::White);
+    assert_eq!(
+      game.parse_san("Nf3").unwrap(),
+      Move::new(square("g1"), square("f3"))
+    );
+    assert_eq!(
+      game.parse_san("e4!?").unwrap(),
+      Move::new(square("e2"), square("e4"))
+    );
+    assert_eq!(game.parse_san("Ke2"), Err("Illegal move: Ke2".to_string()));
+    assert_eq!(
+      game.parse_san("O-O"),
+      Err("Castling is not supported: O-O".to_string())
+    );
+    assert_eq!(game.parse_san("Zz9"), Err("Invalid move: Zz9".to_string()));
+
+    // Both rooks reach d1
+    let game = Game::from_fen("7k/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+    assert_eq!(
+      game.parse_san("Rd1"),
+      Err("Ambiguous move: Rd1".to_string())
+    );
+    assert_eq!(
+      game.parse_san("Rad1").unwrap(),
+      Move::new(square("a1"), square("d1"))
+    );
+  }
+
+  #[test]
+  fn test_parse_san_promotion() {
+    let game = Game::from_fen("7k/P7/8/8/8/8/8/1NB1KBN1 w - - 0 1").unwrap();
+
+    assert_eq!(
+      game.parse_san("a8=Q+").unwrap(),
+      Move::with_promotion(
+        square("a7"),
+        square("a8"),
+        Piece::from_symbol('Q').unwrap()
+      )
+    );
+    // No knight is missing to bring back
+    assert_eq!(game.parse_san("a8N"), Err("Illegal move: a8N".to_string()));
+  }
+
+  #[test]
+  fn test_to_san() {
+    let san = |fen: &str, from: &str, to: &str| {
+      let game = Game::from_fen(fen).unwrap();
+      game.to_san(&Move::new(square(from), square(to)))
+    };
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+    assert_eq!(san(start, "g1", "f3"), "Nf3");
+    assert_eq!(san(start, "e2", "e4"), "e4");
+
+    let open = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 1";
+    assert_eq!(san(open, "e4", "d5"), "exd5");
+    assert_eq!(san("6k1/8/8/8/8/8/4K3/R6R w - - 0 1", "a1", "d1"), "Rad1");
+    // Same file, so the row tells them apart
+    assert_eq!(san("R7/7k/8/8/8/8/4K3/R7 w - - 0 1", "a1", "a4"), "R1a4");
+    assert_eq!(san("k7/8/1K6/8/8/8/8/7R w - - 0 1", "h1", "h8"), "Rh8#");
+    assert_eq!(san("k7/8/2K5/8/8/8/8/7R w - - 0 1", "h1", "h8"), "Rh8+");
+
+    let game = Game::from_fen("7k/P7/8/8/8/8/8/1NB1KBN1 w - - 0 1").unwrap();
+    let promotion = Move::with_promotion(
+      square("a7"),
+      square("a8"),
+      Piece::from_symbol('Q').unwrap(),
+    );
+    assert_eq!(game.to_san(&promotion), "a8=Q+");
+    assert_eq!(game.parse_san(&game.to_san(&promotion)), Ok(promotion));
+  }
+
+  #[test]
+  fn test_to_pgn() {
+    let mut game = Game::new(Color::White);
+    for san in ["e4", "e5", "Nf3", "Nc6", "Bb5"] {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.starts_with("[Event \"?\"]\n"));
+    assert!(pgn.contains("[Result \"*\"]\n\n1. e4 e5 2. Nf3 Nc6 3. Bb5 *\n"));
+    assert!(!pgn.contains("[FEN"));
+
+    let loaded = Game::from_pgn(&pgn).unwrap();
+    assert_eq!(loaded.moves(), game.moves());
+    assert_eq!(loaded.to_fen(), game.to_fen());
+  }
+
+  #[test]
+  fn test_to_pgn_from_position() {
+    let mut game = Game::from_fen("k7/8/1K6/8/8/8/8/7R b - - 0 1").unwrap();
+    for san in ["Kb8", "Rh8#"] {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+
+    let pgn = game.to_pgn().unwrap();
+    assert!(
+      pgn.contains("[SetUp \"1\"]\n[FEN \"k7/8/1K6/8/8/8/8/7R b - - 0 1\"]")
+    );
+    assert!(pgn.ends_with("1... Kb8 2. Rh8# 1-0\n"));
+    assert_eq!(Game::from_pgn(&pgn).unwrap().to_fen(), game.to_fen());
+  }
+
+  #[test]
+  fn test_long_games_wrap() {
+    let mut game = Game::new(Color::White);
+    for _ in 0..10 {
+      for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+        game.play_move(game.parse_san(san).unwrap()).unwrap();
+      }
+    }
+
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.lines().all(|line| line.len() < 80));
+    assert_eq!(Game::from_pgn(&pgn).unwrap().moves(), game.moves());
+  }
+
+  #[test]
+  fn test_from_pgn_errors() {
+    assert_eq!(
+      Game::from_pgn("1. e4 e5 2. Ke3 *").err(),
+      Some("Move 2. Ke3: Illegal move: Ke3".to_string())
+    );
+    assert_eq!(
+      Game::from_pgn("1. e4 Qz9 *").err(),
+      Some("Move 1... Qz9: Invalid move: Qz9".to_string())
+    );
+    assert_eq!(
+      Game::from_pgn("").err(),
+      Some("Expected one game, found 0".to_string())
+    );
+    assert_eq!(
+      Game::from_pgn("1. e4 * 1. d4 *").err(),
+      Some("Expected one game, found 2".to_string())
+    );
+    assert_eq!(
+      Game::from_pgn("1. e4 {unfinished").err(),
+      Some("Unterminated PGN comment".to_string())
+    );
+    assert!(Game::from_pgn("[FEN \"8/8/9/8/8/8/8/8 w - - 0 1\"] *").is_err());
+  }
+}
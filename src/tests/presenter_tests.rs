@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+  use crate::chessboard::Chessboard;
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use crate::presenters::Presenter;
+  use crate::presenters::style::{Glyphs, RenderOptions};
+  use crate::tests::fixtures::square;
+
+  fn render(presenter: &impl Presenter, options: RenderOptions) -> String {
+    let mut out = Vec::new();
+    presenter.render_to(&mut out, &options).unwrap();
+    String::from_utf8(out).unwrap()
+  }
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  #[test]
+  fn test_ascii_board() {
+    let chessboard =
+      Chessboard::from_fen_placement("4k3/8/8/8/8/8/8/4K2R").unwrap();
+    let text = render(&chessboard, RenderOptions::default());
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[0], "  8 . . . . k . . . ");
+    assert_eq!(lines[7], "  1 . . . . K . . R ");
+    assert_eq!(lines[8], "    a b c d e f g h ");
+    assert!(lines[9].starts_with("White dead pieces: P P P P P P P P N N"));
+    assert!(!text.contains('\x1b'));
+  }
+
+  #[test]
+  fn test_black_at_the_bottom() {
+    let chessboard =
+      Chessboard::from_fen_placement("4k3/8/8/8/8/8/8/4K2R").unwrap();
+    let options = RenderOptions {
+      orientation: Color::Black,
+      ..RenderOptions::default()
+    };
+    let text = render(&chessboard, options);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "  1 R . . K . . . . ");
+    assert_eq!(lines[7], "  8 . . . k . . . . ");
+    assert_eq!(lines[8], "    h g f e d c b a ");
+  }
+
+  #[test]
+  fn test_unicode_glyphs() {
+    let chessboard =
+      Chessboard::from_fen_placement("4k3/8/8/8/8/8/8/4K2R").unwrap();
+    let options = RenderOptions {
+      glyphs: Glyphs::Unicode,
+      ..RenderOptions::default()
+    };
+    let text = render(&chessboard, options);
+    assert!(text.starts_with("  8 . . . . \u{265A} . . . \n"));
+    assert!(text.contains("  1 . . . . \u{2654} . . \u{2656} \n"));
+    assert!(text.contains("Black dead pieces: \u{265F} "));
+  }
+
+  #[test]
+  fn test_colored_squares() {
+    let game = Game::new(Color::White);
+    let options = RenderOptions {
+      color: true,
+      ..RenderOptions::default()
+    };
+    let text = render(&game, options);
+    let first_row = text.lines().nth(8).unwrap();
+    // a1 is dark and b1 light, with White's pieces in white
+    assert!(first_row.starts_with(
+      "  1 \x1b[1;38;5;231;48;5;137mR \x1b[0m\x1b[1;38;5;231;48;5;180mN \x1b[0m"
+    ));
+    assert_eq!(first_row.matches("\x1b[0m").count(), 8);
+    assert!(text.starts_with("Current player: White\n"));
+  }
+
+  #[test]
+  fn test_options_from_flags() {
+    assert_eq!(RenderOptions::parse(&[], false), RenderOptions::default());
+    assert!(RenderOptions::parse(&[], true).color);
+    assert_eq!(
+      RenderOptions::parse(&args(&["--unicode", "--no-color"]), true),
+      RenderOptions {
+        glyphs: Glyphs::Unicode,
+        color: false,
+        orientation: Color::White,
+      }
+    );
+    assert!(RenderOptions::parse(&args(&["--color"]), false).color);
+    assert_eq!(
+      RenderOptions::parse(&args(&["--unicode", "--ascii"]), true),
+      RenderOptions::default()
+    );
+  }
+
+  #[test]
+  fn test_display() {
+    let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 0 1").unwrap();
+    let chessboard = game.chessboard();
+    assert_eq!(
+      chessboard.to_string(),
+      chessboard.render_string(&RenderOptions::default())
+    );
+    assert_eq!(
+      game.to_string(),
+      format!("Current player: White\n{}", chessboard)
+    );
+    assert!(format!("{:#}", chessboard).contains("\u{2654} . . \u{2656}"));
+
+    let king = chessboard.get_piece(square("e1")).unwrap();
+    assert_eq!(format!("{}", king), "K");
+    assert_eq!(format!("{:#}", king), "\u{2654}");
+    assert_eq!(format!("[{:>3}]", king), "[  K]");
+  }
+
+  #[test]
+  fn test_render_to_writers() {
+    let game = Game::new(Color::White);
+    let options = RenderOptions {
+      orientation: Color::Black,
+      ..RenderOptions::default()
+    };
+
+    let mut text = String::from("Position:\n");
+    game.write_to(&mut text, &options).unwrap();
+    let mut bytes = Vec::new();
+    game.render_to(&mut bytes, &options).unwrap();
+    assert_eq!(
+      text,
+      format!("Position:\n{}", String::from_utf8(bytes).unwrap())
+    );
+    assert!(text.contains("  1 R N B K Q B N R \n"));
+  }
+}
@@ -0,0 +1,374 @@
+#[cfg(test)]
+mod tests {
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use crate::presenters::gif::encode_gif;
+  use crate::presenters::png::{adler32, crc32};
+  use crate::presenters::raster::Image;
+  use crate::presenters::svg::{
+    BACKGROUND, DARK_SQUARE, LIGHT_SQUARE, SvgOptions,
+  };
+  use crate::tests::fixtures::{endgame, square};
+  use std::time::Duration;
+
+  fn small_board() -> SvgOptions {
+    SvgOptions {
+      square_size: 10,
+      coordinates: false,
+      ..SvgOptions::default()
+    }
+  }
+
+  // Pixels of a piece on the square at (column, row) from the top left of
+  // a board of 10 pixel squares
+  fn piece_pixels(image: &Image, column: usize, row: usize) -> usize {
+    (row * 10..row * 10 + 10)
+      .flat_map(|y| (column * 10..column * 10 + 10).map(move |x| (x, y)))
+      .filter(|&(x, y)| {
+        ![DARK_SQUARE, LIGHT_SQUARE].contains(&image.pixel(x, y))
+      })
+      .count()
+  }
+
+  // The chunks of a PNG, checking each one's CRC
+  fn png_chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut chunks = Vec::new();
+    let mut rest = &png[8..];
+    while !rest.is_empty() {
+      let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+      let (body, crc) = rest[4..].split_at(4 + length);
+      let crc = u32::from_be_bytes(crc[..4].try_into().unwrap());
+      assert_eq!(crc32(body), crc);
+      let kind = String::from_utf8(body[..4].to_vec()).unwrap();
+      chunks.push((kind, body[4..].to_vec()));
+      rest = &rest[8 + length + 4..];
+    }
+    chunks
+  }
+
+  // Bits from the least significant one of each byte, as DEFLATE and GIF's
+  // LZW pack them
+  struct BitReader<'a> {
+    bytes: &'a [u8],
+    bit: usize,
+  }
+
+  impl BitReader<'_> {
+    fn bits(&mut self, count: usize) -> usize {
+      let value = (0..count)
+        .map(|offset| {
+          (self.bytes[(self.bit + offset) / 8] >> ((self.bit + offset) % 8)) & 1
+        })
+        .enumerate()
+        .fold(0, |value, (offset, bit)| value | (bit as usize) << offset);
+      self.bit += count;
+      value
+    }
+
+    // Huffman codes start from their most significant bit
+    fn code(&mut self, count: usize) -> usize {
+      (0..count).fold(0, |code, _| code << 1 | self.bits(1))
+    }
+  }
+
+  // Inflates a zlib stream of blocks with the fixed Huffman codes, the only
+  // ones the encoder writes, and checks its Adler-32
+  fn inflate(stream: &[u8]) -> Vec<u8> {
+    assert_eq!(&stream[..2], &[0x78, 0x01]);
+    // Lengths and distances from their codes and extra bits
+    let mut length_bases = vec![(3, 0)];
+    for code in 1..28 {
+      let extra = if code < 8 { 0 } else { (code - 4) / 4 };
+      let (base, previous_extra) = length_bases[code - 1];
+      length_bases.push((base + (1 << previous_extra), extra));
+    }
+    length_bases.push((258, 0));
+    let mut distance_bases = vec![(1, 0)];
+    for code in 1..30 {
+      let extra = if code < 4 { 0 } else { code / 2 - 1 };
+      let (base, previous_extra) = distance_bases[code - 1];
+      distance_bases.push((base + (1 << previous_extra), extra));
+    }
+
+    let mut reader = BitReader {
+      bytes: &stream[2..stream.len() - 4],
+      bit: 0,
+    };
+    let mut data: Vec<u8> = Vec::new();
+    loop {
+      let last = reader.bits(1);
+      assert_eq!(reader.bits(2), 1, "Only fixed Huffman blocks");
+      loop {
+        let mut code = reader.code(7);
+        let symbol = if code < 24 {
+          256 + code
+        } else {
+          code = code << 1 | reader.bits(1);
+          match code {
+            0x30..=0xbf => code - 0x30,
+            0xc0..=0xc7 => 280 + code - 0xc0,
+            _ => 144 + (code << 1 | reader.bits(1)) - 0x190,
+          }
+        };
+        match symbol {
+          0..=255 => data.push(symbol as u8),
+          256 => break,
+          _ => {
+            let (base, extra) = length_bases[symbol - 257];
+            let length = base + reader.bits(extra);
+            let (base, extra) = distance_bases[reader.code(5)];
+            let distance = base + reader.bits(extra);
+            for _ in 0..length {
+              data.push(data[data.len() - distance]);
+            }
+          }
+        }
+      }
+      if last == 1 {
+        break;
+      }
+    }
+    let checksum = &stream[stream.len() - 4..];
+    assert_eq!(adler32(&data).to_be_bytes(), checksum);
+    data
+  }
+
+  // The palette, delays and decoded color indices of each GIF frame
+  fn decode_gif(gif: &[u8]) -> (Vec<[u8; 3]>, Vec<u16>, Vec<Vec<u8>>) {
+    assert_eq!(&gif[..6], b"GIF89a");
+    let colors = 2 << (gif[10] & 0x07);
+    let palette = gif[13..13 + 3 * colors]
+      .chunks(3)
+      .map(|color| [color[0], color[1], color[2]])
+      .collect();
+    let (mut delays, mut frames) = (Vec::new(), Vec::new());
+    let mut index = 13 + 3 * colors;
+    loop {
+      match gif[index] {
+        0x3b => break,
+        0x21 => {
+          if gif[index + 1] == 0xf9 {
+            delays.push(u16::from_le_bytes([gif[index + 4], gif[index + 5]]));
+          }
+          index += 2;
+          while gif[index] != 0 {
+            index += gif[index] as usize + 1;
+          }
+          index += 1;
+        }
+        0x2c => {
+          let min_code_size = gif[index + 10] as u32;
+          index += 11;
+          let mut data = Vec::new();
+          while gif[index] != 0 {
+            let length = gif[index] as usize;
+            data.extend(&gif[index + 1..index + 1 + length]);
+            index += length + 1;
+          }
+          index += 1;
+          frames.push(decode_lzw(&data, min_code_size));
+        }
+        byte => panic!("Unexpected block {:#x}", byte),
+      }
+    }
+    (palette, delays, frames)
+  }
+
+  fn decode_lzw(data: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1 << min_code_size;
+    let reset = || -> Vec<Vec<u8>> {
+      let mut table: Vec<Vec<u8>> =
+        (0..clear).map(|code| vec![code as u8]).collect();
+      table.extend([Vec::new(), Vec::new()]);
+      table
+    };
+    let mut table = reset();
+    let mut code_size = min_code_size + 1;
+    let mut previous: Option<usize> = None;
+    let mut pixels = Vec::new();
+    let mut reader = BitReader {
+      bytes: data,
+      bit: 0,
+    };
+    loop {
+      let code = reader.bits(code_size as usize);
+      if code == clear {
+        table = reset();
+        code_size = min_code_size + 1;
+        previous = None;
+        continue;
+      }
+      if code == clear + 1 {
+        return pixels;
+      }
+      let Some(last) = previous else {
+        pixels.extend(&table[code]);
+        previous = Some(code);
+        continue;
+      };
+      let entry = match table.get(code) {
+        Some(entry) => entry.clone(),
+        None if code == table.len() => {
+          [table[last].clone(), vec![table[last][0]]].concat()
+        }
+        None => panic!("Code {} before it was defined", code),
+      };
+      if table.len() < 4096 {
+        table.push([table[last].clone(), vec![entry[0]]].concat());
+      }
+      pixels.extend(&entry);
+      previous = Some(code);
+      if table.len() == 1 << code_size && code_size < 12 {
+        code_size += 1;
+      }
+    }
+  }
+
+  #[test]
+  fn test_image_squares_and_highlights() {
+    let image = endgame().to_image(&small_board());
+    assert_eq!((image.width(), image.height()), (80, 80));
+    // a1 in the bottom left corner is dark, a8 above it light
+    assert_eq!(image.pixel(0, 79), DARK_SQUARE);
+    assert_eq!(image.pixel(0, 0), LIGHT_SQUARE);
+
+    let options = SvgOptions {
+      highlights: vec![square("e2")],
+      ..small_board()
+    };
+    // Half way between the light square and yellow
+    let image = endgame().to_image(&options);
+    assert_eq!(image.pixel(41, 61), [0xf8, 0xec, 0x74]);
+
+    // A margin around the board for the coordinates, drawn in it
+    let image = endgame().to_image(&SvgOptions {
+      coordinates: true,
+      ..small_board()
+    });
+    assert_eq!((image.width(), image.height()), (90, 90));
+    assert_eq!(image.pixel(0, 0), BACKGROUND);
+    assert_eq!(image.pixel(5, 84), DARK_SQUARE);
+    assert!(image.pixels().iter().any(|&pixel| pixel[0] == 0x40));
+  }
+
+  #[test]
+  fn test_image_orientation() {
+    let white = endgame().to_image(&small_board());
+    // The white king on e1 and rook on h1 at the bottom
+    assert!(piece_pixels(&white, 4, 7) > 0);
+    assert!(piece_pixels(&white, 7, 7) > 0);
+    assert_eq!(piece_pixels(&white, 0, 0), 0);
+
+    let black = endgame().to_image(&SvgOptions {
+      orientation: Color::Black,
+      ..small_board()
+    });
+    // Now at the top, the rook in the left corner
+    assert!(piece_pixels(&black, 3, 0) > 0);
+    assert!(piece_pixels(&black, 0, 0) > 0);
+    assert_eq!(piece_pixels(&black, 7, 7), 0);
+    assert_eq!(black.pixel(79, 0), DARK_SQUARE);
+  }
+
+  #[test]
+  fn test_checksums() {
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    assert_eq!(adler32(b""), 1);
+  }
+
+  #[test]
+  fn test_png_structure() {
+    let options = SvgOptions {
+      square_size: 30,
+      ..SvgOptions::default()
+    };
+    let png = endgame().to_png(&options);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    let chunks = png_chunks(&png);
+    let kinds: Vec<&str> =
+      chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+    assert_eq!(kinds, ["IHDR", "IDAT", "IEND"]);
+    let header = &chunks[0].1;
+    assert_eq!(&header[..8], &[0, 0, 1, 14, 0, 0, 1, 14]);
+    assert_eq!(&header[8..], &[8, 2, 0, 0, 0]);
+    // Flat colors compress to a small part of the 270 by 270 pixels
+    let stream = &chunks[1].1;
+    assert!(stream.len() < 270 * 271 * 3 / 10);
+
+    // Rows of RGB pixels, each after a 0 for no filtering
+    let data = inflate(stream);
+    let image = endgame().to_image(&options);
+    assert_eq!(data.len(), 270 * (270 * 3 + 1));
+    for (row, pixels) in
+      data.chunks(270 * 3 + 1).zip(image.pixels().chunks(270))
+    {
+      assert_eq!(row[0], 0);
+      assert!(row[1..].iter().eq(pixels.iter().flatten()));
+    }
+  }
+
+  #[test]
+  fn test_gif_of_game() {
+    let mut game = Game::new(Color::White);
+    game.play(square("e2"), square("e4")).unwrap();
+    game.play(square("e7"), square("e5")).unwrap();
+    let options = SvgOptions {
+      square_size: 20,
+      ..SvgOptions::default()
+    };
+    let gif = game.to_gif(&options, Duration::from_millis(300));
+    assert_eq!(&gif[6..10], &[180, 0, 180, 0]);
+    assert!(gif.windows(11).any(|bytes| bytes == b"NETSCAPE2.0"));
+    assert_eq!(gif.last(), Some(&0x3b));
+
+    // The start and each move, the last position held longer
+    let (palette, delays, frames) = decode_gif(&gif);
+    assert_eq!(delays, [30, 30, 120]);
+    assert_eq!(frames.len(), 3);
+
+    let mut replay = Game::new(Color::White);
+    for (number, frame) in frames.iter().enumerate() {
+      let mut expected_options = options.clone();
+      if let Some(chess_move) = game.moves()[..number].last() {
+        replay.play_move(*chess_move).unwrap();
+        expected_options.highlights = vec![chess_move.from(), chess_move.to()];
+      }
+      let image = replay.chessboard().to_image(&expected_options);
+      assert_eq!(frame.len(), 180 * 180);
+      // Colors in the palette come back as they were
+      for (&index, &pixel) in frame.iter().zip(image.pixels()) {
+        if palette.contains(&pixel) {
+          assert_eq!(palette[index as usize], pixel);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_gif_restarts_full_code_table() {
+    // Enough different pixels to fill the 4096 codes several times
+    let options = SvgOptions {
+      square_size: 60,
+      highlights: vec![square("e1"), square("h8"), square("d4")],
+      arrows: vec![(square("h1"), square("a8"))],
+      ..SvgOptions::default()
+    };
+    let image = endgame().to_image(&options);
+    let gif = encode_gif(std::slice::from_ref(&image), Duration::from_secs(1));
+    let (palette, _, frames) = decode_gif(&gif);
+    assert_eq!(frames[0].len(), image.pixels().len());
+    for (&index, &pixel) in frames[0].iter().zip(image.pixels()) {
+      if palette.contains(&pixel) {
+        assert_eq!(palette[index as usize], pixel);
+      }
+    }
+  }
+
+  #[test]
+  fn test_gif_without_frames() {
+    assert!(encode_gif(&[], Duration::from_secs(1)).is_empty());
+  }
+}
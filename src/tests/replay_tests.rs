@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use crate::ui::replay::Replay;
+
+  fn game(sans: &[&str]) -> Game {
+    let mut game = Game::new(Color::White);
+    for san in sans {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+    game
+  }
+
+  #[test]
+  fn test_stepping_through_the_game() {
+    let mut replay = Replay::new(&game(&["e4", "e5", "Nf3"]));
+    let start = replay.chessboard().to_fen_placement();
+    assert_eq!(replay.len(), 3);
+    assert_eq!(replay.ply(), 0);
+    assert!(replay.back().is_err());
+
+    replay.forward().unwrap();
+    assert_eq!(
+      replay.chessboard().to_fen_placement(),
+      "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR"
+    );
+    assert_eq!(replay.player_color(), Color::Black);
+
+    replay.goto(3).unwrap();
+    assert!(replay.forward().is_err());
+    assert_eq!(replay.goto(4), Err("The game only has 3 plies".to_string()));
+    replay.back().unwrap();
+    assert_eq!(replay.ply(), 2);
+
+    replay.goto(0).unwrap();
+    assert_eq!(replay.chessboard().to_fen_placement(), start);
+  }
+
+  #[test]
+  fn test_move_list_highlights_current_ply() {
+    let mut replay = Replay::new(&game(&["e4", "e5", "Nf3", "Nc6"]));
+    assert_eq!(replay.move_list(), "1. e4 e5 2. Nf3 Nc6");
+    replay.goto(3).unwrap();
+    assert_eq!(replay.move_list(), "1. e4 e5 2. [Nf3] Nc6");
+
+    let mut game = Game::from_fen("k7/8/1K6/8/8/8/8/7R b - - 0 1").unwrap();
+    for san in ["Kb8", "Rh8#"] {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+    let mut replay = Replay::new(&game);
+    replay.goto(1).unwrap();
+    assert_eq!(replay.move_list(), "1... [Kb8] 2. Rh8#");
+  }
+
+  #[test]
+  fn test_long_move_lists_wrap() {
+    let moves = ["Nf3", "Nf6", "Ng1", "Ng8"].repeat(5);
+    let replay = Replay::new(&game(&moves));
+    let move_list = replay.move_list();
+    let lines: Vec<&str> = move_list.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("1. Nf3 Nf6 2. Ng1 Ng8"));
+    assert!(lines[1].starts_with("9. Nf3"));
+  }
+
+  #[test]
+  fn test_open() {
+    let path = std::env::temp_dir()
+      .join(format!("rust_chess_replay_{}.pgn", std::process::id()));
+    std::fs::write(
+      &path,
+      "[Event \"One\"]\n\n1. d4 d5 *\n\n[Event \"Two\"]\n\n1. e4 e5 2. O-O *\n",
+    )
+    .unwrap();
+
+    let (replay, warning) = Replay::open(&path, 1).unwrap();
+    assert_eq!(replay.len(), 2);
+    assert_eq!(warning, None);
+
+    let (replay, warning) = Replay::open(&path, 2).unwrap();
+    assert_eq!(replay.move_list(), "1. e4 e5");
+    assert_eq!(
+      warning,
+      Some("Stopped after 2 plies: O-O can't be played here".to_string())
+    );
+
+    assert_eq!(
+      Replay::open(&path, 3).err(),
+      Some(format!("No game 3 in {}", path.display()))
+    );
+    std::fs::remove_file(path).unwrap();
+  }
+}
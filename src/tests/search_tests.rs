@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::engine::Engine;
+  use crate::engine::search::{Score, SearchLimits, SearchOptions};
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+
+  fn search(fen: &str, depth: u32) -> (Option<Move>, Score) {
+    let game = Game::from_fen(fen).unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let result = Engine::new().search(
+      &game,
+      SearchLimits::depth(depth),
+      stop,
+      &mut |_| {},
+    );
+    (result.best_move, result.score)
+  }
+
+  #[test]
+  fn test_finds_mate_in_one() {
+    let (best_move, score) = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+
+    assert_eq!(best_move.unwrap().to_long_algebraic(), "a1a8");
+    assert_eq!(score, Score::Mate(1));
+  }
+
+  #[test]
+  fn test_captures_hanging_queen() {
+    let (best_move, _) = search("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", 2);
+
+    assert_eq!(best_move.unwrap().to_long_algebraic(), "d1d5");
+  }
+
+  #[test]
+  fn test_no_move_when_checkmated() {
+    let (best_move, _) = search("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1", 2);
+
+    assert!(best_move.is_none());
+  }
+
+  #[test]
+  fn test_search_respects_stop() {
+    let game = Game::new(Color::White);
+    let stop = Arc::new(AtomicBool::new(true));
+    let result =
+      Engine::new().search(&game, SearchLimits::default(), stop, &mut |_| {});
+
+    // Nothing was searched, but a legal move is still returned
+    assert_eq!(result.depth, 0);
+    assert!(result.best_move.is_some());
+  }
+
+  #[test]
+  fn test_finds_mate_in_two_with_each_pruning_toggle() {
+    let game =
+      Game::from_fen("1r4k1/5ppp/8/8/8/8/3R1PPP/3R2K1 w - - 0 1").unwrap();
+    let defaults = SearchOptions::default();
+    let options = [
+      defaults,
+      SearchOptions::without_pruning(),
+      SearchOptions {
+        null_move_pruning: false,
+        ..defaults
+      },
+      SearchOptions {
+        late_move_reductions: false,
+        ..defaults
+      },
+      SearchOptions {
+        futility_pruning: false,
+        ..defaults
+      },
+      SearchOptions {
+        reverse_futility_pruning: false,
+        ..defaults
+      },
+      SearchOptions {
+        aspiration_windows: false,
+        ..defaults
+      },
+    ];
+
+    for search_options in options {
+      let mut engine = Engine::new();
+      engine.set_search_options(search_options);
+      let stop = Arc::new(AtomicBool::new(false));
+      let result =
+        engine.search(&game, SearchLimits::depth(4), stop, &mut |_| {});
+
+      assert_eq!(result.best_move.unwrap().to_long_algebraic(), "d2d8");
+      assert_eq!(result.score, Score::Mate(2));
+    }
+  }
+
+  #[test]
+  fn test_pruning_options() {
+    let mut engine = Engine::new();
+    engine.set_option("NullMovePruning", "false").unwrap();
+    engine.set_option("AspirationWindows", "false").unwrap();
+
+    let search_options = engine.search_options();
+    assert!(!search_options.null_move_pruning);
+    assert!(!search_options.aspiration_windows);
+    assert!(search_options.late_move_reductions);
+    assert!(engine.set_option("FutilityPruning", "maybe").is_err());
+  }
+
+  #[test]
+  fn test_multi_pv_reports_distinct_lines() {
+    // Taking the queen is best, then the rook
+    let game = Game::from_fen("4k3/8/8/3q1r2/8/8/8/3RKR2 w - - 0 1").unwrap();
+    let mut engine = Engine::new();
+    engine.set_option("MultiPV", "3").unwrap();
+    assert!(engine.set_option("MultiPV", "0").is_err());
+
+    let mut lines = Vec::new();
+    let stop = Arc::new(AtomicBool::new(false));
+    let result =
+      engine.search(&game, SearchLimits::depth(3), stop, &mut |info| {
+        if info.depth == 3 {
+          lines.push((info.multipv, info.pv[0], info.score));
+        }
+      });
+
+    let ranks: Vec<usize> = lines.iter().map(|line| line.0).collect();
+    assert_eq!(ranks, vec![1, 2, 3]);
+    assert_eq!(lines[0].1.to_long_algebraic(), "d1d5");
+    assert_eq!(lines[1].1.to_long_algebraic(), "f1f5");
+    assert_ne!(lines[2].1, lines[0].1);
+    assert_ne!(lines[2].1, lines[1].1);
+    assert_eq!(result.best_move, Some(lines[0].1));
+    assert_eq!(result.score, lines[0].2);
+  }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::engine::Engine;
+  use crate::engine::search::SearchLimits;
+  use crate::game::Game;
+  use crate::move_generator::MoveGenerator;
+  use crate::pieces::types::{color::Color, position::Position};
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+
+  fn see(fen: &str, from: &str, to: &str) -> i32 {
+    let game = Game::from_fen(fen).unwrap();
+    let chess_move = Move::new(
+      Position::from_algebraic(from).unwrap(),
+      Position::from_algebraic(to).unwrap(),
+    );
+    game.chessboard().see(&chess_move)
+  }
+
+  #[test]
+  fn test_undefended_capture_wins_the_piece() {
+    assert_eq!(see("4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1", "d1", "d5"), 320);
+  }
+
+  #[test]
+  fn test_defended_captures() {
+    // Pawn takes a knight defended by a pawn
+    assert_eq!(see("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1", "e4", "d5"), 220);
+    // Rook takes a pawn defended by a pawn
+    assert_eq!(see("4k3/8/2p5/3p4/8/8/8/3RK3 w - - 0 1", "d1", "d5"), -400);
+  }
+
+  #[test]
+  fn test_x_ray_attackers_join_the_exchange() {
+    // Rook takes a pawn defended by a rook, backed up by a second rook
+    let fen = "3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1";
+    assert_eq!(see(fen, "d2", "d5"), 100);
+
+    // Without the second rook the exchange loses the rook for a pawn
+    let fen = "3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1";
+    assert_eq!(see(fen, "d2", "d5"), -400);
+  }
+
+  #[test]
+  fn test_attackers() {
+    let game = Game::from_fen("3rk3/8/8/3p4/8/2N5/3R4/3RK3 w - - 0 1").unwrap();
+    let move_generator = MoveGenerator::new(game.chessboard());
+    let d5 = Position::from_algebraic("d5").unwrap();
+
+    // The rook on d1 is hidden behind the one on d2
+    let attackers = move_generator.attackers(d5, Color::White);
+    assert_eq!(
+      attackers,
+      vec![
+        Position::from_algebraic("d2").unwrap(),
+        Position::from_algebraic("c3").unwrap(),
+      ]
+    );
+    assert_eq!(move_generator.attackers(d5, Color::Black).len(), 1);
+  }
+
+  #[test]
+  fn test_quiescence_sees_recapture_at_horizon() {
+    // Taking the pawn on d5 with the queen loses her to the c6 pawn
+    let game = Game::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let result =
+      Engine::new().search(&game, SearchLimits::depth(1), stop, &mut |_| {});
+
+    assert_ne!(result.best_move.unwrap().to_long_algebraic(), "d1d5");
+  }
+}
@@ -0,0 +1,108 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::chessboard::{Chessboard, MoveResult};
+  use crate::game::Game;
+  use crate::pieces::piece::Piece;
+  use crate::pieces::types::{color::Color, position::Position};
+  use crate::tests::fixtures::square;
+  use serde_json::json;
+
+  #[test]
+  fn test_simple_types() {
+    assert_eq!(serde_json::to_value(square("e4")).unwrap(), json!("e4"));
+    assert_eq!(
+      serde_json::from_value::<Position>(json!("h8")).unwrap(),
+      square("h8")
+    );
+    assert!(serde_json::from_value::<Position>(json!("i9")).is_err());
+
+    assert_eq!(serde_json::to_value(Color::Black).unwrap(), json!("black"));
+    assert_eq!(
+      serde_json::from_value::<Color>(json!("white")).unwrap(),
+      Color::White
+    );
+
+    let knight = Piece::from_symbol('n').unwrap();
+    assert_eq!(serde_json::to_value(knight).unwrap(), json!("n"));
+    assert_eq!(serde_json::from_value::<Piece>(json!("n")).unwrap(), knight);
+    assert!(serde_json::from_value::<Piece>(json!("x")).is_err());
+    assert!(serde_json::from_value::<Piece>(json!("nn")).is_err());
+
+    assert_eq!(
+      serde_json::to_value(MoveResult::CanUpgradePiece).unwrap(),
+      json!("can_upgrade_piece")
+    );
+    assert_eq!(
+      serde_json::from_value::<MoveResult>(json!("check_king")).unwrap(),
+      MoveResult::CheckKing
+    );
+  }
+
+  #[test]
+  fn test_moves() {
+    let chess_move = Move::new(square("e2"), square("e4"));
+    let value = json!({"from": "e2", "to": "e4"});
+    assert_eq!(serde_json::to_value(chess_move).unwrap(), value);
+    assert_eq!(serde_json::from_value::<Move>(value).unwrap(), chess_move);
+
+    let queen = Piece::from_symbol('Q').unwrap();
+    let promotion = Move::with_promotion(square("a7"), square("a8"), queen);
+    let value = json!({"from": "a7", "to": "a8", "promotion": "Q"});
+    assert_eq!(serde_json::to_value(promotion).unwrap(), value);
+    assert_eq!(serde_json::from_value::<Move>(value).unwrap(), promotion);
+  }
+
+  #[test]
+  fn test_chessboard() {
+    let game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+    let value = serde_json::to_value(game.chessboard()).unwrap();
+    assert_eq!(value["pieces"], json!({"e1": "K", "e2": "P", "e8": "k"}));
+    assert_eq!(value["white_dead_pieces"].as_array().unwrap().len(), 14);
+
+    let chessboard: Chessboard = serde_json::from_value(value).unwrap();
+    assert_eq!(chessboard.to_fen_placement(), "4k3/8/8/8/8/8/4P3/4K3");
+    assert_eq!(
+      chessboard.white_dead_pieces(),
+      game.chessboard().white_dead_pieces()
+    );
+
+    let wrong_dead_piece = json!({
+      "pieces": {},
+      "white_dead_pieces": ["q"],
+      "black_dead_pieces": []
+    });
+    assert!(serde_json::from_value::<Chessboard>(wrong_dead_piece).is_err());
+  }
+
+  #[test]
+  fn test_game_round_trip() {
+    let mut game = Game::new(Color::White);
+    for san in ["e4", "d5", "exd5"] {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+
+    let json = serde_json::to_string(&game).unwrap();
+    let loaded: Game = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.moves(), game.moves());
+    assert_eq!(loaded.to_fen(), game.to_fen());
+    assert_eq!(loaded.initial_fen(), game.initial_fen());
+    // The history comes back too
+    let mut loaded = loaded;
+    loaded.undo().unwrap();
+    assert_eq!(loaded.player_color(), Color::White);
+    assert_eq!(loaded.moves().len(), 2);
+  }
+
+  #[test]
+  fn test_inconsistent_game() {
+    let game = Game::new(Color::White);
+    let mut value = serde_json::to_value(&game).unwrap();
+    value["moves"] = json!([{"from": "e2", "to": "e5"}]);
+    let error = serde_json::from_value::<Game>(value.clone()).err().unwrap();
+    assert_eq!(error.to_string(), "Move 1 (e2e5): Illegal move");
+
+    value["moves"] = json!([{"from": "e2", "to": "e4"}]);
+    assert!(serde_json::from_value::<Game>(value).is_err());
+  }
+}
@@ -0,0 +1,27 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+// Collects what a protocol writes from its search thread, for assertions
+#[derive(Clone, Default)]
+pub struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl SharedOutput {
+  pub fn lines(&self) -> Vec<String> {
+    let bytes = self.0.lock().unwrap().clone();
+    String::from_utf8(bytes)
+      .unwrap()
+      .lines()
+      .map(|line| line.to_string())
+      .collect()
+  }
+}
+
+impl Write for SharedOutput {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0.lock().unwrap().write(buf)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
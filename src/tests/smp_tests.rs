@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::engine::Engine;
+  use crate::engine::search::{Score, SearchLimits, SearchResult};
+  use crate::engine::transposition_table::{Bound, TranspositionTable};
+  use crate::game::Game;
+  use crate::pieces::piece::Piece;
+  use crate::pieces::queen::Queen;
+  use crate::pieces::types::{color::Color, position::Position};
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+  use std::thread;
+
+  fn search(engine: &mut Engine, fen: &str, depth: u32) -> SearchResult {
+    let game = Game::from_fen(fen).unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    engine.search(&game, SearchLimits::depth(depth), stop, &mut |_| {})
+  }
+
+  #[test]
+  fn test_single_thread_search_is_deterministic() {
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b - - 0 1";
+    let first = search(&mut Engine::new(), fen, 4);
+    let second = search(&mut Engine::new(), fen, 4);
+
+    assert_eq!(first.nodes, second.nodes);
+    assert_eq!(first.pv, second.pv);
+  }
+
+  #[test]
+  fn test_multi_threaded_search() {
+    let mut engine = Engine::new();
+    engine.set_option("Threads", "4").unwrap();
+    assert_eq!(engine.threads(), 4);
+
+    let result = search(&mut engine, "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+    assert_eq!(result.best_move.unwrap().to_long_algebraic(), "a1a8");
+    assert_eq!(result.score, Score::Mate(1));
+
+    assert!(engine.set_option("Threads", "0").is_err());
+  }
+
+  #[test]
+  fn test_table_is_shared_between_threads() {
+    let table = TranspositionTable::new(1);
+    let promotion = Piece::Queen(Queen::new(Color::Black));
+    let best_move = Move::with_promotion(
+      Position::from_algebraic("b2").unwrap(),
+      Position::from_algebraic("b1").unwrap(),
+      promotion,
+    );
+
+    thread::scope(|scope| {
+      for key in 0..4 {
+        let table = &table;
+        scope.spawn(move || {
+          table.store(key, key as u32, Bound::Lower, -50, Some(best_move), 0);
+        });
+      }
+    });
+
+    for key in 0..4 {
+      let entry = table.probe(key).unwrap();
+      assert_eq!(entry.depth(), key as u32);
+      assert_eq!(entry.score(0), -50);
+      assert_eq!(entry.best_move(), Some(best_move));
+    }
+  }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+  use crate::pieces::types::color::Color;
+  use crate::presenters::svg::SvgOptions;
+  use crate::tests::fixtures::{endgame, square};
+
+  #[test]
+  fn test_snapshot() {
+    let options = SvgOptions {
+      square_size: 40,
+      highlights: vec![square("e1"), square("e2")],
+      arrows: vec![(square("h1"), square("h8"))],
+      ..SvgOptions::default()
+    };
+    assert_eq!(
+      endgame().to_svg(&options),
+      include_str!("snapshots/endgame.svg")
+    );
+  }
+
+  #[test]
+  fn test_size_and_coordinates() {
+    let svg = endgame().to_svg(&SvgOptions::default());
+    assert!(svg.starts_with(
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"405\" height=\"405\""
+    ));
+    assert_eq!(svg.matches("<text ").count(), 16);
+    assert!(svg.ends_with("</svg>\n"));
+
+    let options = SvgOptions {
+      square_size: 50,
+      coordinates: false,
+      ..SvgOptions::default()
+    };
+    let svg = endgame().to_svg(&options);
+    assert!(svg.contains("width=\"400\" height=\"400\""));
+    assert!(!svg.contains("<text "));
+    // Squares then pieces, one group each
+    assert_eq!(svg.matches("<rect ").count(), 64);
+    assert_eq!(svg.matches("<g ").count(), 3);
+  }
+
+  #[test]
+  fn test_orientation() {
+    let options = SvgOptions {
+      coordinates: false,
+      ..SvgOptions::default()
+    };
+    let white = endgame().to_svg(&options);
+    // a1 is the dark square in the bottom left corner
+    assert!(white.contains(
+      "<rect x=\"0\" y=\"315\" width=\"45\" height=\"45\" fill=\"#b58863\"/>"
+    ));
+    assert!(white.contains("translate(180 315)"));
+
+    let options = SvgOptions {
+      orientation: Color::Black,
+      ..options
+    };
+    let black = endgame().to_svg(&options);
+    // Now in the top right corner, with the white king at the top
+    assert!(black.contains(
+      "<rect x=\"315\" y=\"0\" width=\"45\" height=\"45\" fill=\"#b58863\"/>"
+    ));
+    assert!(black.contains("translate(135 0)"));
+  }
+
+  #[test]
+  fn test_arrow_to_the_same_square_is_skipped() {
+    let options = SvgOptions {
+      arrows: vec![(square("e1"), square("e1"))],
+      ..SvgOptions::default()
+    };
+    let svg = endgame().to_svg(&options);
+    assert!(!svg.contains("fill-opacity=\"0.8\""));
+  }
+}
@@ -0,0 +1,259 @@
+#[cfg(test)]
+mod tests {
+  use crate::chessboard::Chessboard;
+  use crate::engine::Engine;
+  use crate::engine::search::{Score, SearchLimits, TABLEBASE_WIN};
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use crate::protocols::uci::Uci;
+  use crate::tablebase::{Tablebase, Wdl};
+  use crate::tests::shared_output::SharedOutput;
+  use std::fs;
+  use std::io::Cursor;
+  use std::path::PathBuf;
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+
+  const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+  const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+  // KQvK with white to move and king, queen, king for both sides
+  const KQVK_PIECES: [u8; 3] = [0x66, 0x55, 0xEE];
+
+  // A fresh directory for the tables of one test
+  fn table_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+      "rust_chess_tb_{}_{}",
+      std::process::id(),
+      name
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  // WDL table giving every position the same result for each side to move,
+  // stored as the value plus 2
+  fn single_value_wdl(white: u8, black: u8) -> Vec<u8> {
+    let mut bytes = WDL_MAGIC.to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00]);
+    bytes.extend_from_slice(&KQVK_PIECES);
+    bytes.push(0x00);
+    bytes.extend_from_slice(&[0x80, white, 0x80, black]);
+    bytes
+  }
+
+  // DTZ table for white to move, counting `plies - 1` for every position
+  fn single_value_dtz(plies: u8) -> Vec<u8> {
+    let mut bytes = DTZ_MAGIC.to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00]);
+    bytes.extend_from_slice(&KQVK_PIECES);
+    bytes.push(0x00);
+    bytes.extend_from_slice(&[0x84, plies - 1]);
+    bytes
+  }
+
+  // White always wins KQvK, which holds unless the queen can be taken
+  fn kqvk_tablebase(name: &str) -> (PathBuf, Tablebase) {
+    let dir = table_dir(name);
+    fs::write(dir.join("KQvK.rtbw"), single_value_wdl(4, 0)).unwrap();
+    fs::write(dir.join("KQvK.rtbz"), single_value_dtz(9)).unwrap();
+    let tablebase = Tablebase::open(dir.to_str().unwrap()).unwrap();
+    (dir, tablebase)
+  }
+
+  fn board(fen: &str) -> Chessboard {
+    Game::from_fen(fen).unwrap().chessboard().clone()
+  }
+
+  // Tables in the Syzygy format, from SYZYGY_PATH or the 3 piece ones
+  // next to the tests
+  fn real_tablebase() -> Tablebase {
+    let path = std::env::var("SYZYGY_PATH").unwrap_or_else(|_| {
+      concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/syzygy").to_string()
+    });
+    match Tablebase::open(&path) {
+      Ok(tablebase) if !tablebase.is_empty() => tablebase,
+      Ok(_) => panic!("No Syzygy tables in {}", path),
+      Err(e) => panic!("Failed to open Syzygy tables in {}: {}", path, e),
+    }
+  }
+
+  #[test]
+  fn test_open_finds_tables() {
+    let dir = table_dir("open");
+    fs::write(dir.join("KQvK.rtbw"), single_value_wdl(4, 0)).unwrap();
+    fs::write(dir.join("KXvK.rtbw"), single_value_wdl(4, 0)).unwrap();
+    fs::write(dir.join("KQvK.txt"), "").unwrap();
+
+    let tablebase = Tablebase::open(dir.to_str().unwrap()).unwrap();
+    assert_eq!(tablebase.len(), 1);
+    assert_eq!(tablebase.max_pieces(), 3);
+    assert!(tablebase.covers(&board("k7/8/8/8/8/8/8/KQ6 w - - 0 1")));
+    assert!(!tablebase.covers(&board("k7/8/8/8/8/8/8/KQR5 w - - 0 1")));
+
+    assert!(Tablebase::open("/nonexistent/syzygy").is_err());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_probe_wdl() {
+    let (dir, tablebase) = kqvk_tablebase("wdl");
+
+    let white_to_move = board("k7/8/8/8/8/8/8/KQ6 w - - 0 1");
+    assert_eq!(
+      tablebase.probe_wdl(&white_to_move, Color::White),
+      Ok(Wdl::Win)
+    );
+    assert_eq!(
+      tablebase.probe_wdl(&white_to_move, Color::Black),
+      Ok(Wdl::Loss)
+    );
+    // The queen can be taken
+    let hanging_queen = board("kQ6/8/8/8/8/8/8/K7 b - - 0 1");
+    assert_eq!(
+      tablebase.probe_wdl(&hanging_queen, Color::Black),
+      Ok(Wdl::Draw)
+    );
+    // Black's queen uses the same table
+    let black_queen = board("K7/8/8/8/8/8/8/kq6 b - - 0 1");
+    assert_eq!(
+      tablebase.probe_wdl(&black_queen, Color::Black),
+      Ok(Wdl::Win)
+    );
+    assert_eq!(
+      tablebase.probe_wdl(&board("k7/8/8/8/8/8/8/K7 w - - 0 1"), Color::White),
+      Ok(Wdl::Draw)
+    );
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_probe_errors() {
+    let (dir, tablebase) = kqvk_tablebase("errors");
+
+    let rook = board("k7/8/8/8/8/8/8/KR6 w - - 0 1");
+    assert_eq!(
+      tablebase.probe_wdl(&rook, Color::White),
+      Err("Missing table: KRvK".to_string())
+    );
+    let too_many = board("k7/8/8/8/8/8/8/KQQ5 w - - 0 1");
+    assert!(tablebase.probe_wdl(&too_many, Color::White).is_err());
+
+    let corrupted = table_dir("corrupted");
+    fs::write(corrupted.join("KQvK.rtbw"), [0; 16]).unwrap();
+    let tablebase = Tablebase::open(corrupted.to_str().unwrap()).unwrap();
+    let result =
+      tablebase.probe_wdl(&board("k7/8/8/8/8/8/8/KQ6 w - - 0 1"), Color::White);
+    assert!(result.unwrap_err().starts_with("Corrupted table"));
+
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_dir_all(corrupted).unwrap();
+  }
+
+  #[test]
+  fn test_rank_moves() {
+    let (dir, tablebase) = kqvk_tablebase("rank");
+    let chessboard = board("k7/8/2K5/8/8/8/8/1Q6 w - - 0 1");
+
+    let root_moves = tablebase.rank_moves(&chessboard, Color::White).unwrap();
+    let best = root_moves[0];
+    assert_eq!(best.chess_move.to_long_algebraic(), "b1b7");
+    assert_eq!((best.wdl, best.dtz), (Wdl::Win, 1));
+    // Giving the queen away draws
+    let last = root_moves.last().unwrap();
+    assert_eq!(last.chess_move.to_long_algebraic(), "b1b8");
+    assert_eq!((last.wdl, last.dtz), (Wdl::Draw, 0));
+    // Other moves take the table's count plus the reply
+    assert!(
+      root_moves
+        .iter()
+        .any(|root_move| root_move.wdl == Wdl::Win && root_move.dtz == 11)
+    );
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_engine_plays_tablebase_moves() {
+    let (dir, tablebase) = kqvk_tablebase("engine");
+    let game = Game::from_fen("k7/8/2K5/8/8/8/8/1Q6 w - - 0 1").unwrap();
+    let mut engine = Engine::new();
+    engine.set_tablebase(Some(tablebase));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let result =
+      engine.search(&game, SearchLimits::depth(4), stop, &mut |_| {});
+    assert_eq!(result.best_move.unwrap().to_long_algebraic(), "b1b7");
+    assert_eq!(result.score, Score::Centipawns(TABLEBASE_WIN - 1));
+    assert_eq!(result.nodes, 0);
+
+    assert!(
+      engine
+        .set_option("SyzygyPath", "/nonexistent/syzygy")
+        .is_err()
+    );
+    engine.set_option("SyzygyPath", "<empty>").unwrap();
+    assert!(engine.tablebase().is_none());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_uci_syzygy_path() {
+    let (dir, _) = kqvk_tablebase("uci");
+    let commands = format!(
+      "uci\nsetoption name SyzygyPath value {}\n\
+       position fen k7/8/2K5/8/8/8/8/1Q6 w - - 0 1\ngo depth 3\n",
+      dir.display()
+    );
+    let output = SharedOutput::default();
+    Uci::new(output.clone()).run(Cursor::new(commands));
+    let lines = output.lines();
+
+    assert!(lines.iter().any(|line| line.contains("name SyzygyPath")));
+    assert_eq!(lines.last().unwrap(), "bestmove b1b7");
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_real_tables() {
+    let tablebase = real_tablebase();
+    let probe = |fen: &str| {
+      let game = Game::from_fen(fen).unwrap();
+      tablebase.probe_wdl(game.chessboard(), game.player_color())
+    };
+
+    assert_eq!(probe("k7/8/8/8/8/8/8/KQ6 w - - 0 1"), Ok(Wdl::Win));
+    assert_eq!(probe("k7/8/8/8/8/8/8/KQ6 b - - 0 1"), Ok(Wdl::Loss));
+    assert_eq!(probe("7K/8/8/8/8/8/8/kr6 b - - 0 1"), Ok(Wdl::Win));
+    assert_eq!(probe("k7/8/8/8/8/8/8/KB6 w - - 0 1"), Ok(Wdl::Draw));
+    assert_eq!(probe("k7/8/8/8/8/8/8/KN6 b - - 0 1"), Ok(Wdl::Draw));
+    assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"), Ok(Wdl::Win));
+    assert_eq!(probe("k7/8/K7/P7/8/8/8/8 w - - 0 1"), Ok(Wdl::Draw));
+    // Stalemate in front of the pawn
+    assert_eq!(probe("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1"), Ok(Wdl::Draw));
+
+    let dtz = |fen: &str| {
+      let game = Game::from_fen(fen).unwrap();
+      tablebase.probe_dtz(game.chessboard(), game.player_color())
+    };
+    // Mate in one, in seven and in fourteen
+    assert_eq!(dtz("k7/8/1K6/8/8/8/8/7R w - - 0 1"), Ok(1));
+    assert_eq!(dtz("k7/8/8/8/8/8/8/KR6 w - - 0 1"), Ok(13));
+    assert_eq!(dtz("k7/8/8/8/8/8/8/KR6 b - - 0 1"), Ok(-16));
+    assert_eq!(dtz("8/8/8/3k4/8/8/8/R3K3 w - - 0 1"), Ok(27));
+    // The pawn moves next
+    assert_eq!(dtz("7k/8/8/8/8/8/P7/K7 b - - 0 1"), Ok(-2));
+
+    let chessboard = board("k7/8/2K5/8/8/8/8/1Q6 w - - 0 1");
+    let best = tablebase.best_move(&chessboard, Color::White).unwrap();
+    let best = best.unwrap();
+    assert_eq!(best.chess_move.to_long_algebraic(), "b1b7");
+    assert_eq!((best.wdl, best.dtz), (Wdl::Win, 1));
+
+    let chessboard = board("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1");
+    let best = tablebase.best_move(&chessboard, Color::White).unwrap();
+    let best = best.unwrap();
+    assert_eq!(best.chess_move.to_long_algebraic(), "e6d6");
+    assert_eq!((best.wdl, best.dtz), (Wdl::Win, 3));
+  }
+}
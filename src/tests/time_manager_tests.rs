@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+  use crate::engine::Engine;
+  use crate::engine::search::{SearchLimits, SearchResult};
+  use crate::engine::time_manager::TimeManager;
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+  use std::time::{Duration, Instant};
+
+  fn search(game: &Game, limits: SearchLimits) -> SearchResult {
+    let stop = Arc::new(AtomicBool::new(false));
+    Engine::new().search(game, limits, stop, &mut |_| {})
+  }
+
+  fn clock(time_left_ms: u64, increment_ms: u64) -> SearchLimits {
+    SearchLimits {
+      time_left: Some(Duration::from_millis(time_left_ms)),
+      increment: Duration::from_millis(increment_ms),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_clock_budgets() {
+    let time_manager = TimeManager::new(&clock(60_000, 0));
+
+    // The time left, minus the move overhead, shared among 30 moves
+    let soft_budget = Duration::from_millis(59_950) / 30;
+    assert!(time_manager.is_adaptive());
+    assert_eq!(time_manager.soft_budget(), Some(soft_budget));
+    assert_eq!(time_manager.hard_budget(), Some(soft_budget * 4));
+
+    // Three quarters of the increment come on top
+    let time_manager = TimeManager::new(&clock(60_000, 2_000));
+    assert_eq!(
+      time_manager.soft_budget(),
+      Some(soft_budget + Duration::from_millis(1_500))
+    );
+  }
+
+  #[test]
+  fn test_budgets_keep_time_on_the_clock() {
+    let limits = SearchLimits {
+      moves_to_go: Some(1),
+      ..clock(10_050, 0)
+    };
+    let time_manager = TimeManager::new(&limits);
+
+    assert_eq!(time_manager.soft_budget(), Some(Duration::from_secs(5)));
+    assert_eq!(
+      time_manager.hard_budget(),
+      Some(Duration::from_millis(7_500))
+    );
+  }
+
+  #[test]
+  fn test_scaled_budget_stays_under_the_hard_budget() {
+    let time_manager = TimeManager::new(&clock(60_000, 0));
+    let soft_budget = time_manager.soft_budget().unwrap();
+
+    assert_eq!(
+      time_manager.scaled_budget(1.5),
+      Some(soft_budget.mul_f64(1.5))
+    );
+    assert_eq!(time_manager.scaled_budget(10.0), time_manager.hard_budget());
+  }
+
+  #[test]
+  fn test_movetime_is_exact() {
+    let movetime = Duration::from_millis(300);
+    let limits = SearchLimits {
+      movetime: Some(movetime),
+      ..Default::default()
+    };
+    let time_manager = TimeManager::new(&limits);
+    assert!(!time_manager.is_adaptive());
+    assert_eq!(time_manager.soft_budget(), Some(movetime));
+    assert_eq!(time_manager.hard_budget(), Some(movetime));
+
+    let start = Instant::now();
+    search(&Game::new(Color::White), limits);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= movetime);
+    assert!(elapsed < movetime + Duration::from_millis(500));
+  }
+
+  #[test]
+  fn test_nodes_limit_is_exact() {
+    let limits = SearchLimits {
+      nodes: Some(1_234),
+      ..Default::default()
+    };
+    let result = search(&Game::new(Color::White), limits);
+
+    assert_eq!(result.nodes, 1_234);
+    assert!(result.best_move.is_some());
+  }
+
+  #[test]
+  fn test_only_move_is_played_right_away() {
+    // Taking the rook on b2 is the only way out of check
+    let game = Game::from_fen("k7/8/8/8/8/8/1r6/K1r5 w - - 0 1").unwrap();
+    assert_eq!(game.legal_moves().len(), 1);
+
+    let result = search(&game, clock(60_000, 0));
+    assert_eq!(result.depth, 1);
+    assert_eq!(result.best_move.unwrap().to_long_algebraic(), "a1b2");
+  }
+
+  #[test]
+  fn test_clock_search_stays_within_the_hard_budget() {
+    let limits = clock(2_000, 0);
+    let hard_budget = TimeManager::new(&limits).hard_budget().unwrap();
+
+    let start = Instant::now();
+    let result = search(&Game::new(Color::White), limits);
+    assert!(start.elapsed() < hard_budget + Duration::from_millis(200));
+    assert!(result.best_move.is_some());
+  }
+}
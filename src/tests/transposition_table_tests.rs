@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+  use crate::chess_move::Move;
+  use crate::engine::Engine;
+  use crate::engine::search::{MATE_SCORE, SearchLimits};
+  use crate::engine::transposition_table::{Bound, TranspositionTable};
+  use crate::game::Game;
+  use crate::pieces::types::color::Color;
+  use crate::tests::fixtures::square;
+  use std::sync::Arc;
+  use std::sync::atomic::AtomicBool;
+
+  #[test]
+  fn test_store_and_probe() {
+    let table = TranspositionTable::new(1);
+    let best_move = Move::new(square("e2"), square("e4"));
+
+    table.store(42, 3, Bound::Exact, 15, Some(best_move), 0);
+    let entry = table.probe(42).unwrap();
+
+    assert_eq!(entry.depth(), 3);
+    assert_eq!(entry.bound(), Bound::Exact);
+    assert_eq!(entry.score(0), 15);
+    assert_eq!(entry.best_move(), Some(best_move));
+    assert!(table.probe(43).is_none());
+  }
+
+  #[test]
+  fn test_deeper_entries_are_kept() {
+    let table = TranspositionTable::new(1);
+
+    table.store(42, 5, Bound::Exact, 10, None, 0);
+    table.store(42, 2, Bound::Lower, 20, None, 0);
+    assert_eq!(table.probe(42).unwrap().depth(), 5);
+
+    table.store(42, 6, Bound::Upper, 30, None, 0);
+    assert_eq!(table.probe(42).unwrap().depth(), 6);
+
+    // Leftovers from a previous search can always be replaced
+    table.new_search();
+    table.store(42, 1, Bound::Exact, 40, None, 0);
+    assert_eq!(table.probe(42).unwrap().depth(), 1);
+  }
+
+  #[test]
+  fn test_mate_scores_are_relative_to_ply() {
+    let table = TranspositionTable::new(1);
+
+    // Mate found 3 plies below the root, from a position at ply 1
+    table.store(42, 4, Bound::Exact, MATE_SCORE - 3, None, 1);
+
+    // Reached again at ply 5, the mate is 4 plies further away
+    assert_eq!(table.probe(42).unwrap().score(5), MATE_SCORE - 7);
+  }
+
+  #[test]
+  fn test_size_follows_megabytes() {
+    let small = TranspositionTable::new(1);
+    let large = TranspositionTable::new(4);
+
+    assert_eq!(large.len(), small.len() * 4);
+  }
+
+  #[test]
+  fn test_hash_option() {
+    let mut engine = Engine::new();
+    engine.set_option("Hash", "2").unwrap();
+    let size = engine.transposition_table().len();
+
+    engine.set_option("Hash", "4").unwrap();
+    assert_eq!(engine.transposition_table().len(), size * 2);
+    assert!(engine.set_option("Hash", "0").is_err());
+    assert!(engine.set_option("Hash", "lots").is_err());
+  }
+
+  #[test]
+  fn test_hash_depends_on_position_only() {
+    let mut first = Game::new(Color::White);
+    first.play(square("g1"), square("f3")).unwrap();
+    first.play(square("g8"), square("f6")).unwrap();
+    first.play(square("b1"), square("c3")).unwrap();
+
+    let mut second = Game::new(Color::White);
+    second.play(square("b1"), square("c3")).unwrap();
+    second.play(square("g8"), square("f6")).unwrap();
+    second.play(square("g1"), square("f3")).unwrap();
+
+    let hash = |game: &Game| game.chessboard().hash(game.player_color());
+    assert_eq!(hash(&first), hash(&second));
+    assert_ne!(
+      first.chessboard().hash(Color::White),
+      first.chessboard().hash(Color::Black)
+    );
+  }
+
+  #[test]
+  fn test_table_is_reused_between_searches() {
+    let game = Game::new(Color::White);
+    let mut engine = Engine::new();
+    let mut search = || {
+      let stop = Arc::new(AtomicBool::new(false));
+      engine.search(&game, SearchLimits::depth(3), stop, &mut |_| {})
+    };
+
+    let first = search();
+    let second = search();
+
+    assert!(second.nodes < first.nodes);
+    assert_eq!(first.best_move, second.best_move);
+  }
+}
@@ -0,0 +1,293 @@
+#[cfg(test)]
+mod tests {
+  use crate::game::Game;
+  use crate::pieces::piece::Piece;
+  use crate::pieces::types::color::Color;
+  use crate::presenters::style::{Glyphs, RenderOptions};
+  use crate::tests::fixtures::square;
+  use crate::ui::tui::terminal::{Key, parse_keys};
+  use crate::ui::tui::view::frame;
+  use crate::ui::tui::{Action, State};
+
+  fn colored() -> RenderOptions {
+    RenderOptions {
+      color: true,
+      ..RenderOptions::default()
+    }
+  }
+
+  fn press(state: &mut State, game: &mut Game, keys: &[Key]) -> Action {
+    keys
+      .iter()
+      .map(|key| state.handle_key(game, *key))
+      .last()
+      .unwrap_or(Action::None)
+  }
+
+  #[test]
+  fn test_parse_keys() {
+    assert_eq!(
+      parse_keys(b"\x1b[A\x1b[B\x1bOC\x1b[D"),
+      vec![Key::Up, Key::Down, Key::Right, Key::Left]
+    );
+    assert_eq!(
+      parse_keys(b"\r \x1bq"),
+      vec![Key::Enter, Key::Enter, Key::Escape, Key::Char('q')]
+    );
+    assert_eq!(parse_keys(b"\x1b[Zu\x01"), vec![Key::Char('u')]);
+  }
+
+  #[test]
+  fn test_cursor_stays_on_the_board() {
+    let mut game = Game::new(Color::White);
+    let mut state = State::default();
+    assert_eq!(state.cursor, square("e2"));
+
+    press(&mut state, &mut game, &[Key::Down, Key::Down, Key::Left]);
+    assert_eq!(state.cursor, square("d1"));
+    press(&mut state, &mut game, &[Key::Right; 9]);
+    assert_eq!(state.cursor, square("h1"));
+    press(&mut state, &mut game, &[Key::Up; 9]);
+    assert_eq!(state.cursor, square("h8"));
+  }
+
+  #[test]
+  fn test_select_and_play() {
+    let mut game = Game::new(Color::White);
+    let mut state = State::default();
+
+    press(&mut state, &mut game, &[Key::Enter]);
+    assert_eq!(state.selected, Some(square("e2")));
+    assert_eq!(state.targets(&game), vec![square("e3"), square("e4")]);
+
+    press(&mut state, &mut game, &[Key::Up, Key::Up, Key::Enter]);
+    assert_eq!(state.selected, None);
+    assert_eq!(game.san_moves().unwrap(), vec!["e4"]);
+    assert_eq!(game.player_color(), Color::Black);
+
+    // White's pieces can't be picked on Black's turn
+    press(&mut state, &mut game, &[Key::Enter]);
+    assert_eq!(state.selected, None);
+    assert_eq!(state.message, "No Black piece there");
+
+    press(&mut state, &mut game, &[Key::Char('u')]);
+    assert!(game.moves().is_empty());
+    assert_eq!(state.message, "");
+    press(&mut state, &mut game, &[Key::Char('u')]);
+    assert_eq!(state.message, "No move to undo");
+  }
+
+  #[test]
+  fn test_illegal_destination_cancels_the_selection() {
+    let mut game = Game::new(Color::White);
+    let mut state = State::default();
+
+    press(
+      &mut state,
+      &mut game,
+      &[Key::Enter, Key::Up, Key::Up, Key::Up],
+    );
+    press(&mut state, &mut game, &[Key::Enter]);
+    assert_eq!(state.selected, None);
+    assert_eq!(state.message, "Not a legal move");
+    assert!(game.moves().is_empty());
+
+    press(&mut state, &mut game, &[Key::Down, Key::Down, Key::Down]);
+    press(&mut state, &mut game, &[Key::Enter, Key::Escape]);
+    assert_eq!(state.selected, None);
+    assert_eq!(
+      press(&mut state, &mut game, &[Key::Char('q')]),
+      Action::Quit
+    );
+  }
+
+  #[test]
+  fn test_upgrade_with_letter() {
+    let mut game = Game::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let mut state = State {
+      cursor: square("e7"),
+      ..State::default()
+    };
+
+    let action =
+      press(&mut state, &mut game, &[Key::Enter, Key::Up, Key::Enter]);
+    assert_eq!(action, Action::Upgrade(square("e8")));
+    assert_eq!(state.upgrade, Some(square("e8")));
+    assert!(
+      frame(&game, &state, &colored())
+        .iter()
+        .any(|line| line.ends_with("Upgrade the pawn on e8: P N B R Q"))
+    );
+
+    // Nothing else works until a piece is picked
+    press(&mut state, &mut game, &[Key::Left, Key::Char('x')]);
+    assert_eq!(state.cursor, square("e8"));
+    assert!(state.upgrade.is_some());
+    assert_eq!(state.message, "No dead piece for x");
+    assert!(
+      frame(&game, &state, &colored())
+        .last()
+        .unwrap()
+        .starts_with("Letter")
+    );
+    // `q` still quits rather than picking the queen
+    assert_eq!(
+      press(&mut state, &mut game, &[Key::Char('q')]),
+      Action::Quit
+    );
+    assert!(state.upgrade.is_some());
+    press(&mut state, &mut game, &[Key::Enter]);
+    assert_eq!(state.upgrade, None);
+    assert!(matches!(
+      game.chessboard().get_piece(square("e8")),
+      Some(Piece::Queen(_))
+    ));
+    assert_eq!(game.san_moves().unwrap(), vec!["e8=Q+"]);
+  }
+
+  #[test]
+  fn test_upgrade_taken_back_with_escape() {
+    let mut game = Game::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let mut state = State {
+      cursor: square("e7"),
+      ..State::default()
+    };
+
+    press(&mut state, &mut game, &[Key::Enter, Key::Up, Key::Enter]);
+    press(&mut state, &mut game, &[Key::Escape]);
+    assert_eq!(state.upgrade, None);
+    assert!(game.moves().is_empty());
+    assert!(matches!(
+      game.chessboard().get_piece(square("e7")),
+      Some(Piece::Pawn(_))
+    ));
+  }
+
+  #[test]
+  fn test_upgrade_without_dead_pieces() {
+    let mut game =
+      Game::from_fen("rnbqkbnr/pPpppppp/8/8/8/8/P1PPPPPP/RNBQKBNR w").unwrap();
+    let mut state = State {
+      cursor: square("b7"),
+      ..State::default()
+    };
+
+    // Nothing to bring back, so the pawn stays a pawn and play goes on
+    let action = press(
+      &mut state,
+      &mut game,
+      &[Key::Enter, Key::Up, Key::Left, Key::Enter],
+    );
+    assert_eq!(action, Action::None);
+    assert_eq!(state.upgrade, None);
+    assert!(matches!(
+      game.chessboard().get_piece(square("a8")),
+      Some(Piece::Pawn(_))
+    ));
+    assert_eq!(game.player_color(), Color::Black);
+    assert_eq!(
+      press(&mut state, &mut game, &[Key::Char('q')]),
+      Action::Quit
+    );
+  }
+
+  #[test]
+  fn test_frame() {
+    let mut game = Game::new(Color::White);
+    for san in ["e4", "d5", "exd5"] {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+    let state = State::default();
+    let lines = frame(&game, &state, &colored());
+
+    // Black to move has its side at the bottom
+    assert!(lines[0].trim_start().starts_with("h"));
+    assert!(lines[1].starts_with("1 "));
+    assert!(lines[8].starts_with("8 "));
+    // The cursor is drawn in brackets around the square's piece
+    assert!(lines[2].contains("[ ]"));
+    assert!(lines[0].ends_with("Black to move"));
+    assert!(lines.iter().any(|line| line.ends_with("1. e4      d5")));
+    assert!(lines.iter().any(|line| line.ends_with("2. exd5")));
+    assert!(
+      lines
+        .iter()
+        .any(|line| line.ends_with("Captured by White: p"))
+    );
+    assert!(
+      lines
+        .iter()
+        .any(|line| line.ends_with("Captured by Black: "))
+    );
+    assert!(lines.last().unwrap().starts_with("Arrows"));
+  }
+
+  #[test]
+  fn test_frame_without_colors() {
+    let mut game = Game::new(Color::White);
+    let mut state = State::default();
+    press(&mut state, &mut game, &[Key::Enter]);
+    let options = RenderOptions {
+      glyphs: Glyphs::Unicode,
+      ..RenderOptions::default()
+    };
+    let lines = frame(&game, &state, &options);
+
+    assert!(lines.iter().all(|line| !line.contains('\x1b')));
+    assert_eq!(
+      lines[1].trim_end(),
+      "8  \u{265C}  \u{265E}  \u{265D}  \u{265B}  \u{265A}  \u{265D}  \
+       \u{265E}  \u{265C}  8"
+    );
+    // The selected pawn under the cursor, and where it can go
+    assert!(lines[5].starts_with("4  .  .  .  . (.) .  .  .  4"));
+    assert!(lines[6].starts_with("3  .  .  .  . (.) .  .  .  3"));
+    assert!(lines[7].contains(" [\u{2659}] "));
+  }
+
+  #[test]
+  fn test_undo_against_engine() {
+    let mut game = Game::new(Color::White);
+    let mut state = State {
+      opponent: Some(Color::Black),
+      ..State::default()
+    };
+    for san in ["e4", "e5", "Nf3"] {
+      game.play_move(game.parse_san(san).unwrap()).unwrap();
+    }
+
+    // The engine's reply goes with the player's move
+    press(&mut state, &mut game, &[Key::Char('u')]);
+    assert_eq!(game.san_moves().unwrap(), vec!["e4", "e5"]);
+    press(&mut state, &mut game, &[Key::Char('u')]);
+    assert!(game.moves().is_empty());
+    assert_eq!(game.player_color(), Color::White);
+  }
+
+  #[test]
+  fn test_orientation_and_flip() {
+    let mut game = Game::new(Color::White);
+    let mut state = State::default();
+    game.play_move(game.parse_san("e4").unwrap()).unwrap();
+
+    // Seen from Black, up goes toward White's side
+    assert_eq!(state.orientation(&game), Color::Black);
+    press(&mut state, &mut game, &[Key::Up, Key::Right]);
+    assert_eq!(state.cursor, square("d1"));
+
+    press(&mut state, &mut game, &[Key::Char('f')]);
+    assert_eq!(state.orientation(&game), Color::White);
+    let lines = frame(&game, &state, &colored());
+    assert!(lines[1].starts_with("8 "));
+    // Flipped stays put when the turn changes
+    game.play_move(game.parse_san("e5").unwrap()).unwrap();
+    assert_eq!(state.orientation(&game), Color::White);
+
+    // Against an engine, the player's side is at the bottom
+    let state = State {
+      opponent: Some(Color::White),
+      ..State::default()
+    };
+    assert_eq!(state.orientation(&game), Color::Black);
+  }
+}
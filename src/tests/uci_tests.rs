@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+  use crate::protocols::uci::Uci;
+  use crate::tests::shared_output::SharedOutput;
+  use std::io::Cursor;
+
+  fn run(commands: &str) -> Vec<String> {
+    let output = SharedOutput::default();
+    Uci::new(output.clone()).run(Cursor::new(commands.to_string()));
+    output.lines()
+  }
+
+  #[test]
+  fn test_handshake() {
+    let lines = run("uci\nisready\n");
+
+    assert!(lines[0].starts_with("id name"));
+    assert!(lines.contains(&"uciok".to_string()));
+    assert_eq!(lines.last().unwrap(), "readyok");
+  }
+
+  #[test]
+  fn test_go_depth_reports_info_and_best_move() {
+    let lines =
+      run("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 3\n");
+
+    assert!(lines.iter().any(|line| line.starts_with("info depth 1 ")));
+    assert!(lines.iter().any(|line| line.contains("score mate 1")));
+    assert_eq!(lines.last().unwrap(), "bestmove a1a8");
+  }
+
+  #[test]
+  fn test_position_with_moves() {
+    // After 1. e4 f6 2. d4 g5 white mates with Qh5
+    let lines =
+      run("position startpos moves e2e4 f7f6 d2d4 g7g5\ngo depth 2\n");
+
+    assert_eq!(lines.last().unwrap(), "bestmove d1h5");
+  }
+
+  #[test]
+  fn test_stop_ends_infinite_search() {
+    let lines = run("position startpos\ngo infinite\nstop\n");
+
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+  }
+
+  #[test]
+  fn test_closed_input_ends_infinite_search() {
+    for go in ["go infinite", "go ponder", "go"] {
+      let lines = run(&format!("position startpos\n{}\n", go));
+
+      assert!(lines.last().unwrap().starts_with("bestmove "));
+    }
+  }
+
+  #[test]
+  fn test_ponder_searches_until_ponderhit() {
+    let lines = run(
+      "position startpos moves e2e4\ngo ponder wtime 1000 btime 1000\n\
+       ponderhit\n",
+    );
+
+    assert!(lines.iter().all(|line| !line.starts_with("info string")));
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+  }
+
+  #[test]
+  fn test_search_moves_are_skipped() {
+    let lines = run("position startpos\ngo searchmoves e2e4 d2d4 depth 1\n");
+
+    assert!(lines.iter().all(|line| !line.starts_with("info string")));
+    assert!(lines.iter().any(|line| line.starts_with("info depth 1 ")));
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+  }
+
+  #[test]
+  fn test_invalid_input_is_reported() {
+    let lines = run("position startpos moves e2e5\nfoo\nsetoption name Foo\n");
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines.iter().all(|line| line.starts_with("info string")));
+  }
+
+  #[test]
+  fn test_multi_pv_option() {
+    let lines =
+      run("setoption name MultiPV value 2\nposition startpos\ngo depth 2\n");
+
+    assert!(
+      lines
+        .iter()
+        .any(|line| line.starts_with("info depth 2 multipv 2 "))
+    );
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+  }
+}
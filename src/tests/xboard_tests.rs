@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+  use crate::protocols::xboard::XBoard;
+  use crate::tests::shared_output::SharedOutput;
+  use std::io::Cursor;
+
+  fn run(commands: &str) -> Vec<String> {
+    let output = SharedOutput::default();
+    XBoard::new(output.clone()).run(Cursor::new(commands.to_string()));
+    output.lines()
+  }
+
+  #[test]
+  fn test_protover_lists_features() {
+    let lines = run("xboard\nprotover 2\n");
+
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].contains("usermove=1"));
+    assert!(lines[0].ends_with("done=1"));
+  }
+
+  #[test]
+  fn test_engine_answers_user_move() {
+    let lines = run("new\nsd 2\nusermove e2e4\n");
+
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("move "));
+  }
+
+  #[test]
+  fn test_force_mode_does_not_think() {
+    let lines = run("new\nforce\nusermove e2e4\nusermove e7e5\n");
+
+    assert!(lines.is_empty());
+  }
+
+  #[test]
+  fn test_go_plays_for_side_to_move_and_reports_mate() {
+    let lines =
+      run("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\nsd 3\ngo\n");
+
+    assert_eq!(lines, vec!["move a1a8", "1-0 {White mates}"]);
+  }
+
+  #[test]
+  fn test_undo_takes_back_moves() {
+    let lines =
+      run("new\nforce\nusermove e2e4\nundo\nusermove e7e5\nundo\nping 7\n");
+
+    // Black can't move first, and there's nothing left to undo at the end
+    assert_eq!(
+      lines,
+      vec![
+        "Illegal move: e7e5",
+        "Error (no move to undo): undo",
+        "pong 7"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_level_and_time_limit_thinking() {
+    let lines =
+      run("new\nlevel 40 0:10 0\ntime 100\notim 100\nusermove d2d4\n");
+
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("move "));
+  }
+
+  #[test]
+  fn test_invalid_commands() {
+    let lines = run("level 40\nusermove e9e4\nfoo\n");
+
+    assert_eq!(
+      lines,
+      vec![
+        "Error (invalid level): 40",
+        "Illegal move: e9e4",
+        "Error (unknown command): foo"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_egtpath() {
+    let lines = run("egtpath gaviota /tmp\negtpath syzygy /nonexistent\n");
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+      lines[0],
+      "Error (unsupported tablebases): egtpath gaviota /tmp"
+    );
+    assert!(lines[1].starts_with("Error (Failed to read /nonexistent"));
+  }
+}
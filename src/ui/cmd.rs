@@ -1,32 +1,510 @@
+use crate::bitbase::Bitbases;
+use crate::book::OpeningBook;
 use crate::chessboard::MoveResult;
-use crate::game::Game;
+use crate::engine::hint::{Hint, quick_move};
+use crate::engine::search::{Score, SearchInfo, SearchLimits};
+use crate::engine::{Engine, MAX_MULTI_PV};
+use crate::external_engine::ExternalEngine;
+use crate::game::{Game, GameResult};
+use crate::pieces::types::color::Color;
 use crate::pieces::types::position::Position;
 use crate::presenters::Presenter;
+use crate::presenters::style::RenderOptions;
+use crate::presenters::svg::SvgOptions;
+use crate::tablebase::{RootMove, Tablebase};
 use crate::ui::GameUI;
+use crate::ui::replay::Replay;
+use std::fs;
 use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
 
-pub struct CmdUI;
+const EXTERNAL_ENGINE_MOVE_TIME: Duration = Duration::from_secs(1);
+const DEFAULT_ANALYSIS_LINES: usize = 3;
+const HINT_TIME: Duration = Duration::from_secs(1);
+// Time each position of an exported game stays on screen
+const GIF_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct CmdUI {
+  // Plays the other side's moves and answers `analyse` when set
+  external_engine: Option<ExternalEngine>,
+  // Answers `analyse` and `hint` otherwise, and the `tablebase` command
+  // with its tablebases when set
+  engine: Engine,
+  // Answers the `book` command when set
+  book: Option<OpeningBook>,
+  // How boards are printed
+  render_options: RenderOptions,
+  // Side at the bottom once the board has been flipped, otherwise the one
+  // to move in hot-seat games and White against an engine
+  orientation: Option<Color>,
+}
+
+impl CmdUI {
+  pub fn new() -> Self {
+    CmdUI {
+      external_engine: None,
+      engine: Engine::new(),
+      book: None,
+      render_options: RenderOptions::default(),
+      orientation: None,
+    }
+  }
+
+  pub fn with_external_engine(external_engine: ExternalEngine) -> Self {
+    CmdUI {
+      external_engine: Some(external_engine),
+      engine: Engine::new(),
+      book: None,
+      render_options: RenderOptions::default(),
+      orientation: None,
+    }
+  }
+
+  pub fn set_book(&mut self, book: OpeningBook) {
+    self.book = Some(book);
+  }
+
+  // Used by the `tablebase` command, and by analysis and hints
+  pub fn set_tablebase(&mut self, tablebase: Tablebase) {
+    self.engine.set_tablebase(Some(tablebase));
+  }
+
+  // Used by analysis and hints to play the endgames they cover
+  pub fn set_bitbases(&mut self, bitbases: Bitbases) {
+    self.engine.set_bitbases(Some(bitbases));
+  }
+
+  pub fn set_render_options(&mut self, render_options: RenderOptions) {
+    self.render_options = render_options;
+  }
+
+  fn orientation(&self, game: &Game) -> Color {
+    self.orientation.unwrap_or(match self.external_engine {
+      Some(_) => Color::White,
+      None => game.player_color(),
+    })
+  }
+
+  fn show(&self, presenter: &impl Presenter, orientation: Color) {
+    let options = RenderOptions {
+      orientation,
+      ..self.render_options
+    };
+    presenter
+      .render_to(&mut io::stdout(), &options)
+      .expect("Failed to write to stdout");
+  }
+
+  // The board as shown, with the last move highlighted, as an SVG or PNG
+  // diagram, or the whole game as an animated GIF
+  fn save_diagram(
+    &self,
+    game: &Game,
+    format: &str,
+    path: &str,
+  ) -> Result<(), String> {
+    let mut options = SvgOptions {
+      orientation: self.orientation(game),
+      ..SvgOptions::default()
+    };
+    if let Some(chess_move) = game.moves().last() {
+      options.highlights = vec![chess_move.from(), chess_move.to()];
+    }
+    let contents = match format {
+      "svg" => game.chessboard().to_svg(&options).into_bytes(),
+      "png" => game.chessboard().to_png(&options),
+      _ => game.to_gif(&options, GIF_DELAY),
+    };
+    fs::write(path, contents)
+      .map_err(|e| format!("Failed to write {}: {}", path, e))
+  }
+
+  fn show_tablebase_moves(&self, game: &Game) {
+    let Some(tablebase) = self.engine.tablebase() else {
+      println!("No tablebases loaded. Start with --syzygy <dir>.");
+      return;
+    };
+
+    let chessboard = game.chessboard();
+    if !tablebase.covers(chessboard) {
+      println!(
+        "Tablebases only cover positions with up to {} pieces",
+        tablebase.max_pieces()
+      );
+      return;
+    }
+
+    match tablebase.rank_moves(chessboard, game.player_color()) {
+      Ok(root_moves) if root_moves.is_empty() => println!("No legal move"),
+      Ok(root_moves) => {
+        for root_move in root_moves {
+          println!(
+            "{}  {}",
+            root_move.chess_move.to_long_algebraic(),
+            describe_root_move(&root_move)
+          );
+        }
+      }
+      Err(e) => println!("Tablebase error: {}", e),
+    }
+  }
+
+  fn show_book_moves(&self, game: &Game) {
+    let Some(book) = self.book.as_ref() else {
+      println!("No opening book loaded. Start with --book <path>.");
+      return;
+    };
+
+    let moves = book.moves(game);
+    if moves.is_empty() {
+      println!("Position is not in the book");
+      return;
+    }
+
+    let total: u32 = moves.iter().map(|m| m.weight as u32).sum();
+    for book_move in moves {
+      let share = match total {
+        0 => 0.0,
+        total => book_move.weight as f64 * 100.0 / total as f64,
+      };
+      println!(
+        "{}  weight {:5}  ({:.1}%)",
+        book_move.chess_move.to_long_algebraic(),
+        book_move.weight,
+        share
+      );
+    }
+  }
+
+  fn play_external_engine_move(&mut self, game: &mut Game) {
+    let Some(external_engine) = self.external_engine.as_mut() else {
+      return;
+    };
+    if game.result().is_some() {
+      return;
+    }
+
+    let limits = SearchLimits {
+      movetime: Some(EXTERNAL_ENGINE_MOVE_TIME),
+      ..Default::default()
+    };
+    let best_move = external_engine
+      .search(game, &limits, &mut |_| {})
+      .and_then(|result| result.best_move.ok_or("No move found".to_string()));
+
+    match best_move
+      .and_then(|chess_move| game.play_move(chess_move).map(|_| chess_move))
+    {
+      Ok(chess_move) => {
+        println!("Engine plays {}", chess_move.to_long_algebraic())
+      }
+      Err(e) => {
+        // Black still has to move, so the built-in engine plays instead
+        // of handing the turn back to the player
+        println!("Engine error: {}", e);
+        self.play_built_in_engine_move(game);
+      }
+    }
+  }
+
+  fn play_built_in_engine_move(&mut self, game: &mut Game) {
+    let limits = SearchLimits {
+      movetime: Some(EXTERNAL_ENGINE_MOVE_TIME),
+      ..Default::default()
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let result = self.engine.search(game, limits, stop, &mut |_| {});
+    let Some(chess_move) = result.best_move else {
+      println!("No legal move");
+      return;
+    };
+    match game.play_move(chess_move) {
+      Ok(_) => println!(
+        "Built-in engine plays {} instead",
+        chess_move.to_long_algebraic()
+      ),
+      Err(e) => println!("Error: {}", e),
+    }
+  }
+
+  // A move for the player with what it does, from a short search or, when
+  // `quick`, from the evaluation after each legal move
+  fn show_hint(&mut self, game: &Game, quick: bool) {
+    let (chess_move, score) = if quick {
+      (quick_move(game), None)
+    } else {
+      let limits = SearchLimits {
+        movetime: Some(HINT_TIME),
+        ..Default::default()
+      };
+      let stop = Arc::new(AtomicBool::new(false));
+      let result = self.engine.search(game, limits, stop, &mut |_| {});
+      // Book moves aren't searched, so they have no score
+      let score = (result.depth > 0).then_some(result.score);
+      (result.best_move, score)
+    };
+
+    let Some(chess_move) = chess_move else {
+      println!("No legal move");
+      return;
+    };
+    let hint = Hint::new(game, chess_move);
+    match score {
+      Some(score) => {
+        println!(
+          "Hint: {} ({}): {}",
+          hint.san,
+          format_score(score),
+          hint.reason
+        )
+      }
+      None => println!("Hint: {}: {}", hint.san, hint.reason),
+    }
+  }
+
+  // Steps through a game of a PGN file until "quit" is entered
+  fn replay(&self, path: &str, index: usize) {
+    let mut replay = match Replay::open(path, index) {
+      Ok((replay, warning)) => {
+        if let Some(warning) = warning {
+          println!("{}", warning);
+        }
+        replay
+      }
+      Err(e) => {
+        println!("Error: {}", e);
+        return;
+      }
+    };
+
+    loop {
+      println!("Ply {}/{}", replay.ply(), replay.len());
+      self.show(
+        replay.chessboard(),
+        self.orientation.unwrap_or(Color::White),
+      );
+      println!("{}", replay.move_list());
+      println!(
+        "Enter \"next\", \"prev\", \"first\", \"last\", \"goto <ply>\" \
+         or \"quit\": "
+      );
+
+      let mut input = String::new();
+      io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+      let result = match input.split_whitespace().collect::<Vec<_>>()[..] {
+        ["next" | "n"] | [] => replay.forward(),
+        ["prev" | "p"] => replay.back(),
+        ["first"] => replay.goto(0),
+        ["last"] => replay.goto(replay.len()),
+        ["goto", ply] => match ply.parse::<usize>() {
+          Ok(ply) => replay.goto(ply),
+          Err(_) => Err(format!("Invalid ply: {}", ply)),
+        },
+        ["quit" | "q"] => return,
+        _ => Err("Unknown command".to_string()),
+      };
+      if let Err(e) = result {
+        println!("{}", e);
+      }
+    }
+  }
+
+  // Searches until a line is entered, printing the best `lines` lines
+  // each time a depth is done
+  fn analyse_continuously(&mut self, game: &Game, lines: usize) {
+    if let Err(e) = self.engine.set_option("MultiPV", &lines.to_string()) {
+      println!("{}", e);
+      return;
+    }
+    println!("Analysing. Press Enter or type \"stop\" to stop.");
+
+    let engine = &mut self.engine;
+    let stop = Arc::new(AtomicBool::new(false));
+    let limits = SearchLimits {
+      infinite: true,
+      ..Default::default()
+    };
+    thread::scope(|scope| {
+      let search_stop = Arc::clone(&stop);
+      let search = scope.spawn(move || {
+        engine.search(game, limits, search_stop, &mut print_analysis_line)
+      });
+
+      let mut input = String::new();
+      io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+      stop.store(true, Ordering::Relaxed);
+
+      let result = search.join().unwrap();
+      if let Some(chess_move) = result.best_move {
+        println!(
+          "Best move: {} (depth {})",
+          chess_move.to_long_algebraic(),
+          result.depth
+        );
+      }
+    });
+    // Hints only need the best line
+    self.engine.set_option("MultiPV", "1").unwrap();
+  }
+
+  fn analyse(&mut self, game: &Game, lines: usize) {
+    let Some(external_engine) = self.external_engine.as_mut() else {
+      self.analyse_continuously(game, lines);
+      return;
+    };
+    if let Err(e) = external_engine.set_option("MultiPV", &lines.to_string()) {
+      println!("Engine error: {}", e);
+      return;
+    }
+    println!("Analysing. Press Enter or type \"stop\" to stop.");
+
+    let stop_handle = external_engine.stop_handle();
+    let engine = &mut *external_engine;
+    let limits = SearchLimits {
+      infinite: true,
+      ..Default::default()
+    };
+    let result = thread::scope(|scope| {
+      let search = scope
+        .spawn(move || engine.search(game, &limits, &mut print_analysis_line));
+
+      let mut input = String::new();
+      io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+      // Fails only if the engine is gone, which the search reports
+      let _ = stop_handle.stop();
+      search.join().unwrap()
+    });
+
+    match result {
+      Ok(result) => match result.best_move {
+        Some(chess_move) => println!(
+          "Best move: {} (depth {})",
+          chess_move.to_long_algebraic(),
+          result.info.map_or(0, |info| info.depth)
+        ),
+        None => println!("No legal move"),
+      },
+      Err(e) => println!("Engine error: {}", e),
+    }
+    // Its own moves only need the best line
+    if let Err(e) = external_engine.set_option("MultiPV", "1") {
+      println!("Engine error: {}", e);
+    }
+  }
+}
 
 impl GameUI for CmdUI {
   fn start_game_loop(&mut self, game: &mut Game) {
     loop {
-      game.render();
+      self.show(game, self.orientation(game));
+      if let Some(result) = game.result() {
+        println!("{}", describe_result(result));
+        return;
+      }
 
       let mut input = String::new();
-      println!("Enter your move (e.g., e2 e4): ");
+      println!(
+        "Enter your move (e.g., e2 e4), \"hint\", \"analyse [lines]\", \
+         \"book\", \"tablebase\", \"flip\", \"save <file>\", \
+         \"load <file>\", \"svg|png|gif <file>\" or \"replay <file.pgn>\": "
+      );
       io::stdin()
         .read_line(&mut input)
         .expect("Failed to read line");
 
-      let positions: Vec<&str> = input.trim().split_whitespace().collect();
+      let words: Vec<&str> = input.split_whitespace().collect();
+      if let Some((&("analyse" | "analyze"), args)) = words.split_first() {
+        match args {
+          [] => self.analyse(game, DEFAULT_ANALYSIS_LINES),
+          [lines] => match lines.parse::<usize>() {
+            Ok(lines) if (1..=MAX_MULTI_PV).contains(&lines) => {
+              self.analyse(game, lines)
+            }
+            _ => println!("Lines to show must be from 1 to {}", MAX_MULTI_PV),
+          },
+          _ => println!("Usage: analyse [lines]"),
+        }
+        continue;
+      }
+      match words.as_slice() {
+        ["save", path] => {
+          match game.save(path) {
+            Ok(()) => println!("Game saved to {}", path),
+            Err(e) => println!("Error: {}", e),
+          }
+          continue;
+        }
+        [format @ ("svg" | "png" | "gif"), path] => {
+          match self.save_diagram(game, format, path) {
+            Ok(()) => println!("Diagram saved to {}", path),
+            Err(e) => println!("Error: {}", e),
+          }
+          continue;
+        }
+        ["replay", path] => {
+          self.replay(path, 1);
+          continue;
+        }
+        ["replay", path, index] => {
+          match index.parse::<usize>() {
+            Ok(index) => self.replay(path, index),
+            Err(_) => println!("Usage: replay <file.pgn> [game number]"),
+          }
+          continue;
+        }
+        ["load", path] => {
+          match Game::load(path) {
+            Ok(mut loaded) => {
+              // A game saved without a clock goes on with this one's
+              if loaded.clock().is_none() {
+                loaded.set_clock(game.clock().cloned());
+              }
+              *game = loaded;
+              println!("Game loaded from {}", path);
+            }
+            Err(e) => println!("Error: {}", e),
+          }
+          continue;
+        }
+        _ => (),
+      }
+      if matches!(words.as_slice(), ["hint"] | ["hint", "quick"]) {
+        self.show_hint(game, words.len() == 2);
+        continue;
+      }
+      if input.trim() == "flip" {
+        // Stays this way until flipped again
+        self.orientation = Some(self.orientation(game).next());
+        continue;
+      }
+      if input.trim() == "book" {
+        self.show_book_moves(game);
+        continue;
+      }
+      if matches!(input.trim(), "tablebase" | "tb") {
+        self.show_tablebase_moves(game);
+        continue;
+      }
+
+      let positions: Vec<&str> = input.split_whitespace().collect();
 
       if positions.len() != 2 {
         println!("Invalid input. Please enter two positions.");
         continue;
       }
 
-      let start_pos = Position::from_str(positions[0]);
-      let end_pos = Position::from_str(positions[1]);
+      let start_pos = Position::from_algebraic(positions[0]);
+      let end_pos = Position::from_algebraic(positions[1]);
 
       if start_pos.is_err() || end_pos.is_err() {
         println!("Invalid position format. Please try again.");
@@ -53,6 +531,7 @@ impl GameUI for CmdUI {
               self.handle_upgrade_piece(game, end_pos)
             }
           }
+          self.play_external_engine_move(game);
         }
         Err(e) => println!("Error: {}", e),
       }
@@ -91,3 +570,52 @@ impl GameUI for CmdUI {
     }
   }
 }
+
+// In centipawns, or moves to mate, negative when getting mated, like UCI
+// engines report them: "cp +25" or "mate -3"
+fn format_score(score: Score) -> String {
+  match score {
+    Score::Centipawns(cp) => format!("cp {:+}", cp),
+    Score::Mate(moves) => format!("mate {}", moves),
+  }
+}
+
+// Each depth starts with a header, then one line per MultiPV rank
+fn print_analysis_line(info: &SearchInfo) {
+  if info.multipv == 1 {
+    let millis = info.elapsed.as_millis().max(1) as u64;
+    println!(
+      "depth {}  nodes {}  nps {}",
+      info.depth,
+      info.nodes,
+      info.nodes * 1000 / millis
+    );
+  }
+  let score = format_score(info.score);
+  let pv: Vec<String> = info.pv.iter().map(|m| m.to_long_algebraic()).collect();
+  println!("  {:2}. {:>10}  {}", info.multipv, score, pv.join(" "));
+}
+
+fn describe_result(result: GameResult) -> String {
+  match result {
+    GameResult::Checkmate { winner } => format!("Checkmate! {} wins.", winner),
+    GameResult::Stalemate => "Stalemate. The game is a draw.".to_string(),
+    GameResult::Timeout { winner } => {
+      format!("{} ran out of time. {} wins.", winner.next(), winner)
+    }
+    GameResult::TimeoutDraw => "Time is up, but the other side couldn't \
+                                 have mated. The game is a draw."
+      .to_string(),
+  }
+}
+
+// Result after the move for the side playing it. DTZ, the distance to
+// zeroing, counts plies until the next capture or pawn move that keeps the
+// result, which isn't the distance to mate.
+fn describe_root_move(root_move: &RootMove) -> String {
+  match root_move.wdl.signum() {
+    1 => format!("win, DTZ {}", root_move.dtz.abs()),
+    -1 => format!("loss, DTZ {}", root_move.dtz.abs()),
+    _ => "draw".to_string(),
+  }
+}
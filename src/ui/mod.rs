@@ -1,4 +1,6 @@
 pub mod cmd;
+pub mod replay;
+pub mod tui;
 
 use crate::game::Game;
 use crate::pieces::types::position::Position;
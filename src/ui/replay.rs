@@ -0,0 +1,149 @@
+use crate::chessboard::Chessboard;
+use crate::game::Game;
+use crate::notation::pgn::parse_pgn;
+use crate::pieces::types::color::Color;
+use std::fs;
+use std::path::Path;
+
+// Moves listed on each line of the move list
+const MOVES_PER_LINE: usize = 8;
+
+// A finished game to step through, one ply at a time
+pub struct Replay {
+  // Board after each ply, starting with the initial one
+  boards: Vec<Chessboard>,
+  initial_player_color: Color,
+  // Moves in SAN
+  moves: Vec<String>,
+  ply: usize,
+}
+
+impl Replay {
+  pub fn new(game: &Game) -> Self {
+    let mut replay = Game::with_chessboard(
+      game.initial_chessboard().clone(),
+      game.initial_player_color(),
+    );
+    let mut boards = vec![replay.chessboard().clone()];
+    for chess_move in game.moves() {
+      replay.play_move(*chess_move).unwrap();
+      boards.push(replay.chessboard().clone());
+    }
+
+    Replay {
+      boards,
+      initial_player_color: game.initial_player_color(),
+      // Just replayed, so every move can be written
+      moves: game.san_moves().expect("Failed to write the moves"),
+      ply: 0,
+    }
+  }
+
+  // The `index`th game of a PGN file, counting from 1. Moves that can't be
+  // played here, like castling, end the game early with a warning.
+  pub fn open(
+    path: impl AsRef<Path>,
+    index: usize,
+  ) -> Result<(Self, Option<String>), String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+      .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let games = parse_pgn(&text)?;
+    let pgn_game = index
+      .checked_sub(1)
+      .and_then(|index| games.get(index))
+      .ok_or(format!("No game {} in {}", index, path.display()))?;
+
+    let game = pgn_game.replay(|_, _| true)?;
+    let played = game.moves().len();
+    let warning = pgn_game.moves.get(played).map(|san| {
+      format!(
+        "Stopped after {} plies: {} can't be played here",
+        played, san
+      )
+    });
+    Ok((Self::new(&game), warning))
+  }
+
+  pub fn ply(&self) -> usize {
+    self.ply
+  }
+
+  // Number of plies in the game
+  pub fn len(&self) -> usize {
+    self.moves.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.moves.is_empty()
+  }
+
+  pub fn chessboard(&self) -> &Chessboard {
+    &self.boards[self.ply]
+  }
+
+  pub fn player_color(&self) -> Color {
+    match self.ply % 2 {
+      0 => self.initial_player_color,
+      _ => self.initial_player_color.next(),
+    }
+  }
+
+  pub fn forward(&mut self) -> Result<(), String> {
+    if self.ply == self.len() {
+      return Err("Already at the last move".to_string());
+    }
+    self.ply += 1;
+    Ok(())
+  }
+
+  pub fn back(&mut self) -> Result<(), String> {
+    if self.ply == 0 {
+      return Err("Already at the start".to_string());
+    }
+    self.ply -= 1;
+    Ok(())
+  }
+
+  // Goes to the position after `ply` plies, 0 being the start
+  pub fn goto(&mut self, ply: usize) -> Result<(), String> {
+    if ply > self.len() {
+      return Err(format!("The game only has {} plies", self.len()));
+    }
+    self.ply = ply;
+    Ok(())
+  }
+
+  // Numbered moves with the one just played in brackets, e.g.
+  // "1. e4 e5 2. [Nf3] Nc6"
+  pub fn move_list(&self) -> String {
+    let first_ply = usize::from(self.initial_player_color == Color::Black);
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut moves_on_line = 0;
+    for (ply, san) in self.moves.iter().enumerate() {
+      let number = (first_ply + ply) / 2 + 1;
+      if (first_ply + ply).is_multiple_of(2) {
+        if moves_on_line == MOVES_PER_LINE {
+          lines.push(line.join(" "));
+          line.clear();
+          moves_on_line = 0;
+        }
+        line.push(format!("{}.", number));
+        moves_on_line += 1;
+      } else if ply == 0 {
+        line.push(format!("{}...", number));
+        moves_on_line += 1;
+      }
+
+      match ply + 1 == self.ply {
+        true => line.push(format!("[{}]", san)),
+        false => line.push(san.clone()),
+      }
+    }
+    if !line.is_empty() {
+      lines.push(line.join(" "));
+    }
+    lines.join("\n")
+  }
+}
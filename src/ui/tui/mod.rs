@@ -0,0 +1,353 @@
+pub mod terminal;
+pub mod view;
+
+use crate::chessboard::MoveResult;
+use crate::engine::search::SearchLimits;
+use crate::external_engine::ExternalEngine;
+use crate::game::Game;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use crate::presenters::style::RenderOptions;
+use crate::ui::GameUI;
+use crate::ui::tui::terminal::{Key, Terminal};
+use std::time::Duration;
+
+const EXTERNAL_ENGINE_MOVE_TIME: Duration = Duration::from_secs(1);
+
+// What the loop has to do after a key
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+  None,
+  Quit,
+  // A pawn reached the last row and can be upgraded
+  Upgrade(Position),
+}
+
+// Cursor and selection, kept apart from the terminal so keys can be tested
+#[derive(Clone, Debug)]
+pub struct State {
+  pub cursor: Position,
+  // Piece picked to move
+  pub selected: Option<Position>,
+  // Pawn waiting for the piece to upgrade to
+  pub upgrade: Option<Position>,
+  // Side played by the engine, whose moves are taken back with the player's
+  pub opponent: Option<Color>,
+  // Side at the bottom once the board has been flipped
+  pub flipped: Option<Color>,
+  pub message: String,
+}
+
+impl Default for State {
+  fn default() -> Self {
+    State {
+      cursor: Position::from_algebraic("e2").unwrap(),
+      selected: None,
+      upgrade: None,
+      opponent: None,
+      flipped: None,
+      message: String::new(),
+    }
+  }
+}
+
+impl State {
+  // Side at the bottom: the flipped one, otherwise the player's against an
+  // engine and the one to move in hot-seat games, like the move prompt
+  pub fn orientation(&self, game: &Game) -> Color {
+    self.flipped.unwrap_or(match self.opponent {
+      Some(opponent) => opponent.next(),
+      None => game.player_color(),
+    })
+  }
+
+  // Squares the selected piece can go to
+  pub fn targets(&self, game: &Game) -> Vec<Position> {
+    let Some(selected) = self.selected else {
+      return Vec::new();
+    };
+    let mut targets: Vec<Position> = game
+      .legal_moves()
+      .into_iter()
+      .filter(|chess_move| chess_move.from() == selected)
+      .map(|chess_move| chess_move.to())
+      .collect();
+    // Upgrades to different pieces share their square
+    targets.dedup();
+    targets
+  }
+
+  pub fn handle_key(&mut self, game: &mut Game, key: Key) -> Action {
+    self.message.clear();
+    if let Some(position) = self.upgrade {
+      return self.choose_upgrade(game, position, key);
+    }
+
+    // Arrows go the way they point on the board as drawn
+    let step = match self.orientation(game) {
+      Color::White => 1,
+      Color::Black => -1,
+    };
+    match key {
+      Key::Up => self.move_cursor(step, 0),
+      Key::Down => self.move_cursor(-step, 0),
+      Key::Left => self.move_cursor(0, -step),
+      Key::Right => self.move_cursor(0, step),
+      Key::Enter => return self.select(game),
+      Key::Escape => self.selected = None,
+      Key::Char('u') => {
+        self.selected = None;
+        if let Err(e) = game.undo() {
+          self.message = e;
+        } else if self.opponent == Some(game.player_color()) {
+          // Back to the player's move, or the engine would play again
+          let _ = game.undo();
+        }
+      }
+      // Stays this way until flipped again
+      Key::Char('f') => self.flipped = Some(self.orientation(game).next()),
+      Key::Char('q') => return Action::Quit,
+      Key::Char(_) => (),
+    }
+    Action::None
+  }
+
+  // Stops at the edges of the board
+  fn move_cursor(&mut self, rows: isize, files: isize) {
+    let last = BOARD_SIZE as isize - 1;
+    let x = (self.cursor.x() as isize + rows).clamp(0, last);
+    let y = (self.cursor.y() as isize + files).clamp(0, last);
+    self.cursor = Position::new(x as usize, y as usize).unwrap();
+  }
+
+  // Picks the piece under the cursor, or plays the selected piece there
+  fn select(&mut self, game: &mut Game) -> Action {
+    if game.result().is_some() {
+      self.message = "The game is over".to_string();
+      return Action::None;
+    }
+
+    let cursor = self.cursor;
+    if let Some(selected) = self.selected
+      && self.targets(game).contains(&cursor)
+    {
+      self.selected = None;
+      match game.play(selected, cursor) {
+        // With no dead piece to bring back, the pawn stays a pawn
+        Ok(MoveResult::CanUpgradePiece)
+          if !game
+            .chessboard()
+            .dead_pieces(game.player_color().next())
+            .is_empty() =>
+        {
+          self.upgrade = Some(cursor);
+          return Action::Upgrade(cursor);
+        }
+        Ok(MoveResult::CanUpgradePiece) => (),
+        Ok(MoveResult::CheckKing) => self.message = "Check!".to_string(),
+        Ok(MoveResult::None) => (),
+        Err(e) => self.message = e,
+      }
+      return Action::None;
+    }
+
+    match game.chessboard().get_piece(cursor) {
+      Some(piece) if piece.is_of_color(game.player_color()) => {
+        self.selected = Some(cursor);
+      }
+      _ if self.selected.is_some() => {
+        self.selected = None;
+        self.message = "Not a legal move".to_string();
+      }
+      _ => self.message = format!("No {} piece there", game.player_color()),
+    }
+    Action::None
+  }
+
+  // The letter of a dead piece brings the first one of that kind back, and
+  // Enter the strongest one. Escape takes the pawn's move back and `q`
+  // still quits, so a queen is picked with Enter or `Q`.
+  fn choose_upgrade(
+    &mut self,
+    game: &mut Game,
+    position: Position,
+    key: Key,
+  ) -> Action {
+    let dead_pieces = game.chessboard().dead_pieces(game.player_color().next());
+    let index = match key {
+      Key::Char('q') => return Action::Quit,
+      Key::Escape => {
+        self.upgrade = None;
+        if let Err(e) = game.undo() {
+          self.message = e;
+        }
+        return Action::None;
+      }
+      Key::Enter => {
+        (0..dead_pieces.len()).max_by_key(|&index| dead_pieces[index].value())
+      }
+      Key::Char(letter) => {
+        let index = dead_pieces
+          .iter()
+          .position(|piece| piece.symbol().eq_ignore_ascii_case(&letter));
+        if index.is_none() {
+          self.message = format!("No dead piece for {}", letter);
+        }
+        index
+      }
+      _ => None,
+    };
+    if let Some(index) = index {
+      match game.upgrade_piece(index, position) {
+        Ok(_) => self.upgrade = None,
+        Err(e) => self.message = e,
+      }
+    }
+    Action::None
+  }
+}
+
+// Full-screen interface: the board is drawn in place and played with the
+// arrow keys, next to the moves, captured pieces and clocks
+pub struct TerminalUI {
+  terminal: Option<Terminal>,
+  state: State,
+  // Plays Black when set
+  external_engine: Option<ExternalEngine>,
+  // The glyphs, and whether squares are colored
+  render_options: RenderOptions,
+}
+
+impl Default for TerminalUI {
+  fn default() -> Self {
+    TerminalUI {
+      terminal: None,
+      state: State::default(),
+      external_engine: None,
+      render_options: RenderOptions {
+        color: true,
+        ..RenderOptions::default()
+      },
+    }
+  }
+}
+
+impl TerminalUI {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_external_engine(external_engine: ExternalEngine) -> Self {
+    TerminalUI {
+      external_engine: Some(external_engine),
+      state: State {
+        opponent: Some(Color::Black),
+        ..State::default()
+      },
+      ..Self::default()
+    }
+  }
+
+  pub fn set_render_options(&mut self, render_options: RenderOptions) {
+    self.render_options = render_options;
+  }
+
+  fn draw(&mut self, game: &Game) {
+    let lines = view::frame(game, &self.state, &self.render_options);
+    if let Some(terminal) = self.terminal.as_mut() {
+      terminal.draw(&lines).expect("Failed to draw");
+    }
+  }
+
+  fn read_keys(&mut self) -> Vec<Key> {
+    match self.terminal.as_mut() {
+      Some(terminal) => terminal.read_keys().expect("Failed to read keys"),
+      None => Vec::new(),
+    }
+  }
+
+  // The engine's reply once the player's move is complete. If it fails, the
+  // player goes on with both sides.
+  fn play_external_engine_move(&mut self, game: &mut Game) {
+    if self.external_engine.is_none()
+      || self.state.opponent != Some(game.player_color())
+      || game.result().is_some()
+      || game.pending_upgrade().is_some()
+    {
+      return;
+    }
+    self.state.message = "The engine is thinking...".to_string();
+    self.draw(game);
+
+    let limits = SearchLimits {
+      movetime: Some(EXTERNAL_ENGINE_MOVE_TIME),
+      ..Default::default()
+    };
+    let Some(external_engine) = self.external_engine.as_mut() else {
+      return;
+    };
+    let best_move = external_engine
+      .search(game, &limits, &mut |_| {})
+      .and_then(|result| result.best_move.ok_or("No move found".to_string()));
+    match best_move.and_then(|chess_move| game.play_move(chess_move)) {
+      Ok(_) => self.state.message.clear(),
+      Err(e) => {
+        self.external_engine = None;
+        self.state.opponent = None;
+        self.state.message = format!("Engine error: {}", e);
+      }
+    }
+  }
+}
+
+impl GameUI for TerminalUI {
+  fn start_game_loop(&mut self, game: &mut Game) {
+    match Terminal::enter() {
+      Ok(terminal) => self.terminal = Some(terminal),
+      Err(e) => {
+        eprintln!("{}", e);
+        return;
+      }
+    }
+
+    loop {
+      self.play_external_engine_move(game);
+      self.draw(game);
+      for key in self.read_keys() {
+        match self.state.handle_key(game, key) {
+          Action::None => (),
+          Action::Quit => {
+            // Restores the terminal
+            self.terminal = None;
+            return;
+          }
+          Action::Upgrade(position) => {
+            self.handle_upgrade_piece(game, position);
+            if self.terminal.is_none() {
+              return;
+            }
+          }
+        }
+      }
+    }
+  }
+
+  fn handle_upgrade_piece(
+    &mut self,
+    game: &mut Game,
+    upgrade_position: Position,
+  ) {
+    self.state.upgrade = Some(upgrade_position);
+    while self.state.upgrade.is_some() {
+      self.draw(game);
+      for key in self.read_keys() {
+        if self.state.handle_key(game, key) == Action::Quit {
+          // Restores the terminal, which ends the game loop too
+          self.terminal = None;
+          return;
+        }
+      }
+    }
+  }
+}
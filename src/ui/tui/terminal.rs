@@ -0,0 +1,106 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key {
+  Up,
+  Down,
+  Left,
+  Right,
+  Enter,
+  Escape,
+  Char(char),
+}
+
+// Keys in what a read returned. Arrows come as escape sequences, either
+// "ESC [ A" or "ESC O A" depending on the terminal's mode.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+  let mut keys = Vec::new();
+  let mut bytes = bytes.iter().copied().peekable();
+  while let Some(byte) = bytes.next() {
+    let key = match byte {
+      0x1b => match bytes.peek() {
+        Some(b'[' | b'O') => {
+          bytes.next();
+          match bytes.next() {
+            Some(b'A') => Key::Up,
+            Some(b'B') => Key::Down,
+            Some(b'C') => Key::Right,
+            Some(b'D') => Key::Left,
+            _ => continue,
+          }
+        }
+        _ => Key::Escape,
+      },
+      b'\r' | b'\n' | b' ' => Key::Enter,
+      byte if byte.is_ascii_graphic() => Key::Char(byte as char),
+      _ => continue,
+    };
+    keys.push(key);
+  }
+  keys
+}
+
+// The terminal in raw mode on the alternate screen, set with `stty` so no
+// terminal library is needed. Dropping it puts everything back.
+pub struct Terminal {
+  saved_settings: String,
+}
+
+impl Terminal {
+  pub fn enter() -> Result<Self, String> {
+    let saved_settings = stty(&["-g"])?;
+    // Reads give up after half a second, so that clocks keep ticking
+    stty(&["raw", "-echo", "min", "0", "time", "5"])?;
+    let terminal = Terminal {
+      saved_settings: saved_settings.trim().to_string(),
+    };
+
+    // Alternate screen, cursor hidden
+    print!("\x1b[?1049h\x1b[?25l");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    Ok(terminal)
+  }
+
+  // Draws over the previous frame line by line, which doesn't flicker
+  // like clearing the screen first does
+  pub fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "\x1b[H")?;
+    for line in lines {
+      write!(stdout, "{}\x1b[K\r\n", line)?;
+    }
+    write!(stdout, "\x1b[J")?;
+    stdout.flush()
+  }
+
+  // Keys pressed since the last read, or none after half a second
+  pub fn read_keys(&mut self) -> io::Result<Vec<Key>> {
+    let mut buffer = [0; 64];
+    let read = io::stdin().read(&mut buffer)?;
+    Ok(parse_keys(&buffer[..read]))
+  }
+}
+
+impl Drop for Terminal {
+  fn drop(&mut self) {
+    print!("\x1b[?25h\x1b[?1049l");
+    let _ = io::stdout().flush();
+    let _ = stty(&[self.saved_settings.as_str()]);
+  }
+}
+
+fn stty(args: &[&str]) -> Result<String, String> {
+  let output = Command::new("stty")
+    .args(args)
+    .stdin(Stdio::inherit())
+    .output()
+    .map_err(|e| format!("Failed to run stty: {}", e))?;
+  if !output.status.success() {
+    return Err(format!(
+      "stty failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    ));
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
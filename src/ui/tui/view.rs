@@ -0,0 +1,221 @@
+use crate::clock::format_duration;
+use crate::game::{Game, GameResult};
+use crate::move_generator::MoveGenerator;
+use crate::pieces::piece::Piece;
+use crate::pieces::types::BOARD_SIZE;
+use crate::pieces::types::color::Color;
+use crate::pieces::types::position::Position;
+use crate::presenters::cmd::{board_order, glyph};
+use crate::presenters::style::{
+  BLACK_PIECE, DARK_SQUARE, LIGHT_SQUARE, RenderOptions, WHITE_PIECE,
+};
+use crate::ui::tui::State;
+
+// Highlights from the 256 color palette
+const LAST_MOVE: u8 = 143;
+const SELECTED: u8 = 74;
+const TARGET: u8 = 108;
+const CHECK: u8 = 167;
+
+// Full moves shown in the move list, the latest ones
+const MOVE_LIST_LINES: usize = 12;
+
+const HELP: &str =
+  "Arrows: move  Enter: select/play  Esc: cancel  f: flip  u: undo  q: quit";
+const UPGRADE_HELP: &str =
+  "Letter: upgrade  Enter: strongest  Esc: take back  q: quit";
+
+// The screen: the board with the side panel next to it, then the message
+// and the keys to use
+pub fn frame(
+  game: &Game,
+  state: &State,
+  options: &RenderOptions,
+) -> Vec<String> {
+  let board = board_lines(game, state, options);
+  let panel = panel_lines(game, state);
+
+  let mut lines: Vec<String> = (0..board.len().max(panel.len()))
+    .map(|index| {
+      let board_line = board
+        .get(index)
+        .map_or(" ".repeat(BOARD_SIZE * 3 + 4), |line| line.clone());
+      let panel_line = panel.get(index).map_or("", |line| line.as_str());
+      format!("{}   {}", board_line, panel_line)
+    })
+    .collect();
+  lines.push(String::new());
+  lines.push(state.message.clone());
+  lines.push(match state.upgrade {
+    Some(_) => UPGRADE_HELP.to_string(),
+    None => HELP.to_string(),
+  });
+  lines
+}
+
+// The side from `State::orientation` at the bottom, each square three
+// columns wide with the cursor in brackets. Without colors, the selected piece and where it can go are in
+// parentheses instead.
+fn board_lines(
+  game: &Game,
+  state: &State,
+  options: &RenderOptions,
+) -> Vec<String> {
+  let chessboard = game.chessboard();
+  let targets = state.targets(game);
+  let last_move = game.moves().last();
+  let player_color = game.player_color();
+  let checked_king = MoveGenerator::new(chessboard)
+    .is_king_in_check(player_color)
+    .then(|| chessboard.get_king_position(player_color))
+    .flatten();
+
+  let (rows, columns) = board_order(state.orientation(game));
+  let files: String = columns
+    .iter()
+    .map(|&y| format!(" {} ", (b'a' + y as u8) as char))
+    .collect();
+  let mut lines = vec![format!("  {}", files)];
+  for x in rows {
+    let mut line = format!("{} ", x + 1);
+    for &y in &columns {
+      let position = Position::new(x, y).unwrap();
+      let background = if checked_king == Some(position) {
+        CHECK
+      } else if state.selected == Some(position) {
+        SELECTED
+      } else if targets.contains(&position) {
+        TARGET
+      } else if last_move.is_some_and(|chess_move| {
+        chess_move.from() == position || chess_move.to() == position
+      }) {
+        LAST_MOVE
+      } else if (x + y) % 2 == 1 {
+        LIGHT_SQUARE
+      } else {
+        DARK_SQUARE
+      };
+
+      let (symbol, foreground) = match chessboard.get_piece(position) {
+        Some(piece) => (glyph(piece, options.glyphs), piece_color(piece)),
+        None if options.color => (' ', BLACK_PIECE),
+        None => ('.', BLACK_PIECE),
+      };
+      let marked =
+        state.selected == Some(position) || targets.contains(&position);
+      let (left, right) = if state.cursor == position {
+        ('[', ']')
+      } else if marked && !options.color {
+        ('(', ')')
+      } else {
+        (' ', ' ')
+      };
+      if !options.color {
+        line.push_str(&format!("{}{}{}", left, symbol, right));
+        continue;
+      }
+      line.push_str(&format!(
+        "\x1b[1;38;5;{};48;5;{}m{}{}{}\x1b[0m",
+        foreground, background, left, symbol, right
+      ));
+    }
+    line.push_str(&format!(" {}", x + 1));
+    lines.push(line);
+  }
+  lines.push(format!("  {}", files));
+  lines
+}
+
+fn piece_color(piece: &Piece) -> u8 {
+  match piece.color() {
+    Color::White => WHITE_PIECE,
+    Color::Black => BLACK_PIECE,
+  }
+}
+
+// Whose turn it is or how the game ended, the clocks, the moves and the
+// captured pieces
+fn panel_lines(game: &Game, state: &State) -> Vec<String> {
+  let mut lines = vec![match game.result() {
+    Some(result) => describe_result(result),
+    None => format!("{} to move", game.player_color()),
+  }];
+  if let Some(clock) = game.clock() {
+    lines.push(format!(
+      "White {}  Black {}",
+      format_duration(clock.remaining(Color::White)),
+      format_duration(clock.remaining(Color::Black))
+    ));
+  }
+  if let Some(position) = state.upgrade {
+    // Each kind once, in the order the pieces died
+    let mut choices: Vec<String> = Vec::new();
+    for piece in game.chessboard().dead_pieces(game.player_color().next()) {
+      let letter = piece.symbol().to_ascii_uppercase().to_string();
+      if !choices.contains(&letter) {
+        choices.push(letter);
+      }
+    }
+    lines.push(format!(
+      "Upgrade the pawn on {}: {}",
+      position.to_algebraic(),
+      choices.join(" ")
+    ));
+  }
+
+  lines.push(String::new());
+  lines.push("Moves".to_string());
+  let move_lines = move_list(game);
+  let skipped = move_lines.len().saturating_sub(MOVE_LIST_LINES);
+  lines.extend(move_lines.into_iter().skip(skipped));
+
+  lines.push(String::new());
+  let chessboard = game.chessboard();
+  for color in [Color::White, Color::Black] {
+    let captured: String = chessboard
+      .dead_pieces(color.next())
+      .iter()
+      .map(|piece| piece.symbol())
+      .collect();
+    lines.push(format!("Captured by {}: {}", color, captured));
+  }
+  lines
+}
+
+// One full move per line
+fn move_list(game: &Game) -> Vec<String> {
+  let mut sans = match game.san_moves() {
+    Ok(sans) => sans.into_iter(),
+    Err(e) => return vec![e],
+  };
+  let mut number = 1;
+  let mut lines = Vec::new();
+  if game.initial_player_color() == Color::Black
+    && let Some(san) = sans.next()
+  {
+    lines.push(format!("{:3}. ...   {}", number, san));
+    number += 1;
+  }
+  while let Some(white) = sans.next() {
+    let line = match sans.next() {
+      Some(black) => format!("{:3}. {:7} {}", number, white, black),
+      None => format!("{:3}. {}", number, white),
+    };
+    lines.push(line);
+    number += 1;
+  }
+  lines
+}
+
+fn describe_result(result: GameResult) -> String {
+  match result {
+    GameResult::Checkmate { winner } => format!("Checkmate, {} wins", winner),
+    GameResult::Stalemate => "Stalemate, draw".to_string(),
+    GameResult::Timeout { winner } => {
+      format!("{} ran out of time, {} wins", winner.next(), winner)
+    }
+    GameResult::TimeoutDraw => {
+      "Time out with no mating material, draw".to_string()
+    }
+  }
+}